@@ -0,0 +1,245 @@
+//! Compute units used by each instruction, for groups of 2, 6 and 11 voters
+//! where the cost depends on the group size, and for the 20 contributors
+//! `settle_event` takes at most. Results are written to
+//! `benches/compute_units.md` with the delta against the previous run.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use payra::{EventArgs, SpendingShare, MAX_CONTRIBUTORS, MAX_PARTICIPANTS};
+use payra_bench::{
+    empty, even_split, event_counter, mollusk, programs, wallet, Group, CONTRIBUTION, DEADLINE,
+};
+use payra_client::{instructions, pda};
+use solana_account::Account;
+
+// whitelisted members besides the creator, up to the event maximum
+const GROUP_SIZES: [usize; 3] = [1, 5, 10];
+
+type Case = (
+    String,
+    anchor_lang::solana_program::instruction::Instruction,
+    Vec<(Pubkey, Account)>,
+);
+
+fn create_event() -> Case {
+    let group = Group::new(0);
+    let ix = instructions::create_event(
+        group.creator,
+        0,
+        group.mint,
+        group.withdraw_account,
+        EventArgs {
+            name: "bench".to_string(),
+            deadline: DEADLINE,
+            target_amount: CONTRIBUTION,
+        },
+    );
+    let mut accounts = vec![
+        (group.creator, wallet()),
+        event_counter(0),
+        (group.event_key(), empty()),
+        group.mint_account(),
+        (pda::event_vault(&group.event_key(), &group.mint), empty()),
+        group.withdraw_account(),
+    ];
+    accounts.extend(programs());
+    ("create_event".to_string(), ix, accounts)
+}
+
+fn whitelist(members: usize) -> Case {
+    let group = Group::new(0);
+    let wallets = (0..members).map(|_| Pubkey::new_unique()).collect();
+    let ix = instructions::whitelist(group.creator, 0, wallets);
+    let accounts = vec![(group.creator, wallet()), group.event_account()];
+    (format!("whitelist/{members} members"), ix, accounts)
+}
+
+// first contribution of a member, creating their participant account
+fn contribute() -> Case {
+    let group = Group::new(1);
+    let contributor = group.event.whitelist[0];
+    let ix = instructions::contribute(contributor, 0, group.mint, CONTRIBUTION);
+    let mut accounts = vec![
+        (contributor, wallet()),
+        group.mint_account(),
+        (
+            get_associated_token_address(&contributor, &group.mint),
+            payra_bench::token_account(&group.mint, &contributor, CONTRIBUTION),
+        ),
+        group.event_account(),
+        group.vault_account(),
+        (pda::participant(0, &contributor).0, empty()),
+    ];
+    accounts.extend(programs());
+    ("contribute".to_string(), ix, accounts)
+}
+
+// spending proposal shared by every voter
+fn create_proposal(members: usize) -> Case {
+    let group = Group::new(members);
+    let voters = group.voters();
+    let spendings = voters
+        .iter()
+        .zip(even_split(voters.len()))
+        .map(|(wallet, percentage)| SpendingShare {
+            wallet: *wallet,
+            percentage,
+        })
+        .collect();
+    let ix = instructions::create_proposal(
+        group.creator,
+        &group.event,
+        "bench".to_string(),
+        CONTRIBUTION,
+        spendings,
+        DEADLINE,
+    );
+    let mut accounts = vec![
+        (group.creator, wallet()),
+        group.event_account(),
+        group.mint_account(),
+        (pda::proposal(&group.event_key(), 0).0, empty()),
+    ];
+    accounts.extend(programs());
+    (
+        format!("create_proposal/{} voters", voters.len()),
+        ix,
+        accounts,
+    )
+}
+
+// last vote of a spending proposal
+fn vote(members: usize) -> Case {
+    let mut group = Group::new(members);
+    group.event.proposal_count = 1;
+    let voters = group.voters();
+    let (last, others) = voters.split_last().expect("creator votes");
+    let proposal = group.spending_proposal(0, others.to_vec());
+    let ix = instructions::vote(*last, 0, 0, group.mint, true);
+    let mut accounts = vec![
+        (*last, wallet()),
+        group.event_account(),
+        group.mint_account(),
+        group.proposal_account(&proposal),
+    ];
+    accounts.extend(programs());
+    (format!("vote/{} voters", voters.len()), ix, accounts)
+}
+
+// approved spending proposal charged to every voter
+fn settle_proposal(members: usize) -> Case {
+    let mut group = Group::new(members);
+    group.event.proposal_count = 1;
+    let proposal = group.spending_proposal(0, group.voters());
+    let ix = instructions::settle_proposal(group.creator, &group.event, &proposal);
+    let mut accounts = vec![
+        (group.creator, wallet()),
+        group.event_account(),
+        group.mint_account(),
+        group.vault_account(),
+        group.proposal_account(&proposal),
+        group.withdraw_account(),
+    ];
+    accounts.extend(programs());
+    accounts.extend(group.participant_accounts());
+    (
+        format!("settle_proposal/{} voters", group.voters().len()),
+        ix,
+        accounts,
+    )
+}
+
+fn create_settle_proposal() -> Case {
+    let group = Group::new(1);
+    let ix = instructions::create_settle_proposal(group.creator, &group.event, DEADLINE);
+    let mut accounts = vec![
+        (group.creator, wallet()),
+        group.event_account(),
+        group.mint_account(),
+        (pda::proposal(&group.event_key(), 0).0, empty()),
+    ];
+    accounts.extend(programs());
+    ("create_settle_proposal".to_string(), ix, accounts)
+}
+
+// approved settlement recording net_owed of every contributor
+fn settle_event(mut group: Group) -> Case {
+    group.event.proposal_count = 1;
+    let proposal = group.settlement_proposal(0, group.voters());
+    let ix = instructions::settle_event(group.creator, &group.event, 0);
+    let mut accounts = vec![
+        (group.creator, wallet()),
+        group.event_account(),
+        group.proposal_account(&proposal),
+    ];
+    accounts.extend(group.participant_accounts());
+    (
+        format!(
+            "settle_event/{} contributors",
+            group.event.contributors.len()
+        ),
+        ix,
+        accounts,
+    )
+}
+
+// open event with a full whitelist where donors without a vote make up the
+// rest of the contributors
+fn settle_event_of_most_contributors() -> Case {
+    let members = MAX_PARTICIPANTS as usize;
+    let donors = MAX_CONTRIBUTORS as usize - members - 1;
+    settle_event(Group::with_donors(members, donors))
+}
+
+// summary of an event with one open proposal per voter
+fn get_event_summary(members: usize) -> Case {
+    let mut group = Group::new(members);
+    let count = group.voters().len() as u16;
+    group.event.proposal_count = count;
+    let ids: Vec<u16> = (0..count).collect();
+    let ix = instructions::get_event_summary(0, &ids);
+    let mut accounts = vec![group.event_account()];
+    for id in ids {
+        accounts.push(group.proposal_account(&group.spending_proposal(id, Vec::new())));
+    }
+    (format!("get_event_summary/{count} proposals"), ix, accounts)
+}
+
+fn preview_settlement(members: usize) -> Case {
+    let group = Group::new(members);
+    let ix = instructions::preview_settlement(&group.event);
+    let mut accounts = vec![group.event_account()];
+    accounts.extend(group.participant_accounts());
+    (
+        format!("preview_settlement/{} contributors", group.event.contributors.len()),
+        ix,
+        accounts,
+    )
+}
+
+fn main() {
+    let mut cases = vec![
+        create_event(),
+        contribute(),
+        create_settle_proposal(),
+        settle_event_of_most_contributors(),
+    ];
+    for members in GROUP_SIZES {
+        cases.extend([
+            whitelist(members),
+            create_proposal(members),
+            vote(members),
+            settle_proposal(members),
+            settle_event(Group::new(members)),
+            get_event_summary(members),
+            preview_settlement(members),
+        ]);
+    }
+
+    let mut bencher = MolluskComputeUnitBencher::new(mollusk()).must_pass(true);
+    for (name, ix, accounts) in &cases {
+        bencher = bencher.bench((name, ix, accounts));
+    }
+    bencher.execute();
+}
@@ -0,0 +1,314 @@
+//! Fixtures for the compute unit benchmarks: account states of an event with
+//! a given number of members, built directly instead of replaying the
+//! instructions that would create them.
+//!
+//! The program is loaded from `target/deploy/payra.so`, run `anchor build`
+//! before the benchmarks.
+
+use anchor_lang::{
+    prelude::{Pubkey, Rent},
+    solana_program::program_pack::Pack,
+    AccountSerialize, Space,
+};
+use anchor_spl::token::spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use mollusk_svm::Mollusk;
+use mollusk_svm_programs_token::{associated_token, token};
+use payra::{
+    ContributionRules, Event, EventAccess, EventCounter, MemberRole, Participant, Proposal,
+    ProposalType, SpendingShare, EVENT_VERSION, PARTICIPANT_VERSION, PROPOSAL_VERSION,
+};
+use payra_client::pda;
+use solana_account::Account;
+
+// clock of every benchmark, deadlines are set relative to it
+pub const NOW: i64 = 1_700_000_000;
+
+pub const DEADLINE: i64 = NOW + 86_400;
+
+const EVENT_ID: u64 = 0;
+
+// contribution of each voter in the fixtures
+pub const CONTRIBUTION: u64 = 1_000;
+
+const LAMPORTS: u64 = 10_000_000_000;
+
+pub fn mollusk() -> Mollusk {
+    let mut mollusk = Mollusk::new(&payra::ID, "payra");
+    token::add_program(&mut mollusk);
+    associated_token::add_program(&mut mollusk);
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+    mollusk
+}
+
+// programs every instruction with token accounts lists
+pub fn programs() -> Vec<(Pubkey, Account)> {
+    vec![
+        mollusk_svm::program::keyed_account_for_system_program(),
+        token::keyed_account(),
+        associated_token::keyed_account(),
+    ]
+}
+
+pub fn wallet() -> Account {
+    Account::new(LAMPORTS, 0, &anchor_lang::system_program::ID)
+}
+
+// address not created yet, for accounts the instruction initializes
+pub fn empty() -> Account {
+    Account::default()
+}
+
+fn rent_exempt(owner: &Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+// anchor account with its discriminator, padded to the allocated space
+fn program_account<T: AccountSerialize>(state: &T, space: usize) -> Account {
+    let mut data = Vec::with_capacity(8 + space);
+    state
+        .try_serialize(&mut data)
+        .expect("serializable account");
+    data.resize(8 + space, 0);
+    rent_exempt(&payra::ID, data)
+}
+
+pub fn event_counter(count: u64) -> (Pubkey, Account) {
+    let (address, bump) = pda::event_counter();
+    let counter = EventCounter { count, bump };
+    (address, program_account(&counter, EventCounter::INIT_SPACE))
+}
+
+pub fn mint_account() -> Account {
+    let mint = Mint {
+        decimals: 6,
+        is_initialized: true,
+        supply: u64::MAX / 2,
+        ..Mint::default()
+    };
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(mint, &mut data).expect("mint state");
+    rent_exempt(&token::ID, data)
+}
+
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let account = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    };
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(account, &mut data).expect("token account state");
+    rent_exempt(&token::ID, data)
+}
+
+// An event with `members` whitelisted wallets besides the creator, where
+// every voter contributed `CONTRIBUTION`, and only them unless donors are
+// added.
+pub struct Group {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub withdraw_account: Pubkey,
+    pub event: Event,
+}
+
+impl Group {
+    pub fn new(members: usize) -> Self {
+        let creator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let whitelist: Vec<Pubkey> = (0..members).map(|_| Pubkey::new_unique()).collect();
+        let voters = members as u64 + 1;
+        let mut event = Event {
+            version: EVENT_VERSION,
+            event_id: EVENT_ID,
+            creator,
+            pending_creator: None,
+            mint,
+            withdraw_token_account: Pubkey::new_unique(),
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
+            target_amount: CONTRIBUTION * voters,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
+            total_contributed: CONTRIBUTION * voters,
+            total_spent: 0,
+            roles: vec![MemberRole::Member; members],
+            whitelist,
+            contributors: Vec::new(),
+            is_cancelled: false,
+            is_finalized: false,
+            deadline: DEADLINE,
+            proposal_count: 0,
+            name: "bench".to_string(),
+            bump: pda::event(EVENT_ID).1,
+        };
+        event.contributors = event.voters();
+        Self {
+            creator,
+            mint,
+            withdraw_account: event.withdraw_token_account,
+            event,
+        }
+    }
+
+    // open event where `donors` wallets below the voting threshold
+    // contributed `CONTRIBUTION` next to the voters
+    pub fn with_donors(members: usize, donors: usize) -> Self {
+        let mut group = Self::new(members);
+        let event = &mut group.event;
+        event.access = EventAccess::Open {
+            voting_threshold: CONTRIBUTION + 1,
+        };
+        event
+            .contributors
+            .extend((0..donors).map(|_| Pubkey::new_unique()));
+        event.total_contributed += CONTRIBUTION * donors as u64;
+        event.target_amount = event.total_contributed;
+        group
+    }
+
+    pub fn event_key(&self) -> Pubkey {
+        pda::event(EVENT_ID).0
+    }
+
+    pub fn voters(&self) -> Vec<Pubkey> {
+        self.event.voters()
+    }
+
+    pub fn event_account(&self) -> (Pubkey, Account) {
+        (
+            self.event_key(),
+            program_account(&self.event, Event::INIT_SPACE),
+        )
+    }
+
+    pub fn mint_account(&self) -> (Pubkey, Account) {
+        (self.mint, mint_account())
+    }
+
+    pub fn vault_account(&self) -> (Pubkey, Account) {
+        let event = self.event_key();
+        let available = self.event.total_contributed - self.event.total_spent;
+        (
+            pda::event_vault(&event, &self.mint),
+            token_account(&self.mint, &event, available),
+        )
+    }
+
+    pub fn withdraw_account(&self) -> (Pubkey, Account) {
+        (
+            self.withdraw_account,
+            token_account(&self.mint, &self.creator, 0),
+        )
+    }
+
+    pub fn participant_account(&self, wallet: &Pubkey) -> (Pubkey, Account) {
+        let (address, bump) = pda::participant(EVENT_ID, wallet);
+        let participant = Participant {
+            version: PARTICIPANT_VERSION,
+            event: self.event_key(),
+            event_id: EVENT_ID,
+            wallet: *wallet,
+            contributed: CONTRIBUTION,
+            spent: 0,
+            refunded: false,
+            bump,
+            net_owed: 0,
+            periods_paid: 0,
+        };
+        (
+            address,
+            program_account(&participant, Participant::INIT_SPACE),
+        )
+    }
+
+    // one per contributor, the voters first
+    pub fn participant_accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.event
+            .contributors
+            .iter()
+            .map(|wallet| self.participant_account(wallet))
+            .collect()
+    }
+
+    // spending proposal shared evenly by every voter, the first shares take
+    // the rounding remainder
+    pub fn spending_proposal(&self, proposal_id: u16, yes_votes: Vec<Pubkey>) -> Proposal {
+        let voters = self.voters();
+        let percentages = even_split(voters.len());
+        let spendings = voters
+            .into_iter()
+            .zip(percentages)
+            .map(|(wallet, percentage)| SpendingShare { wallet, percentage })
+            .collect();
+        self.proposal(
+            proposal_id,
+            ProposalType::Spending,
+            CONTRIBUTION,
+            spendings,
+            yes_votes,
+        )
+    }
+
+    pub fn settlement_proposal(&self, proposal_id: u16, yes_votes: Vec<Pubkey>) -> Proposal {
+        self.proposal(
+            proposal_id,
+            ProposalType::EventSettlement,
+            0,
+            Vec::new(),
+            yes_votes,
+        )
+    }
+
+    fn proposal(
+        &self,
+        proposal_id: u16,
+        proposal_type: ProposalType,
+        amount: u64,
+        spendings: Vec<SpendingShare>,
+        yes_votes: Vec<Pubkey>,
+    ) -> Proposal {
+        Proposal {
+            version: PROPOSAL_VERSION,
+            proposal_id,
+            title: "bench".to_string(),
+            proposal_type,
+            amount,
+            spendings,
+            milestones: Vec::new(),
+            yes_votes,
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: self.creator,
+            deadline: DEADLINE,
+            settled: false,
+            cancelled: false,
+            fractional_vault: None,
+            bump: pda::proposal(&self.event_key(), proposal_id).1,
+        }
+    }
+
+    pub fn proposal_account(&self, proposal: &Proposal) -> (Pubkey, Account) {
+        (
+            pda::proposal(&self.event_key(), proposal.proposal_id).0,
+            program_account(proposal, Proposal::INIT_SPACE),
+        )
+    }
+}
+
+// percentages adding up to 100 over `shares` wallets
+pub fn even_split(shares: usize) -> Vec<u8> {
+    let base = (100 / shares) as u8;
+    let remainder = 100 % shares;
+    (0..shares)
+        .map(|i| base + u8::from(i < remainder))
+        .collect()
+}
@@ -0,0 +1,119 @@
+use std::{path::PathBuf, str::FromStr};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anyhow::{anyhow, Context as _, Result};
+use serde::Deserialize;
+use solana_commitment_config::CommitmentConfig;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::GlobalArgs;
+
+// the fields of ~/.config/solana/cli/config.yml the tool uses
+#[derive(Deserialize)]
+#[serde(default)]
+struct Config {
+    json_rpc_url: String,
+    keypair_path: String,
+    commitment: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Self {
+            json_rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            keypair_path: format!("{home}/.config/solana/id.json"),
+            commitment: "confirmed".to_string(),
+        }
+    }
+}
+
+impl Config {
+    fn default_path() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME")?;
+        Some(PathBuf::from(home).join(".config/solana/cli/config.yml"))
+    }
+
+    // an explicit path must exist, the default one falls back to defaults
+    fn load(path: Option<&str>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (PathBuf::from(path), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_yaml::from_str(&contents)
+                .with_context(|| format!("invalid config {}", path.display())),
+            Err(_) if !required => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|err| anyhow!("failed to read keypair {path}: {err}"))
+}
+
+// rpc connection and signer resolved from the Solana CLI config, with the
+// command line flags taking precedence
+pub struct Context {
+    pub rpc: RpcClient,
+    pub signer: Keypair,
+    // sponsor covering fees and rent, the signer otherwise
+    pub fee_payer: Option<Keypair>,
+}
+
+impl Context {
+    pub fn load(args: &GlobalArgs) -> Result<Self> {
+        let config = Config::load(args.config.as_deref())?;
+
+        let url = args.url.clone().unwrap_or(config.json_rpc_url);
+        let keypair_path = args.keypair.clone().unwrap_or(config.keypair_path);
+        let commitment =
+            CommitmentConfig::from_str(&config.commitment).unwrap_or(CommitmentConfig::confirmed());
+
+        let signer = read_keypair(&keypair_path)?;
+        let fee_payer = args.fee_payer.as_deref().map(read_keypair).transpose()?;
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url, commitment),
+            signer,
+            fee_payer,
+        })
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.fee_payer.as_ref().unwrap_or(&self.signer).pubkey()
+    }
+
+    // signs with the configured keypair, the fee payer pays the fees
+    pub fn send(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = self
+            .rpc
+            .get_latest_blockhash()
+            .context("failed to fetch a recent blockhash")?;
+        let mut signers = vec![&self.signer];
+        signers.extend(self.fee_payer.as_ref());
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer()),
+            &signers,
+            blockhash,
+        );
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction(&tx)
+            .context("transaction failed")?;
+        println!("Signature: {signature}");
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use payra_client::payra::{
+    settlement::SettlementOutcome, DuesStatus, Event, EventAccess, FractionalStatus,
+    FractionalVault, MemberRole, MilestoneRelease, Participant, Proposal, ProposalType,
+};
+
+fn role(role: MemberRole) -> &'static str {
+    match role {
+        MemberRole::Observer => "observer",
+        MemberRole::Member => "member",
+        MemberRole::Treasurer => "treasurer",
+        MemberRole::Admin => "admin",
+    }
+}
+
+fn proposal_type(proposal_type: &ProposalType) -> String {
+    match proposal_type {
+        ProposalType::Spending => "spending".to_string(),
+        ProposalType::EventSettlement => "event settlement".to_string(),
+        ProposalType::ExtendDeadline { new_deadline } => {
+            format!("extend deadline to {new_deadline}")
+        }
+        ProposalType::ChangeTarget { new_target_amount } => {
+            format!("change target to {new_target_amount}")
+        }
+        ProposalType::ChangeWithdrawAccount {
+            new_withdraw_token_account,
+        } => format!("change withdraw account to {new_withdraw_token_account}"),
+        ProposalType::Milestones {
+            release: MilestoneRelease::Approval,
+        } => "milestones released on approval".to_string(),
+        ProposalType::Milestones {
+            release: MilestoneRelease::Attestation,
+        } => "milestones released on attestation".to_string(),
+        ProposalType::ReservePrice { vault, price } => {
+            format!("set the reserve price of {vault} to {price}")
+        }
+    }
+}
+
+fn status(proposal: &Proposal) -> String {
+    match (proposal.settled, proposal.cancelled) {
+        (_, true) => "cancelled".to_string(),
+        // approved milestone proposal still paying out
+        (true, false) if proposal.next_milestone().is_some() => format!(
+            "released {}/{} milestones, {} approvals",
+            proposal.milestones.iter().filter(|m| m.released).count(),
+            proposal.milestones.len(),
+            proposal.milestone_approvals.len()
+        ),
+        (true, false) => "settled".to_string(),
+        (false, false) => "open".to_string(),
+    }
+}
+
+pub fn event(event: &Event, proposals: &[Proposal]) {
+    println!("Event {}: {}", event.event_id, event.name);
+    println!("  creator:          {}", event.creator);
+    println!("  mint:             {}", event.mint);
+    println!("  withdraw account: {}", event.withdraw_token_account);
+    println!("  target:           {}", event.target_amount);
+    println!("  contributed:      {}", event.total_contributed);
+    println!("  spent:            {}", event.total_spent);
+    println!("  deadline:         {}", event.deadline);
+    if let Some(dues) = &event.dues {
+        println!(
+            "  dues:             {} every {} seconds since {}",
+            dues.amount, dues.period, dues.start
+        );
+    }
+    if let EventAccess::Open { voting_threshold } = event.access {
+        println!(
+            "  access:           open, votes from {} contributed",
+            voting_threshold
+        );
+    }
+    println!(
+        "  status:           {}",
+        if event.is_cancelled {
+            "cancelled"
+        } else if event.is_finalized {
+            "finalized"
+        } else {
+            "open"
+        }
+    );
+
+    println!("Members:");
+    for (wallet, member_role) in event.whitelist.iter().zip(&event.roles) {
+        println!("  {wallet} {}", role(*member_role));
+    }
+
+    println!("Proposals:");
+    for proposal in proposals {
+        println!(
+            "  #{} {} ({}) amount {} yes {} no {} deadline {} {}",
+            proposal.proposal_id,
+            proposal.title,
+            proposal_type(&proposal.proposal_type),
+            proposal.amount,
+            proposal.yes_votes.len(),
+            proposal.no_votes.len(),
+            proposal.deadline,
+            status(proposal),
+        );
+    }
+}
+
+pub fn fractional(vault: &FractionalVault) {
+    println!("NFT {}", vault.nft_mint);
+    println!("  share mint:    {}", vault.share_mint);
+    println!("  shares:        {}", vault.total_shares);
+    println!("  redeemed:      {}", vault.redeemed_shares);
+    println!("  reserve price: {}", vault.reserve_price);
+    println!("  claimed by:    {}", vault.claimed.len());
+    match vault.status {
+        FractionalStatus::Active => println!("  status:        in escrow"),
+        FractionalStatus::BoughtOut { buyer, price } => {
+            println!("  status:        bought out by {buyer} for {price}")
+        }
+        FractionalStatus::Redeemed { holder } => {
+            println!("  status:        redeemed by {holder}")
+        }
+    }
+}
+
+pub fn balances(participants: &[Participant]) {
+    println!(
+        "{:<44} {:>20} {:>20} {:>20}",
+        "wallet", "contributed", "spent", "net owed"
+    );
+    for participant in participants {
+        println!(
+            "{:<44} {:>20} {:>20} {:>20}",
+            participant.wallet.to_string(),
+            participant.contributed,
+            participant.spent,
+            participant.net_owed
+        );
+    }
+}
+
+pub fn settlement(outcome: &SettlementOutcome) {
+    if !outcome.applied_proposals.is_empty() {
+        println!("Assuming proposals {:?} pass", outcome.applied_proposals);
+    }
+    println!("Vault balance: {}", outcome.vault_balance);
+    println!(
+        "{:<44} {:>20} {:>20} {:>20}",
+        "wallet", "contributed", "spent", "net owed"
+    );
+    for balance in &outcome.balances {
+        println!(
+            "{:<44} {:>20} {:>20} {:>20}",
+            balance.wallet.to_string(),
+            balance.contributed,
+            balance.spent,
+            balance.net_owed
+        );
+    }
+    println!("Transfers:");
+    for transfer in &outcome.transfers {
+        let from = transfer
+            .from
+            .map_or_else(|| "event vault".to_string(), |wallet| wallet.to_string());
+        println!("  {from} -> {} {}", transfer.to, transfer.amount);
+    }
+}
+
+pub fn dues(status: &DuesStatus, behind_only: bool) {
+    println!(
+        "Dues: {} every {} seconds since {}, {} periods due",
+        status.amount, status.period, status.start, status.periods_due
+    );
+    println!(
+        "{:<44} {:>12} {:>12} {:>20}",
+        "wallet", "paid", "behind", "amount behind"
+    );
+    for member in &status.members {
+        if behind_only && member.periods_behind == 0 {
+            continue;
+        }
+        println!(
+            "{:<44} {:>12} {:>12} {:>20}",
+            member.wallet.to_string(),
+            member.periods_paid,
+            member.periods_behind,
+            member.amount_behind
+        );
+    }
+}
@@ -0,0 +1,692 @@
+//! `payra` command-line tool: create and manage Payra events from a terminal,
+//! signing with the keypair of the Solana CLI config.
+
+mod config;
+mod display;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use payra_client::{
+    instructions,
+    invite::SignedInvite,
+    payra::{
+        settlement::PendingProposals, EventAccess, EventArgs, InviteVoucher, MemberRole,
+        MilestoneRelease, ProposalType, SpendingShare,
+    },
+};
+use solana_signer::Signer;
+
+use crate::config::Context;
+
+#[derive(Parser)]
+#[command(name = "payra", version, about = "Manage Payra events")]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Solana CLI config file, defaults to ~/.config/solana/cli/config.yml
+    #[arg(long, short = 'C', global = true)]
+    pub config: Option<String>,
+
+    /// RPC url, overrides the config
+    #[arg(long, short = 'u', global = true)]
+    pub url: Option<String>,
+
+    /// Signer keypair file, overrides the config
+    #[arg(long, short = 'k', global = true)]
+    pub keypair: Option<String>,
+
+    /// Keypair paying the fees instead of the signer, and the rent of the accounts members create
+    #[arg(long, global = true)]
+    pub fee_payer: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create an event, signed by its creator
+    CreateEvent {
+        #[arg(long)]
+        name: String,
+        /// Target amount in base units of the mint
+        #[arg(long)]
+        target: u64,
+        /// Unix timestamp
+        #[arg(long)]
+        deadline: i64,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Token account receiving the spendings, defaults to the signer's ATA
+        #[arg(long)]
+        withdraw_account: Option<Pubkey>,
+    },
+    /// Add members to an event
+    Whitelist {
+        event_id: u64,
+        #[arg(required = true)]
+        wallets: Vec<Pubkey>,
+    },
+    /// Print an invite link signed by the signer, an admin of the event
+    Invite {
+        event_id: u64,
+        /// Tells the signer's invites apart, each one counts its own uses
+        invite_id: u64,
+        /// Number of wallets able to join with the link
+        #[arg(long)]
+        max_uses: u16,
+        /// Unix timestamp
+        #[arg(long)]
+        expires_at: i64,
+        #[arg(long, value_enum, default_value_t = Role::Member)]
+        role: Role,
+    },
+    /// Join an event with an invite link
+    Join { invite: String },
+    /// Make members of an event pay a fixed amount every period
+    SetDues {
+        event_id: u64,
+        /// Amount per period in base units of the mint
+        #[arg(long)]
+        amount: u64,
+        /// Period length in seconds, the first one starts now
+        #[arg(long)]
+        period: i64,
+    },
+    /// Open an event to anyone, or back to whitelisted members only
+    SetAccess {
+        event_id: u64,
+        /// Contribution after which an outside contributor gets a vote, opens the event
+        #[arg(long, conflicts_with = "whitelist")]
+        voting_threshold: Option<u64>,
+        /// Only whitelisted members can contribute
+        #[arg(long, required_unless_present = "voting_threshold")]
+        whitelist: bool,
+    },
+    /// Contribute to an event, amount in base units of the mint
+    Contribute { event_id: u64, amount: u64 },
+    /// Create a spending proposal
+    Propose {
+        event_id: u64,
+        #[arg(long)]
+        title: String,
+        /// Amount in base units of the mint
+        #[arg(long)]
+        amount: u64,
+        /// Unix timestamp
+        #[arg(long)]
+        deadline: i64,
+        /// Share of the expense as <wallet>=<percentage>, repeatable
+        #[arg(long = "share", value_parser = parse_share, required = true)]
+        shares: Vec<(Pubkey, u8)>,
+    },
+    /// Create a spending proposal paid out in tranches
+    ProposeMilestones {
+        event_id: u64,
+        #[arg(long)]
+        title: String,
+        /// Amount of a tranche in base units of the mint, repeatable, in payout order
+        #[arg(long = "milestone", required = true)]
+        milestones: Vec<u64>,
+        /// Release tranches on the event creator's attestation instead of an approval
+        #[arg(long)]
+        attestation: bool,
+        /// Unix timestamp
+        #[arg(long)]
+        deadline: i64,
+        /// Share of the expense as <wallet>=<percentage>, repeatable
+        #[arg(long = "share", value_parser = parse_share, required = true)]
+        shares: Vec<(Pubkey, u8)>,
+    },
+    /// Approve the next milestone of an approved milestone proposal
+    ApproveMilestone { event_id: u64, proposal_id: u16 },
+    /// Pay out the next milestone of an approved milestone proposal
+    ReleaseMilestone { event_id: u64, proposal_id: u16 },
+    /// Cancel the milestones not released yet, their funds stay in the event
+    CancelMilestones { event_id: u64, proposal_id: u16 },
+    /// Move an NFT bought by the group into escrow and split it into shares
+    Fractionalize {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+        /// Settled spending proposal that paid for the NFT
+        #[arg(long)]
+        proposal: u16,
+    },
+    /// Propose the lowest buyout price of a fractionalized NFT, voted by the
+    /// event members
+    ProposeReservePrice {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+        /// Lowest buyout price in base units of the event mint
+        #[arg(long)]
+        price: u64,
+        /// Voting deadline, unix timestamp
+        #[arg(long)]
+        deadline: i64,
+    },
+    /// Claim the shares of an NFT for what the signer contributed
+    ClaimShares {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+    },
+    /// Buy an NFT out of escrow, price in base units of the event mint
+    Buyout {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+        #[arg(long)]
+        price: u64,
+    },
+    /// Burn shares against their part of the buyout proceeds
+    RedeemShares {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+        shares: u64,
+    },
+    /// Burn every share of an NFT and take it out of escrow
+    RedeemNft {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+    },
+    /// Print the escrow of a fractionalized NFT
+    Fractional {
+        event_id: u64,
+        #[arg(long)]
+        nft: Pubkey,
+    },
+    /// Create a proposal to settle the event
+    ProposeSettlement {
+        event_id: u64,
+        /// Unix timestamp
+        #[arg(long)]
+        deadline: i64,
+    },
+    /// Vote on a proposal
+    Vote {
+        event_id: u64,
+        proposal_id: u16,
+        #[arg(value_enum)]
+        choice: VoteChoice,
+        /// Vote for this member, the signer being a session key they created
+        #[arg(long)]
+        member: Option<Pubkey>,
+    },
+    /// Let a session key vote for the signer on an event until it expires
+    CreateSession {
+        event_id: u64,
+        /// Public key of the session keypair, which pays its own fees
+        session_key: Pubkey,
+        /// Unix timestamp, at most a week away
+        #[arg(long)]
+        expires_at: i64,
+    },
+    /// Revoke the signer's session key on an event
+    RevokeSession { event_id: u64 },
+    /// Settle a proposal, whatever its type
+    Settle { event_id: u64, proposal_id: u16 },
+    /// Print an event and its proposals
+    Event { event_id: u64 },
+    /// Print the balance of every participant of an event
+    Balances { event_id: u64 },
+    /// Preview the balances and transfers settle_event would lead to
+    PreviewSettlement {
+        event_id: u64,
+        /// Assume every pending spending and milestone proposal passes
+        #[arg(long, conflicts_with = "assume")]
+        assume_all: bool,
+        /// Pending spending or milestone proposal assumed to pass, repeatable
+        #[arg(long)]
+        assume: Vec<u16>,
+    },
+    /// Print the dues paid by every member of an event
+    Dues {
+        event_id: u64,
+        /// Only list members behind on their dues
+        #[arg(long)]
+        behind: bool,
+    },
+    /// Export the statement of an event, or of one of its participants
+    Statement {
+        event_id: u64,
+        /// Only this participant's statement
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+        #[arg(long, value_enum, default_value_t = StatementFormat::Csv)]
+        format: StatementFormat,
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatementFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Role {
+    Observer,
+    Member,
+    Treasurer,
+    Admin,
+}
+
+impl From<Role> for MemberRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Observer => MemberRole::Observer,
+            Role::Member => MemberRole::Member,
+            Role::Treasurer => MemberRole::Treasurer,
+            Role::Admin => MemberRole::Admin,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum VoteChoice {
+    Yes,
+    No,
+}
+
+fn parse_share(value: &str) -> Result<(Pubkey, u8)> {
+    let (wallet, percentage) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected <wallet>=<percentage>"))?;
+    Ok((wallet.parse()?, percentage.parse()?))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = Context::load(&cli.global)?;
+
+    match cli.command {
+        Command::CreateEvent {
+            name,
+            target,
+            deadline,
+            mint,
+            withdraw_account,
+        } => {
+            let event_id = payra_client::fetch_next_event_id(&ctx.rpc)?;
+            let withdraw_account = withdraw_account
+                .unwrap_or_else(|| get_associated_token_address(&ctx.pubkey(), &mint));
+            ctx.send(&[instructions::create_event(
+                ctx.pubkey(),
+                event_id,
+                mint,
+                withdraw_account,
+                EventArgs {
+                    name,
+                    deadline,
+                    target_amount: target,
+                },
+            )])?;
+            println!("Event id: {event_id}");
+        }
+        Command::Whitelist { event_id, wallets } => {
+            ctx.send(&[instructions::whitelist(ctx.pubkey(), event_id, wallets)])?;
+        }
+        Command::Invite {
+            event_id,
+            invite_id,
+            max_uses,
+            expires_at,
+            role,
+        } => {
+            let voucher = InviteVoucher {
+                event: payra_client::pda::event(event_id).0,
+                issuer: ctx.pubkey(),
+                invite_id,
+                role: role.into(),
+                max_uses,
+                expires_at,
+            };
+            let signature = ctx.signer.sign_message(&voucher.message()?);
+            let invite = SignedInvite {
+                voucher,
+                signature: signature.into(),
+            };
+            println!("{}", invite.encode());
+        }
+        Command::Join { invite } => {
+            let invite = SignedInvite::decode(&invite)?;
+            ctx.send(&instructions::sponsored_join_event(
+                ctx.payer(),
+                ctx.pubkey(),
+                &invite,
+            )?)?;
+        }
+        Command::SetDues {
+            event_id,
+            amount,
+            period,
+        } => {
+            ctx.send(&[instructions::set_dues(
+                ctx.pubkey(),
+                event_id,
+                amount,
+                period,
+            )])?;
+        }
+        Command::SetAccess {
+            event_id,
+            voting_threshold,
+            ..
+        } => {
+            let access = match voting_threshold {
+                Some(voting_threshold) => EventAccess::Open { voting_threshold },
+                None => EventAccess::Whitelist,
+            };
+            ctx.send(&[instructions::set_event_access(
+                ctx.pubkey(),
+                event_id,
+                access,
+            )])?;
+        }
+        Command::Contribute { event_id, amount } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            ctx.send(&[instructions::sponsored_contribute(
+                ctx.payer(),
+                ctx.pubkey(),
+                event_id,
+                event.mint,
+                amount,
+            )])?;
+        }
+        Command::Propose {
+            event_id,
+            title,
+            amount,
+            deadline,
+            shares,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let spendings = shares
+                .into_iter()
+                .map(|(wallet, percentage)| SpendingShare { wallet, percentage })
+                .collect();
+            ctx.send(&[instructions::sponsored_create_proposal(
+                ctx.payer(),
+                ctx.pubkey(),
+                &event,
+                title,
+                amount,
+                spendings,
+                deadline,
+            )])?;
+            println!("Proposal id: {}", event.proposal_count);
+        }
+        Command::ProposeMilestones {
+            event_id,
+            title,
+            milestones,
+            attestation,
+            deadline,
+            shares,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let spendings = shares
+                .into_iter()
+                .map(|(wallet, percentage)| SpendingShare { wallet, percentage })
+                .collect();
+            let release = if attestation {
+                MilestoneRelease::Attestation
+            } else {
+                MilestoneRelease::Approval
+            };
+            ctx.send(&[instructions::sponsored_create_milestone_proposal(
+                ctx.payer(),
+                ctx.pubkey(),
+                &event,
+                title,
+                milestones,
+                release,
+                spendings,
+                deadline,
+            )])?;
+            println!("Proposal id: {}", event.proposal_count);
+        }
+        Command::ApproveMilestone {
+            event_id,
+            proposal_id,
+        } => {
+            ctx.send(&[instructions::approve_milestone(
+                ctx.pubkey(),
+                event_id,
+                proposal_id,
+            )])?;
+        }
+        Command::ReleaseMilestone {
+            event_id,
+            proposal_id,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let proposal = payra_client::fetch_proposal(&ctx.rpc, event_id, proposal_id)?;
+            if proposal.next_milestone().is_none() {
+                bail!("proposal {proposal_id} has no milestone left to release");
+            }
+            ctx.send(&[instructions::release_milestone(
+                ctx.pubkey(),
+                &event,
+                &proposal,
+            )])?;
+        }
+        Command::CancelMilestones {
+            event_id,
+            proposal_id,
+        } => {
+            ctx.send(&[instructions::cancel_milestones(
+                ctx.pubkey(),
+                event_id,
+                proposal_id,
+            )])?;
+        }
+        Command::Fractionalize {
+            event_id,
+            nft,
+            proposal,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            ctx.send(&[instructions::fractionalize(
+                ctx.pubkey(),
+                &event,
+                proposal,
+                nft,
+            )])?;
+        }
+        Command::ProposeReservePrice {
+            event_id,
+            nft,
+            price,
+            deadline,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let event_key = payra_client::pda::event(event_id).0;
+            let vault = payra_client::pda::fractional_vault(&event_key, &nft).0;
+            ctx.send(&[instructions::sponsored_create_governance_proposal(
+                ctx.payer(),
+                ctx.pubkey(),
+                &event,
+                ProposalType::ReservePrice { vault, price },
+                deadline,
+            )])?;
+            println!("Proposal id: {}", event.proposal_count);
+        }
+        Command::ClaimShares { event_id, nft } => {
+            ctx.send(&[instructions::claim_shares(ctx.pubkey(), event_id, &nft)])?;
+        }
+        Command::Buyout {
+            event_id,
+            nft,
+            price,
+        } => {
+            let vault = payra_client::fetch_fractional_vault(&ctx.rpc, event_id, &nft)?;
+            ctx.send(&[instructions::buyout(ctx.pubkey(), &vault, price)])?;
+        }
+        Command::RedeemShares {
+            event_id,
+            nft,
+            shares,
+        } => {
+            let vault = payra_client::fetch_fractional_vault(&ctx.rpc, event_id, &nft)?;
+            ctx.send(&[instructions::redeem_shares(ctx.pubkey(), &vault, shares)])?;
+        }
+        Command::RedeemNft { event_id, nft } => {
+            let vault = payra_client::fetch_fractional_vault(&ctx.rpc, event_id, &nft)?;
+            ctx.send(&[instructions::redeem_nft(ctx.pubkey(), &vault)])?;
+        }
+        Command::Fractional { event_id, nft } => {
+            let vault = payra_client::fetch_fractional_vault(&ctx.rpc, event_id, &nft)?;
+            display::fractional(&vault);
+        }
+        Command::ProposeSettlement { event_id, deadline } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            ctx.send(&[instructions::sponsored_create_settle_proposal(
+                ctx.payer(),
+                ctx.pubkey(),
+                &event,
+                deadline,
+            )])?;
+            println!("Proposal id: {}", event.proposal_count);
+        }
+        Command::Vote {
+            event_id,
+            proposal_id,
+            choice,
+            member,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let approve = matches!(choice, VoteChoice::Yes);
+            let ix = match member {
+                Some(member) => instructions::session_vote(
+                    ctx.pubkey(),
+                    member,
+                    event_id,
+                    proposal_id,
+                    event.mint,
+                    approve,
+                ),
+                None => {
+                    instructions::vote(ctx.pubkey(), event_id, proposal_id, event.mint, approve)
+                }
+            };
+            ctx.send(&[ix])?;
+        }
+        Command::CreateSession {
+            event_id,
+            session_key,
+            expires_at,
+        } => {
+            ctx.send(&[instructions::sponsored_create_session(
+                ctx.payer(),
+                ctx.pubkey(),
+                event_id,
+                session_key,
+                expires_at,
+            )])?;
+        }
+        Command::RevokeSession { event_id } => {
+            let session = payra_client::fetch_session(&ctx.rpc, event_id, &ctx.pubkey())?;
+            ctx.send(&[instructions::revoke_session(&session)])?;
+        }
+        Command::Settle {
+            event_id,
+            proposal_id,
+        } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let proposal = payra_client::fetch_proposal(&ctx.rpc, event_id, proposal_id)?;
+            if proposal.settled {
+                bail!("proposal {proposal_id} is already settled");
+            }
+            let ix = match proposal.proposal_type {
+                ProposalType::Spending | ProposalType::Milestones { .. } => {
+                    instructions::settle_proposal(ctx.pubkey(), &event, &proposal)
+                }
+                ProposalType::EventSettlement => {
+                    instructions::settle_event(ctx.pubkey(), &event, proposal_id)
+                }
+                _ => instructions::settle_governance_proposal(ctx.pubkey(), event_id, &proposal),
+            };
+            ctx.send(&[ix])?;
+        }
+        Command::Event { event_id } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let proposals = payra_client::fetch_proposals(&ctx.rpc, &event)?;
+            display::event(&event, &proposals);
+        }
+        Command::Balances { event_id } => {
+            let event = payra_client::fetch_event(&ctx.rpc, event_id)?;
+            let participants = payra_client::fetch_participants(&ctx.rpc, &event)?;
+            display::balances(&participants);
+        }
+        Command::PreviewSettlement {
+            event_id,
+            assume_all,
+            assume,
+        } => {
+            let pending = if assume_all {
+                PendingProposals::AssumeAll
+            } else if assume.is_empty() {
+                PendingProposals::Ignore
+            } else {
+                PendingProposals::Assume(&assume)
+            };
+            let outcome = payra_client::settlement::dry_run(&ctx.rpc, event_id, pending)?;
+            display::settlement(&outcome);
+        }
+        Command::Dues { event_id, behind } => {
+            let status = payra_client::view::dues_status(&ctx.rpc, &ctx.pubkey(), event_id)?;
+            display::dues(&status, behind);
+        }
+        Command::Statement {
+            event_id,
+            wallet,
+            format,
+            output,
+        } => {
+            let statement = payra_client::statement::fetch_statement(&ctx.rpc, event_id)?;
+            let mut writer: Box<dyn Write> = match output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(io::stdout().lock()),
+            };
+            match (wallet, format) {
+                (None, StatementFormat::Csv) => statement.write_csv(&mut writer)?,
+                (None, StatementFormat::Json) => statement.write_json(&mut writer)?,
+                (Some(wallet), format) => {
+                    let participant = statement.participant(&wallet).ok_or_else(|| {
+                        anyhow!("{wallet} is not a participant of event {event_id}")
+                    })?;
+                    match format {
+                        StatementFormat::Csv => participant.write_csv(&mut writer)?,
+                        StatementFormat::Json => participant.write_json(&mut writer)?,
+                    }
+                }
+            }
+            if matches!(format, StatementFormat::Json) {
+                writeln!(writer)?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use payra::{Event, EventCounter, FractionalVault, Participant, Proposal, Session};
+use solana_rpc_client::rpc_client::RpcClient;
+
+use crate::{pda, ClientError, Result};
+
+// getMultipleAccounts accepts at most 100 keys per request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+// decode a Payra account, discriminator included
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    Ok(T::try_deserialize(&mut &data[..])?)
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, key: &Pubkey) -> Result<T> {
+    fetch_optional(rpc, key)?.ok_or(ClientError::AccountNotFound(*key))
+}
+
+pub fn fetch_optional<T: AccountDeserialize>(rpc: &RpcClient, key: &Pubkey) -> Result<Option<T>> {
+    let account = rpc
+        .get_multiple_accounts(std::slice::from_ref(key))?
+        .pop()
+        .flatten();
+    account.map(|acc| decode(&acc.data)).transpose()
+}
+
+// missing accounts are skipped
+pub fn fetch_many<T: AccountDeserialize>(rpc: &RpcClient, keys: &[Pubkey]) -> Result<Vec<T>> {
+    let mut decoded = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        for account in rpc.get_multiple_accounts(chunk)?.into_iter().flatten() {
+            decoded.push(decode(&account.data)?);
+        }
+    }
+    Ok(decoded)
+}
+
+// id the next created event will get
+pub fn fetch_next_event_id(rpc: &RpcClient) -> Result<u64> {
+    let counter: EventCounter = fetch(rpc, &pda::event_counter().0)?;
+    Ok(counter.count)
+}
+
+pub fn fetch_event(rpc: &RpcClient, event_id: u64) -> Result<Event> {
+    fetch(rpc, &pda::event(event_id).0)
+}
+
+pub fn fetch_proposal(rpc: &RpcClient, event_id: u64, proposal_id: u16) -> Result<Proposal> {
+    let event = pda::event(event_id).0;
+    fetch(rpc, &pda::proposal(&event, proposal_id).0)
+}
+
+pub fn fetch_participant(
+    rpc: &RpcClient,
+    event_id: u64,
+    wallet: &Pubkey,
+) -> Result<Option<Participant>> {
+    fetch_optional(rpc, &pda::participant(event_id, wallet).0)
+}
+
+pub fn fetch_session(rpc: &RpcClient, event_id: u64, member: &Pubkey) -> Result<Session> {
+    let event = pda::event(event_id).0;
+    fetch(rpc, &pda::session(&event, member).0)
+}
+
+pub fn fetch_fractional_vault(
+    rpc: &RpcClient,
+    event_id: u64,
+    nft_mint: &Pubkey,
+) -> Result<FractionalVault> {
+    let event = pda::event(event_id).0;
+    fetch(rpc, &pda::fractional_vault(&event, nft_mint).0)
+}
+
+// every proposal ever created for the event, in id order
+pub fn fetch_proposals(rpc: &RpcClient, event: &Event) -> Result<Vec<Proposal>> {
+    let event_key = pda::event(event.event_id).0;
+    let keys: Vec<Pubkey> = (0..event.proposal_count)
+        .map(|id| pda::proposal(&event_key, id).0)
+        .collect();
+    fetch_many(rpc, &keys)
+}
+
+// participant accounts of the voters and of every other wallet that
+// contributed, open event donors included
+pub fn fetch_participants(rpc: &RpcClient, event: &Event) -> Result<Vec<Participant>> {
+    let keys: Vec<Pubkey> = event
+        .participants()
+        .iter()
+        .map(|wallet| pda::participant(event.event_id, wallet).0)
+        .collect();
+    fetch_many(rpc, &keys)
+}
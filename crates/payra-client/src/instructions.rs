@@ -0,0 +1,821 @@
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, sysvar},
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    token,
+};
+use payra::{
+    accounts, instruction, ContributionRules, Event, EventAccess, EventArgs, EventV0,
+    FractionalVault, MemberRole, MilestoneRelease, Proposal, ProposalType, Session, SpendingShare,
+};
+
+use crate::{invite::SignedInvite, pda, Result};
+
+// One builder per `#[program]` method. Builders take the keys a caller
+// already knows and derive every PDA the same way the program does.
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: payra::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn writable(keys: impl IntoIterator<Item = Pubkey>) -> Vec<AccountMeta> {
+    keys.into_iter()
+        .map(|key| AccountMeta::new(key, false))
+        .collect()
+}
+
+pub fn initialize(admin: Pubkey) -> Instruction {
+    build(
+        accounts::Initialize {
+            admin,
+            event_counter: pda::event_counter().0,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {},
+    )
+}
+
+// `event_id` is the current count of the event counter
+pub fn create_event(
+    creator: Pubkey,
+    event_id: u64,
+    mint: Pubkey,
+    withdraw_token_account: Pubkey,
+    args: EventArgs,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::CreateEvent {
+            creator,
+            event_counter: pda::event_counter().0,
+            event,
+            mint,
+            event_vault: pda::event_vault(&event, &mint),
+            withdraw_token_account,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateEvent { args },
+    )
+}
+
+pub fn close_event(creator: Pubkey, event_id: u64, mint: Pubkey) -> Instruction {
+    build(
+        accounts::CloseEvent {
+            creator,
+            event: pda::event(event_id).0,
+            mint,
+            system_program: system_program::ID,
+        },
+        instruction::CloseEvent {},
+    )
+}
+
+pub fn set_contribution_rules(
+    admin: Pubkey,
+    event_id: u64,
+    rules: ContributionRules,
+) -> Instruction {
+    build(
+        accounts::SetContributionRules {
+            admin,
+            event: pda::event(event_id).0,
+        },
+        instruction::SetContributionRules { rules },
+    )
+}
+
+// `period` in seconds, the first period starts when the instruction runs
+pub fn set_dues(admin: Pubkey, event_id: u64, amount: u64, period: i64) -> Instruction {
+    build(
+        accounts::SetDues {
+            admin,
+            event: pda::event(event_id).0,
+        },
+        instruction::SetDues { amount, period },
+    )
+}
+
+pub fn set_event_access(admin: Pubkey, event_id: u64, access: EventAccess) -> Instruction {
+    build(
+        accounts::SetEventAccess {
+            admin,
+            event: pda::event(event_id).0,
+        },
+        instruction::SetEventAccess { access },
+    )
+}
+
+pub fn contribute(contributor: Pubkey, event_id: u64, mint: Pubkey, amount: u64) -> Instruction {
+    sponsored_contribute(contributor, contributor, event_id, mint, amount)
+}
+
+// `payer` covers the participant account rent, the contributor only signs
+// the token transfer. Make the payer the transaction fee payer as well.
+pub fn sponsored_contribute(
+    payer: Pubkey,
+    contributor: Pubkey,
+    event_id: u64,
+    mint: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::Contribute {
+            contributor,
+            mint,
+            contributor_ata: associated_token::get_associated_token_address(&contributor, &mint),
+            event,
+            event_vault: pda::event_vault(&event, &mint),
+            payer,
+            participant: pda::participant(event_id, &contributor).0,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Contribute { amount },
+    )
+}
+
+pub fn whitelist(creator: Pubkey, event_id: u64, wallets_to_add: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::Whitelist {
+            creator,
+            event: pda::event(event_id).0,
+        },
+        instruction::Whitelist { wallets_to_add },
+    )
+}
+
+// The ed25519 program verifies the voucher signature, it has to come right
+// before `join_event` in the same transaction.
+pub fn join_event(member: Pubkey, invite: &SignedInvite) -> Result<[Instruction; 2]> {
+    sponsored_join_event(member, member, invite)
+}
+
+// `payer` covers the invite account rent
+pub fn sponsored_join_event(
+    payer: Pubkey,
+    member: Pubkey,
+    invite: &SignedInvite,
+) -> Result<[Instruction; 2]> {
+    let voucher = &invite.voucher;
+    let verify = solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &voucher.message()?,
+        &invite.signature,
+        &voucher.issuer.to_bytes(),
+    );
+    let join = build(
+        accounts::JoinEvent {
+            member,
+            event: voucher.event,
+            payer,
+            invite: pda::invite(&voucher.event, &voucher.issuer, voucher.invite_id).0,
+            instructions: sysvar::instructions::ID,
+            system_program: system_program::ID,
+        },
+        instruction::JoinEvent {
+            voucher: voucher.clone(),
+        },
+    );
+    Ok([verify, join])
+}
+
+pub fn set_member_role(
+    admin: Pubkey,
+    event_id: u64,
+    wallet: Pubkey,
+    role: MemberRole,
+) -> Instruction {
+    build(
+        accounts::SetMemberRole {
+            admin,
+            event: pda::event(event_id).0,
+            participant: pda::participant(event_id, &wallet).0,
+        },
+        instruction::SetMemberRole { wallet, role },
+    )
+}
+
+// proposal builders use the event's current `proposal_count` as the new id,
+// their sponsored variants leave the proposal rent to `payer`
+pub fn create_proposal(
+    creator: Pubkey,
+    event: &Event,
+    title: String,
+    amount: u64,
+    spendings: Vec<SpendingShare>,
+    deadline: i64,
+) -> Instruction {
+    sponsored_create_proposal(creator, creator, event, title, amount, spendings, deadline)
+}
+
+pub fn sponsored_create_proposal(
+    payer: Pubkey,
+    creator: Pubkey,
+    event: &Event,
+    title: String,
+    amount: u64,
+    spendings: Vec<SpendingShare>,
+    deadline: i64,
+) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    build(
+        accounts::CreateProposal {
+            creator,
+            event: event_key,
+            mint: event.mint,
+            payer,
+            proposal: pda::proposal(&event_key, event.proposal_count).0,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal {
+            title,
+            amount,
+            spendings,
+            deadline,
+        },
+    )
+}
+
+pub fn vote(
+    voter: Pubkey,
+    event_id: u64,
+    proposal_id: u16,
+    mint: Pubkey,
+    vote_choice: bool,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    let mut ix = build(
+        accounts::Vote {
+            voter,
+            event,
+            mint,
+            proposal: pda::proposal(&event, proposal_id).0,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            session_key: None,
+            session: None,
+        },
+        instruction::Vote { vote_choice },
+    );
+    // the voter is unchecked so a session key can vote for them, without a
+    // session they sign themselves
+    ix.accounts[0].is_signer = true;
+    ix
+}
+
+// vote of `voter` signed by the session key they authorized
+pub fn session_vote(
+    session_key: Pubkey,
+    voter: Pubkey,
+    event_id: u64,
+    proposal_id: u16,
+    mint: Pubkey,
+    vote_choice: bool,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::Vote {
+            voter,
+            event,
+            mint,
+            proposal: pda::proposal(&event, proposal_id).0,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            session_key: Some(session_key),
+            session: Some(pda::session(&event, &voter).0),
+        },
+        instruction::Vote { vote_choice },
+    )
+}
+
+pub fn create_session(
+    member: Pubkey,
+    event_id: u64,
+    session_key: Pubkey,
+    expires_at: i64,
+) -> Instruction {
+    sponsored_create_session(member, member, event_id, session_key, expires_at)
+}
+
+// `payer` covers the session account rent
+pub fn sponsored_create_session(
+    payer: Pubkey,
+    member: Pubkey,
+    event_id: u64,
+    session_key: Pubkey,
+    expires_at: i64,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::CreateSession {
+            member,
+            event,
+            payer,
+            session: pda::session(&event, &member).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSession {
+            session_key,
+            expires_at,
+        },
+    )
+}
+
+// the session rent goes back to whoever paid it
+pub fn revoke_session(session: &Session) -> Instruction {
+    build(
+        accounts::RevokeSession {
+            member: session.member,
+            session: pda::session(&session.event, &session.member).0,
+            rent_payer: session.rent_payer,
+        },
+        instruction::RevokeSession {},
+    )
+}
+
+// the participant account of every wallet sharing the expense is passed as a
+// writable remaining account
+pub fn settle_proposal(signer: Pubkey, event: &Event, proposal: &Proposal) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    let mut ix = build(
+        accounts::SettleProposal {
+            signer,
+            event: event_key,
+            mint: event.mint,
+            event_vault: pda::event_vault(&event_key, &event.mint),
+            proposal: pda::proposal(&event_key, proposal.proposal_id).0,
+            withdraw_account: event.withdraw_token_account,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::SettleProposal {},
+    );
+    ix.accounts.extend(writable(
+        proposal
+            .spendings
+            .iter()
+            .map(|s| pda::participant(event.event_id, &s.wallet).0),
+    ));
+    ix
+}
+
+pub fn create_settle_proposal(creator: Pubkey, event: &Event, deadline: i64) -> Instruction {
+    sponsored_create_settle_proposal(creator, creator, event, deadline)
+}
+
+pub fn sponsored_create_settle_proposal(
+    payer: Pubkey,
+    creator: Pubkey,
+    event: &Event,
+    deadline: i64,
+) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    build(
+        accounts::CreateSettleProposal {
+            creator,
+            event: event_key,
+            mint: event.mint,
+            payer,
+            proposal: pda::proposal(&event_key, event.proposal_count).0,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSettleProposal { deadline },
+    )
+}
+
+// the participant account of every contributor, open event donors included,
+// is passed as a writable remaining account
+pub fn settle_event(signer: Pubkey, event: &Event, proposal_id: u16) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    let mut ix = build(
+        accounts::SettleEvent {
+            signer,
+            event: event_key,
+            proposal: pda::proposal(&event_key, proposal_id).0,
+        },
+        instruction::SettleEvent {},
+    );
+    ix.accounts.extend(writable(
+        event
+            .contributors
+            .iter()
+            .map(|wallet| pda::participant(event.event_id, wallet).0),
+    ));
+    ix
+}
+
+pub fn create_governance_proposal(
+    creator: Pubkey,
+    event: &Event,
+    proposal_type: ProposalType,
+    deadline: i64,
+) -> Instruction {
+    sponsored_create_governance_proposal(creator, creator, event, proposal_type, deadline)
+}
+
+pub fn sponsored_create_governance_proposal(
+    payer: Pubkey,
+    creator: Pubkey,
+    event: &Event,
+    proposal_type: ProposalType,
+    deadline: i64,
+) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    let new_withdraw_token_account = match proposal_type {
+        ProposalType::ChangeWithdrawAccount {
+            new_withdraw_token_account,
+        } => Some(new_withdraw_token_account),
+        _ => None,
+    };
+    build(
+        accounts::CreateGovernanceProposal {
+            creator,
+            event: event_key,
+            payer,
+            proposal: pda::proposal(&event_key, event.proposal_count).0,
+            new_withdraw_token_account,
+            fractional_vault: reserve_price_vault(&proposal_type),
+            system_program: system_program::ID,
+        },
+        instruction::CreateGovernanceProposal {
+            proposal_type,
+            deadline,
+        },
+    )
+}
+
+pub fn settle_governance_proposal(
+    signer: Pubkey,
+    event_id: u64,
+    proposal: &Proposal,
+) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::SettleGovernanceProposal {
+            signer,
+            event,
+            proposal: pda::proposal(&event, proposal.proposal_id).0,
+            fractional_vault: reserve_price_vault(&proposal.proposal_type),
+        },
+        instruction::SettleGovernanceProposal {},
+    )
+}
+
+// the vault a ReservePrice proposal sets the price of
+fn reserve_price_vault(proposal_type: &ProposalType) -> Option<Pubkey> {
+    match proposal_type {
+        ProposalType::ReservePrice { vault, .. } => Some(*vault),
+        _ => None,
+    }
+}
+
+pub fn apply_withdraw_account_change(
+    signer: Pubkey,
+    event_id: u64,
+    new_withdraw_token_account: Pubkey,
+) -> Instruction {
+    build(
+        accounts::ApplyWithdrawAccountChange {
+            signer,
+            event: pda::event(event_id).0,
+            new_withdraw_token_account,
+        },
+        instruction::ApplyWithdrawAccountChange {},
+    )
+}
+
+pub fn propose_creator_transfer(
+    creator: Pubkey,
+    event_id: u64,
+    new_creator: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ProposeCreatorTransfer {
+            creator,
+            event: pda::event(event_id).0,
+        },
+        instruction::ProposeCreatorTransfer { new_creator },
+    )
+}
+
+pub fn accept_creator_transfer(new_creator: Pubkey, event_id: u64) -> Instruction {
+    build(
+        accounts::AcceptCreatorTransfer {
+            new_creator,
+            event: pda::event(event_id).0,
+        },
+        instruction::AcceptCreatorTransfer {},
+    )
+}
+
+pub fn create_milestone_proposal(
+    creator: Pubkey,
+    event: &Event,
+    title: String,
+    milestones: Vec<u64>,
+    release: MilestoneRelease,
+    spendings: Vec<SpendingShare>,
+    deadline: i64,
+) -> Instruction {
+    sponsored_create_milestone_proposal(
+        creator, creator, event, title, milestones, release, spendings, deadline,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn sponsored_create_milestone_proposal(
+    payer: Pubkey,
+    creator: Pubkey,
+    event: &Event,
+    title: String,
+    milestones: Vec<u64>,
+    release: MilestoneRelease,
+    spendings: Vec<SpendingShare>,
+    deadline: i64,
+) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    build(
+        accounts::CreateMilestoneProposal {
+            creator,
+            event: event_key,
+            payer,
+            proposal: pda::proposal(&event_key, event.proposal_count).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateMilestoneProposal {
+            title,
+            milestones,
+            release,
+            spendings,
+            deadline,
+        },
+    )
+}
+
+pub fn approve_milestone(approver: Pubkey, event_id: u64, proposal_id: u16) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::ApproveMilestone {
+            approver,
+            event,
+            proposal: pda::proposal(&event, proposal_id).0,
+        },
+        instruction::ApproveMilestone {},
+    )
+}
+
+// participant accounts are passed the same way as for settle_proposal
+pub fn release_milestone(signer: Pubkey, event: &Event, proposal: &Proposal) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    let mut ix = build(
+        accounts::ReleaseMilestone {
+            signer,
+            event: event_key,
+            mint: event.mint,
+            event_vault: pda::event_vault(&event_key, &event.mint),
+            proposal: pda::proposal(&event_key, proposal.proposal_id).0,
+            withdraw_account: event.withdraw_token_account,
+            token_program: token::ID,
+        },
+        instruction::ReleaseMilestone {},
+    );
+    ix.accounts.extend(writable(
+        proposal
+            .spendings
+            .iter()
+            .map(|s| pda::participant(event.event_id, &s.wallet).0),
+    ));
+    ix
+}
+
+pub fn cancel_milestones(admin: Pubkey, event_id: u64, proposal_id: u16) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::CancelMilestones {
+            admin,
+            event,
+            proposal: pda::proposal(&event, proposal_id).0,
+        },
+        instruction::CancelMilestones {},
+    )
+}
+
+// the NFT is taken from the admin's associated token account, `proposal_id`
+// is the settled spending proposal that paid for it
+pub fn fractionalize(
+    admin: Pubkey,
+    event: &Event,
+    proposal_id: u16,
+    nft_mint: Pubkey,
+) -> Instruction {
+    let event_key = pda::event(event.event_id).0;
+    let fractional_vault = pda::fractional_vault(&event_key, &nft_mint).0;
+    build(
+        accounts::Fractionalize {
+            admin,
+            event: event_key,
+            proposal: pda::proposal(&event_key, proposal_id).0,
+            mint: event.mint,
+            nft_mint,
+            admin_nft_account: get_associated_token_address(&admin, &nft_mint),
+            fractional_vault,
+            share_mint: pda::share_mint(&fractional_vault).0,
+            escrow: get_associated_token_address(&fractional_vault, &nft_mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Fractionalize {},
+    )
+}
+
+// the claimer pays the rent of their share token account
+pub fn claim_shares(claimer: Pubkey, event_id: u64, nft_mint: &Pubkey) -> Instruction {
+    let event = pda::event(event_id).0;
+    let fractional_vault = pda::fractional_vault(&event, nft_mint).0;
+    let share_mint = pda::share_mint(&fractional_vault).0;
+    build(
+        accounts::ClaimShares {
+            claimer,
+            event,
+            participant: pda::participant(event_id, &claimer).0,
+            fractional_vault,
+            share_mint,
+            claimer_shares: get_associated_token_address(&claimer, &share_mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimShares {},
+    )
+}
+
+// paid from the buyer's associated token account of the event mint
+pub fn buyout(buyer: Pubkey, vault: &FractionalVault, price: u64) -> Instruction {
+    let fractional_vault = pda::fractional_vault(&vault.event, &vault.nft_mint).0;
+    build(
+        accounts::Buyout {
+            buyer,
+            event: vault.event,
+            mint: vault.mint,
+            nft_mint: vault.nft_mint,
+            fractional_vault,
+            escrow: get_associated_token_address(&fractional_vault, &vault.nft_mint),
+            proceeds: get_associated_token_address(&fractional_vault, &vault.mint),
+            buyer_payment: get_associated_token_address(&buyer, &vault.mint),
+            buyer_nft_account: get_associated_token_address(&buyer, &vault.nft_mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Buyout { price },
+    )
+}
+
+pub fn redeem_shares(holder: Pubkey, vault: &FractionalVault, shares: u64) -> Instruction {
+    let fractional_vault = pda::fractional_vault(&vault.event, &vault.nft_mint).0;
+    build(
+        accounts::RedeemShares {
+            holder,
+            fractional_vault,
+            share_mint: vault.share_mint,
+            holder_shares: get_associated_token_address(&holder, &vault.share_mint),
+            proceeds: get_associated_token_address(&fractional_vault, &vault.mint),
+            holder_payment: get_associated_token_address(&holder, &vault.mint),
+            token_program: token::ID,
+        },
+        instruction::RedeemShares { shares },
+    )
+}
+
+pub fn redeem_nft(holder: Pubkey, vault: &FractionalVault) -> Instruction {
+    let fractional_vault = pda::fractional_vault(&vault.event, &vault.nft_mint).0;
+    build(
+        accounts::RedeemNft {
+            holder,
+            nft_mint: vault.nft_mint,
+            fractional_vault,
+            share_mint: vault.share_mint,
+            holder_shares: get_associated_token_address(&holder, &vault.share_mint),
+            escrow: get_associated_token_address(&fractional_vault, &vault.nft_mint),
+            holder_nft_account: get_associated_token_address(&holder, &vault.nft_mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RedeemNft {},
+    )
+}
+
+// `event` is the stored v0 event, it takes its mint from the withdraw token
+// account and its contributors from the participant accounts of its voters
+pub fn migrate_event(payer: Pubkey, event: &EventV0) -> Instruction {
+    let mut ix = build(
+        accounts::MigrateEvent {
+            payer,
+            account: pda::event(event.event_id).0,
+            withdraw_token_account: event.withdraw_token_account,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateEvent {},
+    );
+    ix.accounts.extend(event.voters().iter().map(|wallet| {
+        AccountMeta::new_readonly(pda::participant(event.event_id, wallet).0, false)
+    }));
+    ix
+}
+
+// the event must be migrated first
+pub fn migrate_proposal(payer: Pubkey, event_id: u64, proposal_id: u16) -> Instruction {
+    let event = pda::event(event_id).0;
+    build(
+        accounts::MigrateProposal {
+            payer,
+            event,
+            account: pda::proposal(&event, proposal_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateProposal {},
+    )
+}
+
+pub fn migrate_participant(payer: Pubkey, participant: Pubkey) -> Instruction {
+    build(
+        accounts::Migrate {
+            payer,
+            account: participant,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateParticipant {},
+    )
+}
+
+// view instructions, run them through `view::simulate` to read the result
+
+// `proposal_ids` are the proposals reported on in `open_proposals`
+pub fn get_event_summary(event_id: u64, proposal_ids: &[u16]) -> Instruction {
+    let event = pda::event(event_id).0;
+    let mut ix = build(
+        accounts::GetEventSummary { event },
+        instruction::GetEventSummary {},
+    );
+    ix.accounts.extend(
+        proposal_ids
+            .iter()
+            .map(|id| AccountMeta::new_readonly(pda::proposal(&event, *id).0, false)),
+    );
+    ix
+}
+
+pub fn preview_settlement(event: &Event) -> Instruction {
+    let mut ix = build(
+        accounts::PreviewSettlement {
+            event: pda::event(event.event_id).0,
+        },
+        instruction::PreviewSettlement {},
+    );
+    ix.accounts.extend(event.contributors.iter().map(|wallet| {
+        AccountMeta::new_readonly(pda::participant(event.event_id, wallet).0, false)
+    }));
+    ix
+}
+
+pub fn get_dues_status(event: &Event) -> Instruction {
+    let mut ix = build(
+        accounts::GetDuesStatus {
+            event: pda::event(event.event_id).0,
+        },
+        instruction::GetDuesStatus {},
+    );
+    ix.accounts.extend(event.voters().iter().map(|wallet| {
+        AccountMeta::new_readonly(pda::participant(event.event_id, wallet).0, false)
+    }));
+    ix
+}
+
+pub fn get_participant_balance(event_id: u64, wallet: &Pubkey) -> Instruction {
+    build(
+        accounts::GetParticipantBalance {
+            event: pda::event(event_id).0,
+            participant: pda::participant(event_id, wallet).0,
+        },
+        instruction::GetParticipantBalance {},
+    )
+}
@@ -0,0 +1,37 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use payra::InviteVoucher;
+
+use crate::{ClientError, Result};
+
+// Invite link: a voucher and the issuer's signature over its message, encoded
+// as url-safe base64 so it fits in a url or a QR code. The signature is only
+// checked on-chain, by the ed25519 instruction sent along with `join_event`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SignedInvite {
+    pub voucher: InviteVoucher,
+    pub signature: [u8; 64],
+}
+
+impl SignedInvite {
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::new();
+        // writing into a Vec can't fail
+        let _ = self.voucher.serialize(&mut bytes);
+        bytes.extend_from_slice(&self.signature);
+        BASE64_URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    // accepts the bare code or any link ending with it
+    pub fn decode(link: &str) -> Result<Self> {
+        let code = link.trim().rsplit(['/', '=']).next().unwrap_or_default();
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(code)
+            .map_err(|_| ClientError::InvalidInvite)?;
+        let mut data = bytes.as_slice();
+        let voucher =
+            InviteVoucher::deserialize(&mut data).map_err(|_| ClientError::InvalidInvite)?;
+        let signature = data.try_into().map_err(|_| ClientError::InvalidInvite)?;
+        Ok(Self { voucher, signature })
+    }
+}
@@ -0,0 +1,48 @@
+//! Rust client for the Payra program: PDA derivation, instruction builders
+//! for every program method, account fetch / decoding helpers, simulation
+//! of the view instructions, settlement dry runs, end of event statements
+//! and invite links.
+
+pub mod fetch;
+pub mod instructions;
+pub mod invite;
+pub mod pda;
+pub mod settlement;
+pub mod statement;
+pub mod view;
+
+pub use fetch::*;
+pub use payra::{self, ID as PROGRAM_ID};
+
+use anchor_lang::prelude::Pubkey;
+
+// rpc and anchor errors are boxed, both are a few hundred bytes
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("rpc error: {0}")]
+    Rpc(Box<solana_rpc_client_api::client_error::Error>),
+    #[error("account decoding failed: {0}")]
+    Decode(Box<anchor_lang::error::Error>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+    #[error("simulation returned no data")]
+    NoReturnData,
+    #[error("invalid invite link")]
+    InvalidInvite,
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for ClientError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        ClientError::Rpc(Box::new(err))
+    }
+}
+
+impl From<anchor_lang::error::Error> for ClientError {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        ClientError::Decode(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+
+// Same seeds as the `#[account(seeds = ...)]` constraints of the program.
+
+pub fn event_counter() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"event_counter"], &payra::ID)
+}
+
+pub fn event(event_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"event", event_id.to_le_bytes().as_ref()], &payra::ID)
+}
+
+pub fn participant(event_id: u64, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"participant",
+            event_id.to_le_bytes().as_ref(),
+            wallet.as_ref(),
+        ],
+        &payra::ID,
+    )
+}
+
+pub fn proposal(event: &Pubkey, proposal_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"proposal",
+            event.as_ref(),
+            proposal_id.to_le_bytes().as_ref(),
+        ],
+        &payra::ID,
+    )
+}
+
+pub fn invite(event: &Pubkey, issuer: &Pubkey, invite_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"invite",
+            event.as_ref(),
+            issuer.as_ref(),
+            invite_id.to_le_bytes().as_ref(),
+        ],
+        &payra::ID,
+    )
+}
+
+pub fn session(event: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", event.as_ref(), member.as_ref()], &payra::ID)
+}
+
+pub fn fractional_vault(event: &Pubkey, nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"fractional", event.as_ref(), nft_mint.as_ref()],
+        &payra::ID,
+    )
+}
+
+pub fn share_mint(fractional_vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"shares", fractional_vault.as_ref()], &payra::ID)
+}
+
+// associated token account of the event PDA holding the contributions
+pub fn event_vault(event: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(event, mint)
+}
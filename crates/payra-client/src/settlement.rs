@@ -0,0 +1,17 @@
+use payra::settlement::{dry_run as run, PendingProposals, SettlementOutcome};
+use solana_rpc_client::rpc_client::RpcClient;
+
+use crate::{fetch_event, fetch_participants, fetch_proposals, Result};
+
+// fetches the event with all its proposals and participants and runs the
+// program's settlement engine on them
+pub fn dry_run(
+    rpc: &RpcClient,
+    event_id: u64,
+    pending: PendingProposals,
+) -> Result<SettlementOutcome> {
+    let event = fetch_event(rpc, event_id)?;
+    let proposals = fetch_proposals(rpc, &event)?;
+    let participants = fetch_participants(rpc, &event)?;
+    Ok(run(&event, &proposals, &participants, pending)?)
+}
@@ -0,0 +1,330 @@
+use std::{fmt::Display, io::Write};
+
+use anchor_lang::prelude::Pubkey;
+use payra::{
+    settlement::{dry_run, PendingProposals},
+    Event, Participant, Proposal, ProposalType,
+};
+use serde::{Serialize, Serializer};
+use solana_rpc_client::rpc_client::RpcClient;
+
+use crate::{fetch_event, fetch_participants, fetch_proposals, Result};
+
+// End of event statements built from the event, proposal and participant
+// accounts. Net balances and the transfers that even them out come from the
+// settlement engine, refunds are the transfers paid by the event vault.
+
+const CSV_HEADER: &str = "wallet,entry,proposal_id,title,amount,counterparty";
+
+fn display<S: Serializer>(
+    value: &impl Display,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EventStatement {
+    pub event_id: u64,
+    pub name: String,
+    #[serde(serialize_with = "display")]
+    pub mint: Pubkey,
+    pub status: &'static str,
+    pub target_amount: u64,
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    pub vault_balance: u64,
+    // settled spending proposals, rejected ones paid nothing and milestone
+    // proposals only what they released so far
+    pub spendings: Vec<SpendingStatement>,
+    pub participants: Vec<ParticipantStatement>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SpendingStatement {
+    pub proposal_id: u16,
+    pub title: String,
+    pub amount: u64,
+    pub paid: u64,
+    pub shares: Vec<Share>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Share {
+    #[serde(serialize_with = "display")]
+    pub wallet: Pubkey,
+    pub percentage: u8,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ParticipantStatement {
+    #[serde(serialize_with = "display")]
+    pub wallet: Pubkey,
+    pub contributed: u64,
+    // share of every paid spending
+    pub charges: Vec<Charge>,
+    pub spent: u64,
+    // what settle_event records: positive when the group owes the wallet
+    pub net_owed: i64,
+    // paid back by the event vault
+    pub refund: u64,
+    pub refunded: bool,
+    pub pays: Vec<Payment>,
+    pub receives: Vec<Payment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Charge {
+    pub proposal_id: u16,
+    pub title: String,
+    pub amount: u64,
+}
+
+// transfer between two participants, `wallet` is the other side
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Payment {
+    #[serde(serialize_with = "display")]
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+fn status(event: &Event) -> &'static str {
+    if event.is_cancelled {
+        "cancelled"
+    } else if event.is_finalized {
+        "finalized"
+    } else {
+        "open"
+    }
+}
+
+impl EventStatement {
+    // Participants are the event's contributors, in order of their first
+    // contribution, like settle_event settles them.
+    pub fn build(
+        event: &Event,
+        proposals: &[Proposal],
+        participants: &[Participant],
+    ) -> Result<Self> {
+        let outcome = dry_run(event, proposals, participants, PendingProposals::Ignore)?;
+
+        let mut spendings = Vec::new();
+        for proposal in proposals
+            .iter()
+            .filter(|p| p.settled && p.proposal_type.is_spending())
+        {
+            let milestones = matches!(proposal.proposal_type, ProposalType::Milestones { .. });
+            let paid = if milestones {
+                proposal.released_amount()
+            } else if proposal.cancelled {
+                0
+            } else {
+                proposal.amount
+            };
+            let shares = proposal
+                .spendings
+                .iter()
+                .enumerate()
+                .map(|(index, share)| {
+                    Ok(Share {
+                        wallet: share.wallet,
+                        percentage: share.percentage,
+                        amount: if milestones {
+                            proposal.released_share(index)?
+                        } else if paid > 0 {
+                            proposal.spending_share(index)?
+                        } else {
+                            0
+                        },
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            spendings.push(SpendingStatement {
+                proposal_id: proposal.proposal_id,
+                title: proposal.title.clone(),
+                amount: proposal.amount,
+                paid,
+                shares,
+            });
+        }
+
+        let participants = outcome
+            .balances
+            .iter()
+            .map(|balance| {
+                let wallet = balance.wallet;
+                let transfers = || outcome.transfers.iter();
+                ParticipantStatement {
+                    wallet,
+                    contributed: balance.contributed,
+                    charges: spendings
+                        .iter()
+                        .filter(|s| s.paid > 0)
+                        .flat_map(|s| {
+                            s.shares
+                                .iter()
+                                .filter(|share| share.wallet == wallet)
+                                .map(|share| Charge {
+                                    proposal_id: s.proposal_id,
+                                    title: s.title.clone(),
+                                    amount: share.amount,
+                                })
+                        })
+                        .collect(),
+                    spent: balance.spent,
+                    net_owed: balance.net_owed,
+                    refund: transfers()
+                        .filter(|t| t.from.is_none() && t.to == wallet)
+                        .map(|t| t.amount)
+                        .sum(),
+                    refunded: participants
+                        .iter()
+                        .any(|p| p.wallet == wallet && p.refunded),
+                    pays: transfers()
+                        .filter(|t| t.from == Some(wallet))
+                        .map(|t| Payment {
+                            wallet: t.to,
+                            amount: t.amount,
+                        })
+                        .collect(),
+                    receives: transfers()
+                        .filter_map(|t| {
+                            let from = t.from?;
+                            (t.to == wallet).then_some(Payment {
+                                wallet: from,
+                                amount: t.amount,
+                            })
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            event_id: event.event_id,
+            name: event.name.clone(),
+            mint: event.mint,
+            status: status(event),
+            target_amount: event.target_amount,
+            total_contributed: event.total_contributed,
+            total_spent: event.total_spent,
+            vault_balance: outcome.vault_balance,
+            spendings,
+            participants,
+        })
+    }
+
+    pub fn participant(&self, wallet: &Pubkey) -> Option<&ParticipantStatement> {
+        self.participants.iter().find(|p| p.wallet == *wallet)
+    }
+
+    // one row per spending, then the rows of every participant
+    pub fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "{CSV_HEADER}")?;
+        for spending in &self.spendings {
+            write_row(
+                writer,
+                "",
+                "spending",
+                Some(spending.proposal_id),
+                &spending.title,
+                spending.paid as i128,
+                "",
+            )?;
+        }
+        for participant in &self.participants {
+            participant.write_rows(writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, writer: &mut impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+}
+
+impl ParticipantStatement {
+    pub fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "{CSV_HEADER}")?;
+        self.write_rows(writer)
+    }
+
+    pub fn write_json(&self, writer: &mut impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, self)
+    }
+
+    fn write_rows(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let wallet = self.wallet.to_string();
+        let row = |writer: &mut _, entry, amount, counterparty: &str| {
+            write_row(writer, &wallet, entry, None, "", amount, counterparty)
+        };
+        row(writer, "contribution", self.contributed as i128, "")?;
+        for charge in &self.charges {
+            write_row(
+                writer,
+                &wallet,
+                "share",
+                Some(charge.proposal_id),
+                &charge.title,
+                charge.amount as i128,
+                "",
+            )?;
+        }
+        row(writer, "net_owed", self.net_owed as i128, "")?;
+        if self.refund > 0 {
+            row(writer, "refund", self.refund as i128, "")?;
+        }
+        for payment in &self.pays {
+            row(
+                writer,
+                "pays",
+                payment.amount as i128,
+                &payment.wallet.to_string(),
+            )?;
+        }
+        for payment in &self.receives {
+            row(
+                writer,
+                "receives",
+                payment.amount as i128,
+                &payment.wallet.to_string(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_row(
+    writer: &mut impl Write,
+    wallet: &str,
+    entry: &str,
+    proposal_id: Option<u16>,
+    title: &str,
+    amount: i128,
+    counterparty: &str,
+) -> std::io::Result<()> {
+    let proposal_id = proposal_id.map(|id| id.to_string()).unwrap_or_default();
+    writeln!(
+        writer,
+        "{wallet},{entry},{proposal_id},{},{amount},{counterparty}",
+        csv_field(title)
+    )
+}
+
+// titles are free text, quoted when they contain a separator, a quote or a
+// line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn fetch_statement(rpc: &RpcClient, event_id: u64) -> Result<EventStatement> {
+    let event = fetch_event(rpc, event_id)?;
+    let proposals = fetch_proposals(rpc, &event)?;
+    let participants = fetch_participants(rpc, &event)?;
+    EventStatement::build(&event, &proposals, &participants)
+}
@@ -0,0 +1,77 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, AnchorDeserialize};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use payra::{DuesStatus, EventSummary, ParticipantBalance, SettlementPreview};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_transaction::Transaction;
+
+use crate::{instructions, ClientError, Result};
+
+// Simulates a view instruction and decodes its return data. `payer` only has
+// to exist with enough lamports for the fee, nothing is signed or sent.
+pub fn simulate<T: AnchorDeserialize>(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instruction: Instruction,
+) -> Result<T> {
+    let tx = Transaction::new_with_payer(&[instruction], Some(payer));
+    let result = rpc
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?
+        .value;
+
+    if let Some(err) = result.err {
+        return Err(ClientError::Simulation(err.to_string()));
+    }
+    let return_data = result.return_data.ok_or(ClientError::NoReturnData)?;
+    let bytes = BASE64_STANDARD
+        .decode(return_data.data.0)
+        .map_err(|err| ClientError::Simulation(err.to_string()))?;
+    Ok(T::deserialize(&mut &bytes[..]).map_err(anchor_lang::error::Error::from)?)
+}
+
+pub fn event_summary(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    event_id: u64,
+    proposal_ids: &[u16],
+) -> Result<EventSummary> {
+    simulate(
+        rpc,
+        payer,
+        instructions::get_event_summary(event_id, proposal_ids),
+    )
+}
+
+pub fn settlement_preview(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    event_id: u64,
+) -> Result<SettlementPreview> {
+    let event = crate::fetch_event(rpc, event_id)?;
+    simulate(rpc, payer, instructions::preview_settlement(&event))
+}
+
+pub fn dues_status(rpc: &RpcClient, payer: &Pubkey, event_id: u64) -> Result<DuesStatus> {
+    let event = crate::fetch_event(rpc, event_id)?;
+    simulate(rpc, payer, instructions::get_dues_status(&event))
+}
+
+pub fn participant_balance(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    event_id: u64,
+    wallet: &Pubkey,
+) -> Result<ParticipantBalance> {
+    simulate(
+        rpc,
+        payer,
+        instructions::get_participant_balance(event_id, wallet),
+    )
+}
@@ -0,0 +1,370 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::AccountMeta};
+use anchor_spl::associated_token::get_associated_token_address;
+use payra_client::{
+    instructions,
+    payra::{
+        ContributionRules, Event, EventAccess, MemberRole, MilestoneRelease, Proposal,
+        ProposalType, Session, SpendingShare, EVENT_VERSION, PROPOSAL_VERSION, SESSION_VERSION,
+    },
+    pda, PROGRAM_ID,
+};
+
+fn event() -> Event {
+    Event {
+        version: EVENT_VERSION,
+        event_id: 6,
+        creator: Pubkey::new_unique(),
+        pending_creator: None,
+        mint: Pubkey::new_unique(),
+        withdraw_token_account: Pubkey::new_unique(),
+        pending_withdraw_token_account: None,
+        withdraw_change_available_at: 0,
+        target_amount: 1_000,
+        contribution_rules: ContributionRules::default(),
+        dues: None,
+        access: EventAccess::Whitelist,
+        total_contributed: 0,
+        total_spent: 0,
+        whitelist: vec![Pubkey::new_unique()],
+        roles: vec![MemberRole::Member],
+        contributors: Vec::new(),
+        is_cancelled: false,
+        is_finalized: false,
+        deadline: 1_000,
+        proposal_count: 2,
+        name: "trip".to_string(),
+        bump: 255,
+    }
+}
+
+// rebuilds the address from the seeds the program uses and the returned bump
+fn assert_pda((address, bump): (Pubkey, u8), seeds: &[&[u8]]) {
+    let mut seeds = seeds.to_vec();
+    let bump = [bump];
+    seeds.push(&bump);
+    assert_eq!(
+        Pubkey::create_program_address(&seeds, &PROGRAM_ID).unwrap(),
+        address
+    );
+}
+
+fn signer(meta: &AccountMeta, key: Pubkey, writable: bool) {
+    assert_eq!(meta.pubkey, key);
+    assert!(meta.is_signer);
+    assert_eq!(meta.is_writable, writable);
+}
+
+#[test]
+fn pdas_use_the_program_seeds() {
+    let event = pda::event(42).0;
+    let wallet = Pubkey::new_unique();
+
+    assert_pda(pda::event_counter(), &[b"event_counter"]);
+    assert_pda(pda::event(42), &[b"event", &42u64.to_le_bytes()]);
+    assert_pda(
+        pda::participant(42, &wallet),
+        &[b"participant", &42u64.to_le_bytes(), wallet.as_ref()],
+    );
+    assert_pda(
+        pda::proposal(&event, 3),
+        &[b"proposal", event.as_ref(), &3u16.to_le_bytes()],
+    );
+    assert_pda(
+        pda::invite(&event, &wallet, 9),
+        &[
+            b"invite",
+            event.as_ref(),
+            wallet.as_ref(),
+            &9u64.to_le_bytes(),
+        ],
+    );
+    assert_pda(
+        pda::session(&event, &wallet),
+        &[b"session", event.as_ref(), wallet.as_ref()],
+    );
+
+    let nft_mint = Pubkey::new_unique();
+    let vault = pda::fractional_vault(&event, &nft_mint);
+    assert_pda(vault, &[b"fractional", event.as_ref(), nft_mint.as_ref()]);
+    assert_pda(pda::share_mint(&vault.0), &[b"shares", vault.0.as_ref()]);
+}
+
+#[test]
+fn sponsored_contribute_leaves_rent_to_the_payer() {
+    let (payer, contributor, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = instructions::sponsored_contribute(payer, contributor, 5, mint, 100);
+    let event = pda::event(5).0;
+
+    assert_eq!(ix.program_id, PROGRAM_ID);
+    signer(&ix.accounts[0], contributor, false);
+    assert_eq!(ix.accounts[1].pubkey, mint);
+    assert_eq!(
+        ix.accounts[2].pubkey,
+        get_associated_token_address(&contributor, &mint)
+    );
+    assert_eq!(ix.accounts[3].pubkey, event);
+    assert_eq!(ix.accounts[4].pubkey, pda::event_vault(&event, &mint));
+    signer(&ix.accounts[5], payer, true);
+    assert_eq!(ix.accounts[6].pubkey, pda::participant(5, &contributor).0);
+    assert!(ix.accounts[6].is_writable);
+
+    // the contributor pays for themselves
+    let ix = instructions::contribute(contributor, 5, mint, 100);
+    signer(&ix.accounts[5], contributor, true);
+}
+
+#[test]
+fn set_member_role_reads_the_participant_account() {
+    let (admin, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ix = instructions::set_member_role(admin, 6, wallet, MemberRole::Observer);
+
+    signer(&ix.accounts[0], admin, false);
+    assert_eq!(ix.accounts[1].pubkey, pda::event(6).0);
+    assert_eq!(ix.accounts[2].pubkey, pda::participant(6, &wallet).0);
+    assert!(!ix.accounts[2].is_writable);
+}
+
+#[test]
+fn proposals_take_the_next_proposal_id() {
+    let event = event();
+    let creator = event.creator;
+    let ix =
+        instructions::create_proposal(creator, &event, "food".to_string(), 10, Vec::new(), 500);
+
+    let event_key = pda::event(event.event_id).0;
+    signer(&ix.accounts[0], creator, false);
+    assert_eq!(ix.accounts[1].pubkey, event_key);
+    assert_eq!(ix.accounts[2].pubkey, event.mint);
+    signer(&ix.accounts[3], creator, true);
+    assert_eq!(ix.accounts[4].pubkey, pda::proposal(&event_key, 2).0);
+
+    let ix = instructions::create_settle_proposal(creator, &event, 500);
+    assert_eq!(ix.accounts[4].pubkey, pda::proposal(&event_key, 2).0);
+    let ix = instructions::create_governance_proposal(
+        creator,
+        &event,
+        ProposalType::ExtendDeadline { new_deadline: 900 },
+        500,
+    );
+    assert_eq!(ix.accounts[3].pubkey, pda::proposal(&event_key, 2).0);
+}
+
+#[test]
+fn sponsored_builders_leave_rent_to_the_payer() {
+    let event = event();
+    let (payer, member) = (Pubkey::new_unique(), event.whitelist[0]);
+    let event_key = pda::event(event.event_id).0;
+
+    let ix = instructions::sponsored_create_session(payer, member, 6, Pubkey::new_unique(), 900);
+    signer(&ix.accounts[0], member, false);
+    signer(&ix.accounts[2], payer, true);
+    assert_eq!(ix.accounts[3].pubkey, pda::session(&event_key, &member).0);
+
+    let ix = instructions::sponsored_create_proposal(
+        payer,
+        member,
+        &event,
+        "food".to_string(),
+        10,
+        Vec::new(),
+        500,
+    );
+    signer(&ix.accounts[0], member, false);
+    signer(&ix.accounts[3], payer, true);
+
+    let ix = instructions::sponsored_create_settle_proposal(payer, member, &event, 500);
+    signer(&ix.accounts[0], member, false);
+    signer(&ix.accounts[3], payer, true);
+
+    let ix = instructions::sponsored_create_governance_proposal(
+        payer,
+        member,
+        &event,
+        ProposalType::ChangeTarget {
+            new_target_amount: 2_000,
+        },
+        500,
+    );
+    signer(&ix.accounts[0], member, false);
+    signer(&ix.accounts[2], payer, true);
+
+    let ix = instructions::sponsored_create_milestone_proposal(
+        payer,
+        member,
+        &event,
+        "roof".to_string(),
+        vec![5, 5],
+        MilestoneRelease::Approval,
+        Vec::new(),
+        500,
+    );
+    signer(&ix.accounts[0], member, false);
+    signer(&ix.accounts[2], payer, true);
+}
+
+#[test]
+fn rent_goes_back_to_whoever_paid_it() {
+    let (payer, member) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let event_key = pda::event(6).0;
+    let session = Session {
+        version: SESSION_VERSION,
+        event: event_key,
+        member,
+        session_key: Pubkey::new_unique(),
+        expires_at: 900,
+        rent_payer: payer,
+        bump: 0,
+    };
+    let ix = instructions::revoke_session(&session);
+    signer(&ix.accounts[0], member, false);
+    assert_eq!(ix.accounts[1].pubkey, pda::session(&event_key, &member).0);
+    assert_eq!(ix.accounts[2].pubkey, payer);
+    assert!(ix.accounts[2].is_writable && !ix.accounts[2].is_signer);
+
+    // the claimer owns their share token account, they pay for it
+    let nft_mint = Pubkey::new_unique();
+    let ix = instructions::claim_shares(member, 6, &nft_mint);
+    let vault = pda::fractional_vault(&event_key, &nft_mint).0;
+    let share_mint = pda::share_mint(&vault).0;
+    signer(&ix.accounts[0], member, true);
+    assert_eq!(
+        ix.accounts[5].pubkey,
+        get_associated_token_address(&member, &share_mint)
+    );
+}
+
+#[test]
+fn settle_proposal_passes_every_sharing_participant() {
+    let event = event();
+    let spendings: Vec<SpendingShare> = [event.creator, event.whitelist[0]]
+        .into_iter()
+        .map(|wallet| SpendingShare {
+            wallet,
+            percentage: 50,
+        })
+        .collect();
+    let proposal = Proposal {
+        version: PROPOSAL_VERSION,
+        proposal_id: 1,
+        title: "food".to_string(),
+        proposal_type: ProposalType::Spending,
+        amount: 10,
+        spendings,
+        milestones: Vec::new(),
+        yes_votes: Vec::new(),
+        no_votes: Vec::new(),
+        milestone_approvals: Vec::new(),
+        creator: event.creator,
+        deadline: 500,
+        settled: false,
+        cancelled: false,
+        fractional_vault: None,
+        bump: 255,
+    };
+    let signer_key = Pubkey::new_unique();
+    let ix = instructions::settle_proposal(signer_key, &event, &proposal);
+
+    let event_key = pda::event(event.event_id).0;
+    assert_eq!(
+        ix.accounts[3].pubkey,
+        pda::event_vault(&event_key, &event.mint)
+    );
+    assert_eq!(ix.accounts[4].pubkey, pda::proposal(&event_key, 1).0);
+    assert_eq!(ix.accounts[5].pubkey, event.withdraw_token_account);
+
+    assert_eq!(ix.accounts.len(), 11);
+    let participants = &ix.accounts[9..];
+    for (meta, share) in participants.iter().zip(&proposal.spendings) {
+        assert_eq!(
+            meta.pubkey,
+            pda::participant(event.event_id, &share.wallet).0
+        );
+        assert!(meta.is_writable && !meta.is_signer);
+    }
+}
+
+#[test]
+fn reserve_price_proposals_pass_the_vault() {
+    let event = event();
+    let event_key = pda::event(event.event_id).0;
+    let vault = pda::fractional_vault(&event_key, &Pubkey::new_unique()).0;
+    let proposal_type = ProposalType::ReservePrice { vault, price: 500 };
+
+    let ix =
+        instructions::create_governance_proposal(event.creator, &event, proposal_type.clone(), 500);
+    assert_eq!(ix.accounts[5].pubkey, vault);
+    // other governance proposals leave the optional accounts out
+    let ix = instructions::create_governance_proposal(
+        event.creator,
+        &event,
+        ProposalType::ExtendDeadline { new_deadline: 900 },
+        500,
+    );
+    assert_eq!(ix.accounts[5].pubkey, payra_client::PROGRAM_ID);
+
+    let proposal = Proposal {
+        version: PROPOSAL_VERSION,
+        proposal_id: 2,
+        title: "Set reserve price".to_string(),
+        proposal_type,
+        amount: 0,
+        spendings: Vec::new(),
+        milestones: Vec::new(),
+        yes_votes: Vec::new(),
+        no_votes: Vec::new(),
+        milestone_approvals: Vec::new(),
+        creator: event.creator,
+        deadline: 500,
+        settled: false,
+        cancelled: false,
+        fractional_vault: None,
+        bump: 255,
+    };
+    let ix = instructions::settle_governance_proposal(event.creator, event.event_id, &proposal);
+    assert_eq!(ix.accounts[2].pubkey, pda::proposal(&event_key, 2).0);
+    assert_eq!(ix.accounts[3].pubkey, vault);
+    assert!(ix.accounts[3].is_writable);
+}
+
+#[test]
+fn settle_event_passes_every_contributor() {
+    let mut event = event();
+    let donor = Pubkey::new_unique();
+    // the member never contributed, the donor has no vote
+    event.contributors = vec![event.creator, donor];
+    let ix = instructions::settle_event(event.creator, &event, 1);
+
+    assert_eq!(ix.accounts.len(), 5);
+    for (meta, wallet) in ix.accounts[3..].iter().zip(&event.contributors) {
+        assert_eq!(meta.pubkey, pda::participant(event.event_id, wallet).0);
+        assert!(meta.is_writable && !meta.is_signer);
+    }
+
+    // previews show what settle_event would record
+    let ix = instructions::preview_settlement(&event);
+    assert_eq!(ix.accounts.len(), 3);
+    for (meta, wallet) in ix.accounts[1..].iter().zip(&event.contributors) {
+        assert_eq!(meta.pubkey, pda::participant(event.event_id, wallet).0);
+    }
+}
+
+#[test]
+fn votes_are_signed_by_the_voter_or_their_session_key() {
+    let (voter, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let event_key = pda::event(6).0;
+
+    let ix = instructions::vote(voter, 6, 2, mint, true);
+    signer(&ix.accounts[0], voter, false);
+    assert_eq!(ix.accounts[3].pubkey, pda::proposal(&event_key, 2).0);
+
+    let session_key = Pubkey::new_unique();
+    let ix = instructions::session_vote(session_key, voter, 6, 2, mint, true);
+    assert_eq!(ix.accounts[0].pubkey, voter);
+    assert!(!ix.accounts[0].is_signer && !ix.accounts[0].is_writable);
+    signer(&ix.accounts[7], session_key, false);
+    assert_eq!(ix.accounts[8].pubkey, pda::session(&event_key, &voter).0);
+}
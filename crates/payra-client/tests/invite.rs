@@ -0,0 +1,58 @@
+use anchor_lang::{prelude::Pubkey, solana_program::ed25519_program};
+use payra_client::{
+    instructions,
+    invite::SignedInvite,
+    payra::{logic::ed25519_signed_message, InviteVoucher, MemberRole},
+    pda, ClientError,
+};
+
+fn invite() -> SignedInvite {
+    SignedInvite {
+        voucher: InviteVoucher {
+            event: pda::event(3).0,
+            issuer: Pubkey::new_unique(),
+            invite_id: 7,
+            role: MemberRole::Treasurer,
+            max_uses: 5,
+            expires_at: 1_767_225_600,
+        },
+        signature: [9; 64],
+    }
+}
+
+#[test]
+fn invite_links_roundtrip() {
+    let invite = invite();
+    let code = invite.encode();
+
+    assert!(SignedInvite::decode(&code).unwrap() == invite);
+    let link = format!("https://payra.app/join?invite={code}");
+    assert!(SignedInvite::decode(&link).unwrap() == invite);
+}
+
+#[test]
+fn rejects_truncated_links() {
+    let code = invite().encode();
+    assert!(matches!(
+        SignedInvite::decode(&code[..code.len() - 4]),
+        Err(ClientError::InvalidInvite)
+    ));
+}
+
+#[test]
+fn join_verifies_the_voucher_first() {
+    let invite = invite();
+    let member = Pubkey::new_unique();
+    let [verify, join] = instructions::join_event(member, &invite).unwrap();
+
+    assert_eq!(verify.program_id, ed25519_program::ID);
+    let (issuer, message) = ed25519_signed_message(&verify.data).unwrap();
+    assert_eq!(issuer, invite.voucher.issuer.as_ref());
+    assert_eq!(message, invite.voucher.message().unwrap());
+
+    assert_eq!(join.accounts[0].pubkey, member);
+    assert_eq!(
+        join.accounts[3].pubkey,
+        pda::invite(&invite.voucher.event, &invite.voucher.issuer, 7).0
+    );
+}
@@ -0,0 +1,229 @@
+use anchor_lang::prelude::Pubkey;
+use payra_client::{
+    payra::{
+        ContributionRules, Event, EventAccess, MemberRole, Milestone, MilestoneRelease,
+        Participant, Proposal, ProposalType, SpendingShare, EVENT_VERSION, PARTICIPANT_VERSION,
+        PROPOSAL_VERSION,
+    },
+    pda,
+    statement::{EventStatement, Payment},
+};
+
+const EVENT_ID: u64 = 3;
+
+struct Group {
+    creator: Pubkey,
+    member: Pubkey,
+    event: Event,
+}
+
+impl Group {
+    fn new() -> Self {
+        let creator = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let event = Event {
+            version: EVENT_VERSION,
+            event_id: EVENT_ID,
+            creator,
+            pending_creator: None,
+            mint: Pubkey::new_unique(),
+            withdraw_token_account: Pubkey::new_unique(),
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
+            target_amount: 1_000,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
+            total_contributed: 1_000,
+            total_spent: 600,
+            whitelist: vec![member],
+            roles: vec![MemberRole::Member],
+            contributors: vec![creator, member],
+            is_cancelled: false,
+            is_finalized: false,
+            deadline: 100,
+            proposal_count: 3,
+            name: "trip".to_string(),
+            bump: pda::event(EVENT_ID).1,
+        };
+        Self {
+            creator,
+            member,
+            event,
+        }
+    }
+
+    fn spending(&self, proposal_id: u16, title: &str, amount: u64, cancelled: bool) -> Proposal {
+        Proposal {
+            version: PROPOSAL_VERSION,
+            proposal_id,
+            title: title.to_string(),
+            proposal_type: ProposalType::Spending,
+            amount,
+            spendings: vec![
+                SpendingShare {
+                    wallet: self.creator,
+                    percentage: 50,
+                },
+                SpendingShare {
+                    wallet: self.member,
+                    percentage: 50,
+                },
+            ],
+            milestones: Vec::new(),
+            yes_votes: Vec::new(),
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: self.creator,
+            deadline: 200,
+            settled: true,
+            cancelled,
+            fractional_vault: None,
+            bump: 0,
+        }
+    }
+
+    fn participant(&self, wallet: Pubkey, contributed: u64, spent: u64) -> Participant {
+        Participant {
+            version: PARTICIPANT_VERSION,
+            event: pda::event(EVENT_ID).0,
+            event_id: EVENT_ID,
+            wallet,
+            contributed,
+            spent,
+            refunded: false,
+            bump: pda::participant(EVENT_ID, &wallet).1,
+            net_owed: 0,
+            periods_paid: 0,
+        }
+    }
+
+    // creator put in 900 and the member 100, a paid dinner of 600 and a
+    // rejected hotel, both split in half; an open proposal is left out
+    fn statement(&self) -> EventStatement {
+        let mut open = self.spending(2, "museum", 100, false);
+        open.settled = false;
+        let proposals = [
+            self.spending(0, "dinner, drinks", 600, false),
+            self.spending(1, "hotel", 800, true),
+            open,
+        ];
+        let participants = [
+            self.participant(self.creator, 900, 300),
+            self.participant(self.member, 100, 300),
+        ];
+        EventStatement::build(&self.event, &proposals, &participants).unwrap()
+    }
+}
+
+#[test]
+fn statement_lists_settled_spendings_and_balances() {
+    let group = Group::new();
+    let statement = group.statement();
+
+    assert_eq!(statement.vault_balance, 400);
+    let paid: Vec<(u16, u64)> = statement
+        .spendings
+        .iter()
+        .map(|s| (s.proposal_id, s.paid))
+        .collect();
+    assert_eq!(paid, [(0, 600), (1, 0)]);
+
+    let creator = statement.participant(&group.creator).unwrap();
+    assert_eq!(creator.charges.len(), 1);
+    assert_eq!(creator.charges[0].amount, 300);
+    assert_eq!(creator.net_owed, 600);
+    // the vault holds 400 of the 600 owed, the member pays the rest
+    assert_eq!(creator.refund, 400);
+    assert_eq!(
+        creator.receives,
+        [Payment {
+            wallet: group.member,
+            amount: 200
+        }]
+    );
+
+    let member = statement.participant(&group.member).unwrap();
+    assert_eq!(member.net_owed, -200);
+    assert_eq!(member.refund, 0);
+    assert_eq!(
+        member.pays,
+        [Payment {
+            wallet: group.creator,
+            amount: 200
+        }]
+    );
+    assert!(statement.participant(&Pubkey::new_unique()).is_none());
+}
+
+#[test]
+fn statement_charges_released_milestones_only() {
+    let group = Group::new();
+    let mut proposal = group.spending(0, "renovation", 300, false);
+    proposal.proposal_type = ProposalType::Milestones {
+        release: MilestoneRelease::Approval,
+    };
+    proposal.milestones = [101, 100, 99]
+        .into_iter()
+        .map(|amount| Milestone {
+            amount,
+            released: amount == 101,
+        })
+        .collect();
+    let participants = [
+        group.participant(group.creator, 900, 51),
+        group.participant(group.member, 100, 50),
+    ];
+    let statement =
+        EventStatement::build(&group.event, &[proposal.clone()], &participants).unwrap();
+    let shares: Vec<u64> = statement.spendings[0]
+        .shares
+        .iter()
+        .map(|s| s.amount)
+        .collect();
+    assert_eq!(statement.spendings[0].paid, 101);
+    assert_eq!(shares, [51, 50]);
+
+    // cancelling keeps what was released, the rest never left the vault
+    proposal.cancelled = true;
+    let statement = EventStatement::build(&group.event, &[proposal], &participants).unwrap();
+    assert_eq!(statement.spendings[0].paid, 101);
+}
+
+#[test]
+fn statement_exports_csv_and_json() {
+    let group = Group::new();
+    let statement = group.statement();
+
+    let mut csv = Vec::new();
+    statement
+        .participant(&group.member)
+        .unwrap()
+        .write_csv(&mut csv)
+        .unwrap();
+    let member = group.member.to_string();
+    let creator = group.creator.to_string();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        format!(
+            "wallet,entry,proposal_id,title,amount,counterparty\n\
+             {member},contribution,,,100,\n\
+             {member},share,0,\"dinner, drinks\",300,\n\
+             {member},net_owed,,,-200,\n\
+             {member},pays,,,200,{creator}\n"
+        )
+    );
+
+    let mut csv = Vec::new();
+    statement.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.contains(",spending,1,hotel,0,\n"));
+    assert!(csv.contains(&format!("{creator},refund,,,400,\n")));
+
+    let mut json = Vec::new();
+    statement.write_json(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    // contributors order, the creator contributed first
+    assert_eq!(json["participants"][0]["wallet"], creator);
+    assert_eq!(json["spendings"][0]["shares"][1]["amount"], 300);
+}
@@ -0,0 +1,604 @@
+use std::time::{Duration, Instant};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, instruction::Instruction,
+        program_option::COption, program_pack::Pack,
+    },
+    system_program, AccountDeserialize,
+};
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use payra::{
+    settlement::{dry_run, PendingProposals, SettlementOutcome},
+    Event, EventArgs, EventCounter, Participant, Proposal, SpendingShare,
+};
+use payra_client::{decode, instructions, pda};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, clock::Clock, compute_budget::ComputeBudgetInstruction,
+    native_token::LAMPORTS_PER_SOL, signature::Keypair, signer::Signer, transaction::Transaction,
+};
+
+use crate::{Action, ACTORS, INITIAL_BALANCE};
+
+const BLOCKHASH_REFRESH: Duration = Duration::from_secs(20);
+
+// anchor ties the account infos to the 'info lifetime of its entrypoint
+fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&[AccountInfo], &[AccountInfo]>(accounts) };
+    payra::entry(program_id, accounts, data)
+}
+
+// what the harness saw happen to an event, independently of the program
+struct EventModel {
+    id: u64,
+    key: Pubkey,
+    creator: usize,
+    // tokens that left contributor accounts
+    contributed_in: u64,
+    // tokens that reached the withdraw account
+    paid_out: u64,
+    // vault balance when the event was first seen cancelled
+    cancelled_vault: Option<u64>,
+}
+
+pub struct Harness {
+    ctx: ProgramTestContext,
+    actors: Vec<Keypair>,
+    withdraw_accounts: Vec<Pubkey>,
+    mint: Pubkey,
+    events: Vec<EventModel>,
+    now: i64,
+    // makes every transaction unique, identical ones would be deduplicated
+    nonce: u64,
+    blockhash_at: Instant,
+    // set by a step when the program did something the dry run didn't predict
+    violation: Option<String>,
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+impl Harness {
+    pub async fn start() -> Self {
+        let mut program = ProgramTest::default();
+        program.prefer_bpf(false);
+        program.add_program("payra", payra::ID, processor!(process));
+
+        let actors: Vec<Keypair> = (0..ACTORS).map(|_| Keypair::new()).collect();
+        let mint = Pubkey::new_unique();
+
+        let mut mint_data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(actors[0].pubkey()),
+            supply: INITIAL_BALANCE * ACTORS as u64,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut mint_data);
+        program.add_account(
+            mint,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: mint_data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let mut withdraw_accounts = Vec::new();
+        for actor in actors.iter() {
+            program.add_account(
+                actor.pubkey(),
+                Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+            );
+            program.add_account(
+                get_associated_token_address(&actor.pubkey(), &mint),
+                token_account(mint, actor.pubkey(), INITIAL_BALANCE),
+            );
+            // kept apart from the contributing account so payouts are visible
+            let withdraw = Pubkey::new_unique();
+            program.add_account(withdraw, token_account(mint, actor.pubkey(), 0));
+            withdraw_accounts.push(withdraw);
+        }
+
+        let ctx = program.start_with_context().await;
+        let now = ctx
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .expect("clock")
+            .unix_timestamp;
+
+        let mut harness = Self {
+            ctx,
+            actors,
+            withdraw_accounts,
+            mint,
+            events: Vec::new(),
+            now,
+            nonce: 0,
+            blockhash_at: Instant::now(),
+            violation: None,
+        };
+        let admin = harness.actors[0].pubkey();
+        assert!(
+            harness.send(0, instructions::initialize(admin)).await,
+            "initialize failed"
+        );
+        harness
+    }
+
+    pub fn wallet(&self, actor: usize) -> Pubkey {
+        self.actors[actor].pubkey()
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        self.mint
+    }
+
+    // the token account payouts of events created by `actor` go to
+    pub fn withdraw_account(&self, actor: usize) -> Pubkey {
+        self.withdraw_accounts[actor]
+    }
+
+    // writes an account as is, for states no instruction creates anymore
+    pub fn set_account(&mut self, key: &Pubkey, account: Account) {
+        self.ctx.set_account(key, &account.into());
+    }
+
+    // token account of `owner` holding `amount` of the harness mint
+    pub fn set_token_account(&mut self, key: &Pubkey, owner: &Pubkey, amount: u64) {
+        let account = token_account(self.mint, *owner, amount);
+        self.set_account(key, account);
+    }
+
+    // false when the program rejected the instruction
+    pub async fn send(&mut self, signer: usize, instruction: Instruction) -> bool {
+        // the bank registers a blockhash every slot, ours expires after 150
+        if self.blockhash_at.elapsed() > BLOCKHASH_REFRESH {
+            self.ctx.last_blockhash = self
+                .ctx
+                .get_new_latest_blockhash()
+                .await
+                .expect("blockhash");
+            self.blockhash_at = Instant::now();
+        }
+        self.nonce += 1;
+        let signer = &self.actors[signer];
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(self.nonce),
+                instruction,
+            ],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.ctx.last_blockhash,
+        );
+        match self.ctx.banks_client.process_transaction(tx).await {
+            Ok(()) => true,
+            Err(BanksClientError::TransactionError(_)) => false,
+            Err(err) => panic!("bank failure: {err}"),
+        }
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, key: Pubkey) -> Option<T> {
+        let account = self.ctx.banks_client.get_account(key).await.ok()??;
+        decode(&account.data).ok()
+    }
+
+    pub async fn token_balance(&mut self, key: Pubkey) -> u64 {
+        match self.ctx.banks_client.get_account(key).await {
+            Ok(Some(account)) => spl_token::state::Account::unpack(&account.data)
+                .map(|a| a.amount)
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn actor(&self, index: u8) -> usize {
+        index as usize % ACTORS
+    }
+
+    fn event_index(&self, index: u8) -> Option<usize> {
+        (!self.events.is_empty()).then(|| index as usize % self.events.len())
+    }
+
+    async fn event(&mut self, index: usize) -> Event {
+        let key = self.events[index].key;
+        self.account(key).await.expect("event account")
+    }
+
+    async fn proposal(&mut self, event: &Event, index: u8) -> Option<Proposal> {
+        if event.proposal_count == 0 {
+            return None;
+        }
+        let id = index as u16 % event.proposal_count;
+        let key = pda::proposal(&pda::event(event.event_id).0, id).0;
+        self.account(key).await
+    }
+
+    // the settlement engine run on the accounts as they are now
+    async fn dry_run(
+        &mut self,
+        event: &Event,
+        pending: PendingProposals<'_>,
+    ) -> Result<SettlementOutcome, String> {
+        let mut proposals = Vec::new();
+        for id in 0..event.proposal_count {
+            let key = pda::proposal(&pda::event(event.event_id).0, id).0;
+            proposals.extend(self.account::<Proposal>(key).await);
+        }
+        let mut participants = Vec::new();
+        for wallet in event.contributors.iter() {
+            let key = pda::participant(event.event_id, wallet).0;
+            participants.extend(self.account::<Participant>(key).await);
+        }
+        dry_run(event, &proposals, &participants, pending).map_err(|err| err.to_string())
+    }
+
+    pub async fn step(&mut self, action: &Action) {
+        match *action {
+            Action::CreateEvent {
+                actor,
+                target,
+                deadline_in,
+            } => {
+                let actor = self.actor(actor);
+                let counter: EventCounter = self
+                    .account(pda::event_counter().0)
+                    .await
+                    .expect("event counter");
+                let id = counter.count;
+                let ix = instructions::create_event(
+                    self.actors[actor].pubkey(),
+                    id,
+                    self.mint,
+                    self.withdraw_accounts[actor],
+                    EventArgs {
+                        name: format!("event {id}"),
+                        deadline: self.now + deadline_in as i64,
+                        target_amount: target as u64,
+                    },
+                );
+                if self.send(actor, ix).await {
+                    self.events.push(EventModel {
+                        id,
+                        key: pda::event(id).0,
+                        creator: actor,
+                        contributed_in: 0,
+                        paid_out: 0,
+                        cancelled_vault: None,
+                    });
+                }
+            }
+            Action::Whitelist { event, members } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let creator = self.events[event].creator;
+                let wallets = (0..ACTORS)
+                    .filter(|i| members & (1 << i) != 0)
+                    .map(|i| self.actors[i].pubkey())
+                    .collect();
+                let ix = instructions::whitelist(
+                    self.actors[creator].pubkey(),
+                    self.events[event].id,
+                    wallets,
+                );
+                self.send(creator, ix).await;
+            }
+            Action::Contribute {
+                event,
+                actor,
+                amount,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let actor = self.actor(actor);
+                let wallet = self.actors[actor].pubkey();
+                let ata = get_associated_token_address(&wallet, &self.mint);
+                let ix = instructions::contribute(
+                    wallet,
+                    self.events[event].id,
+                    self.mint,
+                    amount as u64,
+                );
+                let before = self.token_balance(ata).await;
+                if self.send(actor, ix).await {
+                    let after = self.token_balance(ata).await;
+                    self.events[event].contributed_in += before - after;
+                }
+            }
+            Action::CreateProposal {
+                event,
+                actor,
+                amount,
+                shares,
+                deadline_in,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let wallets: Vec<Pubkey> = (0..ACTORS)
+                    .filter(|i| shares & (1 << i) != 0)
+                    .map(|i| self.actors[i].pubkey())
+                    .collect();
+                if wallets.is_empty() {
+                    return;
+                }
+                let even = 100 / wallets.len() as u8;
+                let spendings = wallets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, wallet)| SpendingShare {
+                        wallet: *wallet,
+                        percentage: if i == 0 {
+                            100 - even * (wallets.len() as u8 - 1)
+                        } else {
+                            even
+                        },
+                    })
+                    .collect();
+                let actor = self.actor(actor);
+                let state = self.event(event).await;
+                let ix = instructions::create_proposal(
+                    self.actors[actor].pubkey(),
+                    &state,
+                    format!("proposal {}", state.proposal_count),
+                    amount as u64,
+                    spendings,
+                    self.now + deadline_in as i64,
+                );
+                self.send(actor, ix).await;
+            }
+            Action::Vote {
+                event,
+                proposal,
+                actor,
+                approve,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let state = self.event(event).await;
+                let Some(proposal) = self.proposal(&state, proposal).await else {
+                    return;
+                };
+                let actor = self.actor(actor);
+                let ix = instructions::vote(
+                    self.actors[actor].pubkey(),
+                    state.event_id,
+                    proposal.proposal_id,
+                    self.mint,
+                    approve,
+                );
+                self.send(actor, ix).await;
+            }
+            Action::SettleProposal {
+                event,
+                proposal,
+                actor,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let state = self.event(event).await;
+                let Some(proposal) = self.proposal(&state, proposal).await else {
+                    return;
+                };
+                let actor = self.actor(actor);
+                let ix =
+                    instructions::settle_proposal(self.actors[actor].pubkey(), &state, &proposal);
+                let id = [proposal.proposal_id];
+                let expected = self.dry_run(&state, PendingProposals::Assume(&id)).await;
+                let before = self.token_balance(state.withdraw_token_account).await;
+                if self.send(actor, ix).await {
+                    let after = self.token_balance(state.withdraw_token_account).await;
+                    self.events[event].paid_out += after - before;
+
+                    // a rejected proposal pays nothing, there's nothing to compare
+                    let key = pda::proposal(&self.events[event].key, proposal.proposal_id).0;
+                    let settled: Option<Proposal> = self.account(key).await;
+                    if settled.is_some_and(|p| !p.cancelled) {
+                        let state = self.event(event).await;
+                        let actual = self.dry_run(&state, PendingProposals::Ignore).await;
+                        self.check_dry_run(state.event_id, expected, actual);
+                    }
+                }
+            }
+            Action::CreateSettleProposal {
+                event,
+                actor,
+                deadline_in,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let state = self.event(event).await;
+                let actor = self.actor(actor);
+                let ix = instructions::create_settle_proposal(
+                    self.actors[actor].pubkey(),
+                    &state,
+                    self.now + deadline_in as i64,
+                );
+                self.send(actor, ix).await;
+            }
+            Action::SettleEvent {
+                event,
+                proposal,
+                actor,
+            } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let state = self.event(event).await;
+                let Some(proposal) = self.proposal(&state, proposal).await else {
+                    return;
+                };
+                let actor = self.actor(actor);
+                let ix = instructions::settle_event(
+                    self.actors[actor].pubkey(),
+                    &state,
+                    proposal.proposal_id,
+                );
+                let expected = self.dry_run(&state, PendingProposals::Ignore).await;
+                if self.send(actor, ix).await {
+                    let mut recorded = Vec::new();
+                    for wallet in state.contributors.iter() {
+                        let key = pda::participant(state.event_id, wallet).0;
+                        let participant: Participant =
+                            self.account(key).await.expect("participant account");
+                        recorded.push((participant.wallet, participant.net_owed));
+                    }
+                    let predicted = expected.map(|outcome| {
+                        outcome
+                            .balances
+                            .iter()
+                            .map(|b| (b.wallet, b.net_owed))
+                            .collect::<Vec<_>>()
+                    });
+                    if predicted.as_ref() != Ok(&recorded) {
+                        self.violation = Some(format!(
+                            "event {}: settle_event recorded {recorded:?}, dry run predicted \
+                             {predicted:?}",
+                            state.event_id
+                        ));
+                    }
+                }
+            }
+            Action::CloseEvent { event, actor } => {
+                let Some(event) = self.event_index(event) else {
+                    return;
+                };
+                let actor = self.actor(actor);
+                let ix = instructions::close_event(
+                    self.actors[actor].pubkey(),
+                    self.events[event].id,
+                    self.mint,
+                );
+                self.send(actor, ix).await;
+            }
+            Action::Warp { seconds } => {
+                self.now += seconds as i64;
+                let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.expect("clock");
+                clock.unix_timestamp = self.now;
+                self.ctx.set_sysvar(&clock);
+            }
+        }
+    }
+
+    // a settled proposal must leave the balances its dry run predicted
+    fn check_dry_run(
+        &mut self,
+        event_id: u64,
+        expected: Result<SettlementOutcome, String>,
+        actual: Result<SettlementOutcome, String>,
+    ) {
+        let matches = match (&expected, &actual) {
+            (Ok(expected), Ok(actual)) => {
+                expected.balances == actual.balances
+                    && expected.vault_balance == actual.vault_balance
+            }
+            _ => false,
+        };
+        if !matches {
+            self.violation = Some(format!(
+                "event {event_id}: settle_proposal left {actual:?}, dry run predicted {expected:?}"
+            ));
+        }
+    }
+
+    // - the vault holds exactly what was contributed minus what was paid out
+    // - every token counted once: participant totals add up to the event's
+    // - a cancelled event never loses funds (there is no refund instruction)
+    // - settle_proposal and settle_event record what the dry run predicted
+    pub async fn check_invariants(&mut self) -> Result<(), String> {
+        if let Some(violation) = self.violation.take() {
+            return Err(violation);
+        }
+        let wallets: Vec<Pubkey> = self.actors.iter().map(|a| a.pubkey()).collect();
+
+        for index in 0..self.events.len() {
+            let event = self.event(index).await;
+            let vault = self
+                .token_balance(pda::event_vault(&self.events[index].key, &self.mint))
+                .await;
+            let model = &self.events[index];
+            let id = model.id;
+
+            if event.total_contributed != model.contributed_in {
+                return Err(format!(
+                    "event {id}: total_contributed {} but {} tokens were transferred in",
+                    event.total_contributed, model.contributed_in
+                ));
+            }
+            if event.total_spent != model.paid_out {
+                return Err(format!(
+                    "event {id}: total_spent {} but {} tokens were paid out",
+                    event.total_spent, model.paid_out
+                ));
+            }
+            if Some(vault) != model.contributed_in.checked_sub(model.paid_out) {
+                return Err(format!(
+                    "event {id}: vault holds {vault}, expected {} contributed - {} paid out",
+                    model.contributed_in, model.paid_out
+                ));
+            }
+            if event.is_cancelled {
+                match model.cancelled_vault {
+                    Some(cancelled) if cancelled != vault => {
+                        return Err(format!(
+                            "event {id}: vault went from {cancelled} to {vault} after cancellation"
+                        ));
+                    }
+                    Some(_) => {}
+                    None => self.events[index].cancelled_vault = Some(vault),
+                }
+            }
+
+            let (mut contributed, mut spent) = (0u64, 0u64);
+            for wallet in wallets.iter() {
+                let key = pda::participant(id, wallet).0;
+                if let Some(participant) = self.account::<Participant>(key).await {
+                    contributed += participant.contributed;
+                    spent += participant.spent;
+                    // settle_event only takes the recorded contributors
+                    if !event.contributors.contains(wallet) {
+                        return Err(format!(
+                            "event {id}: {wallet} contributed but isn't a contributor"
+                        ));
+                    }
+                }
+            }
+            if contributed != event.total_contributed || spent != event.total_spent {
+                return Err(format!(
+                    "event {id}: participants hold {contributed} contributed / {spent} spent, \
+                     event records {} / {}",
+                    event.total_contributed, event.total_spent
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Fuzz harness for Payra: runs arbitrary sequences of instructions against
+//! the program in an in-process bank and checks the accounting invariants
+//! after every step.
+//!
+//! Instructions are allowed to fail, the invariants must hold whatever the
+//! program accepted or rejected.
+
+mod harness;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+pub use harness::Harness;
+
+// signers of the sequence, actor 0 also initializes the event counter
+pub const ACTORS: usize = 4;
+
+// tokens minted to each actor at the start
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+
+// Indexes pick an actor, an event or a proposal modulo the number that exist,
+// so most generated sequences stay meaningful.
+#[derive(Arbitrary, Clone, Debug)]
+pub enum Action {
+    CreateEvent {
+        actor: u8,
+        target: u16,
+        deadline_in: u16,
+    },
+    Whitelist {
+        event: u8,
+        // bit i adds actor i
+        members: u8,
+    },
+    Contribute {
+        event: u8,
+        actor: u8,
+        amount: u16,
+    },
+    CreateProposal {
+        event: u8,
+        actor: u8,
+        amount: u16,
+        // bit i gives actor i a share, percentages split evenly
+        shares: u8,
+        deadline_in: u16,
+    },
+    Vote {
+        event: u8,
+        proposal: u8,
+        actor: u8,
+        approve: bool,
+    },
+    SettleProposal {
+        event: u8,
+        proposal: u8,
+        actor: u8,
+    },
+    CreateSettleProposal {
+        event: u8,
+        actor: u8,
+        deadline_in: u16,
+    },
+    SettleEvent {
+        event: u8,
+        proposal: u8,
+        actor: u8,
+    },
+    CloseEvent {
+        event: u8,
+        actor: u8,
+    },
+    // moves the clock forward
+    Warp {
+        seconds: u16,
+    },
+}
+
+// decodes a sequence of actions from raw fuzzer input and runs it, panicking
+// on the first broken invariant
+pub fn run(data: &[u8]) {
+    let mut input = Unstructured::new(data);
+    let mut actions = Vec::new();
+    while !input.is_empty() {
+        match Action::arbitrary(&mut input) {
+            Ok(action) => actions.push(action),
+            Err(_) => break,
+        }
+    }
+    run_actions(&actions);
+}
+
+pub fn run_actions(actions: &[Action]) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime");
+    runtime.block_on(async {
+        let mut harness = Harness::start().await;
+        for action in actions {
+            harness.step(action).await;
+            if let Err(violation) = harness.check_invariants().await {
+                panic!("{violation}\nafter {action:?}\nsequence: {actions:#?}");
+            }
+        }
+    });
+}
@@ -0,0 +1,203 @@
+use std::future::Future;
+
+use anchor_lang::{
+    prelude::{borsh::to_vec, Pubkey, Rent},
+    AnchorSerialize, Discriminator, Space,
+};
+use payra::{
+    Event, EventV0, Participant, ParticipantV0, Proposal, ProposalTypeV0, ProposalV0,
+    SpendingShare, EVENT_VERSION, PROPOSAL_VERSION,
+};
+use payra_client::{instructions, pda};
+use payra_fuzz::Harness;
+use solana_sdk::account::Account;
+
+const EVENT_ID: u64 = 100;
+
+fn run(test: impl Future<Output = ()>) {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime")
+        .block_on(test);
+}
+
+// account as the program stored it before versioning: allocated at the full
+// v0 size, the tail zeroed
+fn stored_v0(discriminator: &[u8], body: &impl AnchorSerialize, space: usize) -> Account {
+    let mut data = discriminator.to_vec();
+    data.extend(to_vec(body).unwrap());
+    data.resize(8 + space, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: payra::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+// v0 event of actor 0 with actors 1 and 2 whitelisted, `contributed` in its
+// vault before the spendings were paid out
+fn baseline_event(harness: &mut Harness, contributed: u64, spent: u64) -> (Pubkey, EventV0) {
+    let (key, bump) = pda::event(EVENT_ID);
+    let event = EventV0 {
+        event_id: EVENT_ID,
+        creator: harness.wallet(0),
+        withdraw_token_account: harness.withdraw_account(0),
+        target_amount: contributed,
+        total_contributed: contributed,
+        // v0 settle_proposal never recorded it
+        total_spent: 0,
+        whitelist: vec![harness.wallet(1), harness.wallet(2)],
+        is_cancelled: false,
+        is_finalized: false,
+        deadline: 0,
+        proposal_count: 2,
+        name: "trip".to_string(),
+        bump,
+    };
+    harness.set_account(
+        &key,
+        stored_v0(Event::DISCRIMINATOR, &event, EventV0::INIT_SPACE),
+    );
+    let vault = pda::event_vault(&key, &harness.mint());
+    harness.set_token_account(&vault, &key, contributed - spent);
+    (key, event)
+}
+
+// v0 created the participant account on the first contribution
+fn baseline_participant(harness: &mut Harness, event: &Pubkey, actor: usize, contributed: u64) {
+    let wallet = harness.wallet(actor);
+    let (key, bump) = pda::participant(EVENT_ID, &wallet);
+    let participant = ParticipantV0 {
+        event: *event,
+        event_id: EVENT_ID,
+        wallet,
+        contributed,
+        spent: 0,
+        refunded: false,
+        bump,
+        net_owed: 0,
+    };
+    harness.set_account(
+        &key,
+        stored_v0(
+            Participant::DISCRIMINATOR,
+            &participant,
+            ParticipantV0::INIT_SPACE,
+        ),
+    );
+}
+
+fn baseline_spending(
+    harness: &mut Harness,
+    event: &Pubkey,
+    proposal_id: u16,
+    amount: u64,
+    cancelled: bool,
+) {
+    let (key, bump) = pda::proposal(event, proposal_id);
+    let creator = harness.wallet(0);
+    let proposal = ProposalV0 {
+        proposal_id,
+        title: "hotel".to_string(),
+        proposal_type: ProposalTypeV0::Spending,
+        amount,
+        spendings: vec![SpendingShare {
+            wallet: creator,
+            percentage: 100,
+        }],
+        yes_votes: vec![creator],
+        no_votes: Vec::new(),
+        creator,
+        deadline: 0,
+        settled: true,
+        cancelled,
+        bump,
+    };
+    harness.set_account(
+        &key,
+        stored_v0(Proposal::DISCRIMINATOR, &proposal, ProposalV0::INIT_SPACE),
+    );
+}
+
+#[test]
+fn migrated_spendings_count_in_total_spent() {
+    run(async {
+        let mut harness = Harness::start().await;
+        let (event, baseline) = baseline_event(&mut harness, 1_000, 300);
+        baseline_spending(&mut harness, &event, 0, 300, false);
+        baseline_spending(&mut harness, &event, 1, 200, true);
+
+        // proposals wait for their event to be migrated
+        let payer = harness.wallet(3);
+        let migrate_proposal = instructions::migrate_proposal(payer, EVENT_ID, 0);
+        assert!(!harness.send(3, migrate_proposal.clone()).await);
+
+        let migrate_event = instructions::migrate_event(payer, &baseline);
+        assert!(harness.send(3, migrate_event).await);
+        assert!(harness.send(3, migrate_proposal.clone()).await);
+        // the rejected spending never left the vault
+        let migrate_rejected = instructions::migrate_proposal(payer, EVENT_ID, 1);
+        assert!(harness.send(3, migrate_rejected).await);
+
+        let state: Event = harness.account(event).await.unwrap();
+        assert_eq!(state.version, EVENT_VERSION);
+        assert_eq!(state.total_spent, 300);
+        let vault = pda::event_vault(&event, &harness.mint());
+        assert_eq!(
+            harness.token_balance(vault).await,
+            state.total_contributed - state.total_spent
+        );
+        let proposal: Proposal = harness.account(pda::proposal(&event, 0).0).await.unwrap();
+        assert_eq!(proposal.version, PROPOSAL_VERSION);
+
+        // a proposal is only counted once
+        assert!(!harness.send(3, migrate_proposal).await);
+        let state: Event = harness.account(event).await.unwrap();
+        assert_eq!(state.total_spent, 300);
+    });
+}
+
+#[test]
+fn migrated_events_settle_the_voters_who_contributed() {
+    run(async {
+        let mut harness = Harness::start().await;
+        let (event, baseline) = baseline_event(&mut harness, 1_000, 0);
+        // actor 2 was whitelisted but never contributed
+        baseline_participant(&mut harness, &event, 0, 600);
+        baseline_participant(&mut harness, &event, 1, 400);
+
+        let payer = harness.wallet(3);
+        let migrate_event = instructions::migrate_event(payer, &baseline);
+        assert!(harness.send(3, migrate_event).await);
+        for actor in [0, 1] {
+            let key = pda::participant(EVENT_ID, &harness.wallet(actor)).0;
+            let migrate_participant = instructions::migrate_participant(payer, key);
+            assert!(harness.send(3, migrate_participant).await);
+        }
+
+        let state: Event = harness.account(event).await.unwrap();
+        assert_eq!(state.contributors, [harness.wallet(1), harness.wallet(0)]);
+
+        let proposal_id = state.proposal_count;
+        let creator = harness.wallet(0);
+        let ix = instructions::create_settle_proposal(creator, &state, 4_000_000_000);
+        assert!(harness.send(0, ix).await);
+        for actor in 0..3 {
+            let voter = harness.wallet(actor);
+            let ix = instructions::vote(voter, EVENT_ID, proposal_id, harness.mint(), true);
+            assert!(harness.send(actor, ix).await);
+        }
+
+        let state: Event = harness.account(event).await.unwrap();
+        let ix = instructions::settle_event(creator, &state, proposal_id);
+        assert!(harness.send(0, ix).await);
+        let proposal: Proposal = harness
+            .account(pda::proposal(&event, proposal_id).0)
+            .await
+            .unwrap();
+        assert!(proposal.settled);
+    });
+}
@@ -0,0 +1,196 @@
+use payra_fuzz::{run, run_actions, Action};
+use proptest::prelude::*;
+
+// creator spends from an event that missed its target and got cancelled
+#[test]
+fn cancelled_event_keeps_its_funds() {
+    run_actions(&[
+        Action::CreateEvent {
+            actor: 0,
+            target: 1_000,
+            deadline_in: 100,
+        },
+        Action::Whitelist {
+            event: 0,
+            members: 0b0010,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 0,
+            amount: 300,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 1,
+            amount: 200,
+        },
+        Action::CreateProposal {
+            event: 0,
+            actor: 0,
+            amount: 400,
+            shares: 0b0011,
+            deadline_in: 1_000,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 0,
+            approve: true,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 1,
+            approve: true,
+        },
+        Action::Warp { seconds: 200 },
+        Action::CloseEvent { event: 0, actor: 0 },
+        Action::SettleProposal {
+            event: 0,
+            proposal: 0,
+            actor: 0,
+        },
+    ]);
+}
+
+// total_spent must follow every payout out of the vault
+#[test]
+fn settled_spending_counts_as_spent() {
+    run_actions(&[
+        Action::CreateEvent {
+            actor: 0,
+            target: 400,
+            deadline_in: 100,
+        },
+        Action::Whitelist {
+            event: 0,
+            members: 0b0010,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 0,
+            amount: 200,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 1,
+            amount: 200,
+        },
+        Action::CreateProposal {
+            event: 0,
+            actor: 0,
+            amount: 150,
+            shares: 0b0011,
+            deadline_in: 50,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 0,
+            approve: true,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 1,
+            approve: true,
+        },
+        Action::SettleProposal {
+            event: 0,
+            proposal: 0,
+            actor: 0,
+        },
+    ]);
+}
+
+#[test]
+fn settles_a_funded_event() {
+    run_actions(&[
+        Action::CreateEvent {
+            actor: 2,
+            target: 500,
+            deadline_in: 100,
+        },
+        Action::Whitelist {
+            event: 0,
+            members: 0b0011,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 0,
+            amount: 250,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 1,
+            amount: 250,
+        },
+        Action::Contribute {
+            event: 0,
+            actor: 2,
+            amount: 100,
+        },
+        Action::CreateProposal {
+            event: 0,
+            actor: 2,
+            amount: 301,
+            shares: 0b0111,
+            deadline_in: 50,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 0,
+            approve: true,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 1,
+            approve: true,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 0,
+            actor: 2,
+            approve: true,
+        },
+        Action::SettleProposal {
+            event: 0,
+            proposal: 0,
+            actor: 1,
+        },
+        Action::CreateSettleProposal {
+            event: 0,
+            actor: 2,
+            deadline_in: 10,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 1,
+            actor: 2,
+            approve: true,
+        },
+        Action::Vote {
+            event: 0,
+            proposal: 1,
+            actor: 0,
+            approve: true,
+        },
+        Action::Warp { seconds: 20 },
+        Action::SettleEvent {
+            event: 0,
+            proposal: 1,
+            actor: 0,
+        },
+    ]);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn random_sequences_keep_the_invariants(data in prop::collection::vec(any::<u8>(), 0..512)) {
+        run(&data);
+    }
+}
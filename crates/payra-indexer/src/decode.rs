@@ -0,0 +1,103 @@
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use payra::{
+    instruction as ix, upgrade_event, upgrade_participant, upgrade_proposal, Event, EventCounter,
+    Participant, Proposal,
+};
+
+// every account type of the program
+pub enum PayraAccount {
+    EventCounter(EventCounter),
+    Event(Event),
+    Proposal(Proposal),
+    Participant(Participant),
+}
+
+// Accounts not migrated yet are upgraded with the program's own migration,
+// the upgrade refuses accounts already at the current version. v0 events need
+// their withdraw account and participant accounts to upgrade and are skipped.
+pub fn decode_account(data: &[u8]) -> Option<PayraAccount> {
+    let discriminator = data.get(..8)?;
+    if discriminator == EventCounter::DISCRIMINATOR {
+        EventCounter::try_deserialize(&mut &data[..])
+            .ok()
+            .map(PayraAccount::EventCounter)
+    } else if discriminator == Event::DISCRIMINATOR {
+        upgrade_event(data, None, &[])
+            .or_else(|_| Event::try_deserialize(&mut &data[..]))
+            .ok()
+            .map(PayraAccount::Event)
+    } else if discriminator == Proposal::DISCRIMINATOR {
+        upgrade_proposal(data)
+            .or_else(|_| Proposal::try_deserialize(&mut &data[..]))
+            .ok()
+            .map(PayraAccount::Proposal)
+    } else if discriminator == Participant::DISCRIMINATOR {
+        upgrade_participant(data)
+            .or_else(|_| Participant::try_deserialize(&mut &data[..]))
+            .ok()
+            .map(PayraAccount::Participant)
+    } else {
+        None
+    }
+}
+
+macro_rules! instructions {
+    ($($name:ident),* $(,)?) => {
+        // every program instruction with its arguments, as generated by anchor
+        pub enum PayraInstruction {
+            $($name(ix::$name)),*
+        }
+
+        pub fn decode_instruction(data: &[u8]) -> Option<PayraInstruction> {
+            $(
+                if let Some(mut args) = data.strip_prefix(ix::$name::DISCRIMINATOR) {
+                    return ix::$name::deserialize(&mut args)
+                        .ok()
+                        .map(PayraInstruction::$name);
+                }
+            )*
+            None
+        }
+    };
+}
+
+instructions!(
+    Initialize,
+    CreateEvent,
+    CloseEvent,
+    SetContributionRules,
+    SetDues,
+    SetEventAccess,
+    Contribute,
+    Whitelist,
+    JoinEvent,
+    SetMemberRole,
+    CreateProposal,
+    Vote,
+    CreateSession,
+    RevokeSession,
+    SettleProposal,
+    CreateSettleProposal,
+    SettleEvent,
+    CreateGovernanceProposal,
+    SettleGovernanceProposal,
+    ApplyWithdrawAccountChange,
+    ProposeCreatorTransfer,
+    AcceptCreatorTransfer,
+    CreateMilestoneProposal,
+    ApproveMilestone,
+    ReleaseMilestone,
+    CancelMilestones,
+    Fractionalize,
+    ClaimShares,
+    Buyout,
+    RedeemShares,
+    RedeemNft,
+    GetEventSummary,
+    PreviewSettlement,
+    GetParticipantBalance,
+    GetDuesStatus,
+    MigrateEvent,
+    MigrateProposal,
+    MigrateParticipant,
+);
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use payra::{MemberRole, MilestoneRelease, OverfundingMode, ProposalType};
+use serde::{Deserialize, Serialize};
+
+use crate::encoding;
+
+// one successful instruction of an event, in the order it ran
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub slot: u64,
+    pub signature: String,
+    pub block_time: Option<i64>,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+}
+
+// Amounts moved are read from the vault balance, not from the instruction
+// arguments, so trimmed contributions and rejected spendings are exact.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntryKind {
+    EventCreated {
+        #[serde(with = "encoding::pubkey")]
+        creator: Pubkey,
+        name: String,
+        target_amount: u64,
+        deadline: i64,
+    },
+    EventClosed {
+        #[serde(with = "encoding::pubkey")]
+        creator: Pubkey,
+    },
+    ContributionRulesChanged {
+        min_contribution: u64,
+        max_per_participant: u64,
+        equal_share: bool,
+        overfunding: String,
+        stretch_goal: u64,
+    },
+    // no voting threshold for whitelist only events
+    AccessChanged {
+        open: bool,
+        voting_threshold: u64,
+    },
+    DuesSet {
+        amount: u64,
+        period: i64,
+    },
+    MembersAdded {
+        #[serde(with = "encoding::pubkeys")]
+        wallets: Vec<Pubkey>,
+    },
+    RoleChanged {
+        #[serde(with = "encoding::pubkey")]
+        wallet: Pubkey,
+        role: String,
+    },
+    // joined with an invite voucher signed by `issuer`
+    MemberJoined {
+        #[serde(with = "encoding::pubkey")]
+        wallet: Pubkey,
+        role: String,
+        #[serde(with = "encoding::pubkey")]
+        issuer: Pubkey,
+        invite_id: u64,
+    },
+    SessionCreated {
+        #[serde(with = "encoding::pubkey")]
+        member: Pubkey,
+        #[serde(with = "encoding::pubkey")]
+        session_key: Pubkey,
+        expires_at: i64,
+    },
+    Contributed {
+        #[serde(with = "encoding::pubkey")]
+        wallet: Pubkey,
+        amount: u64,
+    },
+    ProposalCreated {
+        proposal_id: u16,
+        #[serde(with = "encoding::pubkey")]
+        creator: Pubkey,
+        proposal_type: String,
+        // only spending proposals are titled by their creator
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        amount: u64,
+        // tranches of a milestone proposal
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        milestones: Vec<u64>,
+    },
+    Voted {
+        proposal_id: u16,
+        #[serde(with = "encoding::pubkey")]
+        voter: Pubkey,
+        approve: bool,
+    },
+    // spending proposal, nothing paid when it was vetoed
+    SpendingSettled {
+        proposal_id: u16,
+        paid: u64,
+    },
+    GovernanceSettled {
+        proposal_id: u16,
+    },
+    MilestoneApproved {
+        proposal_id: u16,
+        #[serde(with = "encoding::pubkey")]
+        approver: Pubkey,
+    },
+    MilestoneReleased {
+        proposal_id: u16,
+        milestone: u8,
+        paid: u64,
+    },
+    MilestonesCancelled {
+        proposal_id: u16,
+    },
+    EventSettled {
+        proposal_id: u16,
+    },
+    NftFractionalized {
+        #[serde(with = "encoding::pubkey")]
+        nft_mint: Pubkey,
+    },
+    SharesClaimed {
+        #[serde(with = "encoding::pubkey")]
+        wallet: Pubkey,
+    },
+    BoughtOut {
+        #[serde(with = "encoding::pubkey")]
+        buyer: Pubkey,
+        price: u64,
+    },
+    WithdrawAccountChanged {
+        #[serde(with = "encoding::pubkey")]
+        withdraw_token_account: Pubkey,
+    },
+    CreatorTransferProposed {
+        #[serde(with = "encoding::option_pubkey")]
+        new_creator: Option<Pubkey>,
+    },
+    CreatorTransferAccepted {
+        #[serde(with = "encoding::pubkey")]
+        new_creator: Pubkey,
+    },
+}
+
+impl EntryKind {
+    pub fn proposal_id(&self) -> Option<u16> {
+        match self {
+            EntryKind::ProposalCreated { proposal_id, .. }
+            | EntryKind::Voted { proposal_id, .. }
+            | EntryKind::SpendingSettled { proposal_id, .. }
+            | EntryKind::GovernanceSettled { proposal_id }
+            | EntryKind::MilestoneApproved { proposal_id, .. }
+            | EntryKind::MilestoneReleased { proposal_id, .. }
+            | EntryKind::MilestonesCancelled { proposal_id }
+            | EntryKind::EventSettled { proposal_id } => Some(*proposal_id),
+            _ => None,
+        }
+    }
+
+    pub fn is_settlement(&self) -> bool {
+        matches!(
+            self,
+            EntryKind::SpendingSettled { .. }
+                | EntryKind::GovernanceSettled { .. }
+                | EntryKind::MilestoneReleased { .. }
+                | EntryKind::MilestonesCancelled { .. }
+                | EntryKind::EventSettled { .. }
+        )
+    }
+}
+
+pub(crate) fn role_name(role: MemberRole) -> &'static str {
+    match role {
+        MemberRole::Observer => "observer",
+        MemberRole::Member => "member",
+        MemberRole::Treasurer => "treasurer",
+        MemberRole::Admin => "admin",
+    }
+}
+
+pub(crate) fn overfunding_name(mode: OverfundingMode) -> &'static str {
+    match mode {
+        OverfundingMode::Allow => "allow",
+        OverfundingMode::Reject => "reject",
+        OverfundingMode::PartialFill => "partial fill",
+    }
+}
+
+pub(crate) fn proposal_type_name(proposal_type: &ProposalType) -> String {
+    match proposal_type {
+        ProposalType::Spending => "spending".to_string(),
+        ProposalType::EventSettlement => "event settlement".to_string(),
+        ProposalType::ExtendDeadline { new_deadline } => {
+            format!("extend deadline to {new_deadline}")
+        }
+        ProposalType::ChangeTarget { new_target_amount } => {
+            format!("change target to {new_target_amount}")
+        }
+        ProposalType::ChangeWithdrawAccount {
+            new_withdraw_token_account,
+        } => format!("change withdraw account to {new_withdraw_token_account}"),
+        ProposalType::Milestones {
+            release: MilestoneRelease::Approval,
+        } => "milestones released on approval".to_string(),
+        ProposalType::Milestones {
+            release: MilestoneRelease::Attestation,
+        } => "milestones released on attestation".to_string(),
+        ProposalType::ReservePrice { vault, price } => {
+            format!("set the reserve price of {vault} to {price}")
+        }
+    }
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryKind::EventCreated {
+                creator,
+                name,
+                target_amount,
+                deadline,
+            } => write!(
+                f,
+                "{creator} created \"{name}\" with target {target_amount}, deadline {deadline}"
+            ),
+            EntryKind::EventClosed { creator } => write!(f, "{creator} closed the event"),
+            EntryKind::ContributionRulesChanged {
+                min_contribution,
+                max_per_participant,
+                equal_share,
+                overfunding,
+                stretch_goal,
+            } => write!(
+                f,
+                "contribution rules: min {min_contribution}, max {max_per_participant}, \
+                 equal share {equal_share}, overfunding {overfunding}, stretch goal {stretch_goal}"
+            ),
+            EntryKind::AccessChanged { open: false, .. } => {
+                write!(f, "event restricted to whitelisted members")
+            }
+            EntryKind::AccessChanged {
+                open: true,
+                voting_threshold,
+            } => write!(
+                f,
+                "event opened to anyone, {voting_threshold} contributed earns a vote"
+            ),
+            EntryKind::DuesSet { amount, period } => {
+                write!(f, "dues of {amount} every {period} seconds")
+            }
+            EntryKind::MembersAdded { wallets } => {
+                write!(f, "added")?;
+                for wallet in wallets {
+                    write!(f, " {wallet}")?;
+                }
+                Ok(())
+            }
+            EntryKind::RoleChanged { wallet, role } => write!(f, "{wallet} is now {role}"),
+            EntryKind::MemberJoined {
+                wallet,
+                role,
+                issuer,
+                invite_id,
+            } => write!(
+                f,
+                "{wallet} joined as {role} with invite #{invite_id} of {issuer}"
+            ),
+            EntryKind::SessionCreated {
+                member,
+                session_key,
+                expires_at,
+            } => write!(
+                f,
+                "{member} let {session_key} vote for them until {expires_at}"
+            ),
+            EntryKind::Contributed { wallet, amount } => {
+                write!(f, "{wallet} contributed {amount}")
+            }
+            EntryKind::ProposalCreated {
+                proposal_id,
+                creator,
+                proposal_type,
+                title,
+                amount,
+                milestones,
+            } => {
+                write!(f, "{creator} proposed #{proposal_id} ")?;
+                if let Some(title) = title {
+                    write!(f, "\"{title}\" ")?;
+                }
+                write!(f, "({proposal_type}) amount {amount}")?;
+                if !milestones.is_empty() {
+                    write!(f, " in {} tranches", milestones.len())?;
+                }
+                Ok(())
+            }
+            EntryKind::Voted {
+                proposal_id,
+                voter,
+                approve,
+            } => write!(
+                f,
+                "{voter} voted {} on #{proposal_id}",
+                if *approve { "yes" } else { "no" }
+            ),
+            EntryKind::SpendingSettled { proposal_id, paid } if *paid == 0 => {
+                write!(f, "#{proposal_id} rejected")
+            }
+            EntryKind::SpendingSettled { proposal_id, paid } => {
+                write!(f, "#{proposal_id} settled, {paid} paid out")
+            }
+            EntryKind::GovernanceSettled { proposal_id } => write!(f, "#{proposal_id} settled"),
+            EntryKind::MilestoneApproved {
+                proposal_id,
+                approver,
+            } => write!(
+                f,
+                "{approver} approved the next milestone of #{proposal_id}"
+            ),
+            EntryKind::MilestoneReleased {
+                proposal_id,
+                milestone,
+                paid,
+            } => write!(
+                f,
+                "milestone {milestone} of #{proposal_id} released, {paid} paid out"
+            ),
+            EntryKind::MilestonesCancelled { proposal_id } => {
+                write!(f, "remaining milestones of #{proposal_id} cancelled")
+            }
+            EntryKind::EventSettled { proposal_id } => {
+                write!(f, "event settled by #{proposal_id}")
+            }
+            EntryKind::NftFractionalized { nft_mint } => {
+                write!(f, "NFT {nft_mint} fractionalized")
+            }
+            EntryKind::SharesClaimed { wallet } => write!(f, "{wallet} claimed their shares"),
+            EntryKind::BoughtOut { buyer, price } => {
+                write!(f, "{buyer} bought the NFT out for {price}")
+            }
+            EntryKind::WithdrawAccountChanged {
+                withdraw_token_account,
+            } => write!(f, "withdraw account changed to {withdraw_token_account}"),
+            EntryKind::CreatorTransferProposed {
+                new_creator: Some(new_creator),
+            } => write!(f, "creator transfer to {new_creator} proposed"),
+            EntryKind::CreatorTransferProposed { new_creator: None } => {
+                write!(f, "creator transfer withdrawn")
+            }
+            EntryKind::CreatorTransferAccepted { new_creator } => {
+                write!(f, "{new_creator} accepted the creator role")
+            }
+        }
+    }
+}
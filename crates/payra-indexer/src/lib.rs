@@ -0,0 +1,37 @@
+//! Off-chain indexer for Payra: replays the program's transactions, from a
+//! validator or from recorded files, into a store of events, contributions,
+//! votes and settlements with a chronological ledger per event.
+//!
+//! Instructions and accounts are decoded with the program's own types, so
+//! the indexer follows every change to `state::contribution_pools`.
+
+pub mod decode;
+mod encoding;
+pub mod ledger;
+pub mod source;
+pub mod store;
+
+pub use ledger::{EntryKind, LedgerEntry};
+pub use source::{IndexedTransaction, RpcSource, TokenChange};
+pub use store::{EventIndex, Store};
+
+// rpc errors are boxed, they are a few hundred bytes
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    #[error("rpc error: {0}")]
+    Rpc(Box<solana_rpc_client_api::client_error::Error>),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid transaction {0}")]
+    InvalidTransaction(String),
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for IndexerError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        IndexerError::Rpc(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
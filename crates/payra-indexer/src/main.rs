@@ -0,0 +1,199 @@
+use std::{
+    fs::OpenOptions,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use clap::{Parser, Subcommand};
+use payra_indexer::{
+    source::{read_recorded, write_recorded},
+    EntryKind, EventIndex, IndexedTransaction, LedgerEntry, RpcSource, Store,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "payra-indexer",
+    about = "Index Payra transactions into a per event ledger"
+)]
+struct Cli {
+    /// Store file, created on first use
+    #[arg(short, long, global = true, default_value = "payra-index.json")]
+    store: PathBuf,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index new transactions and account states from a validator
+    Sync {
+        #[arg(short = 'u', long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Keep syncing every `interval` seconds
+        #[arg(long)]
+        follow: bool,
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+        /// Append the fetched transactions to this file, for replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+    /// Index recorded transactions, files are applied in order
+    Replay { files: Vec<PathBuf> },
+    /// List indexed events
+    Events,
+    /// Chronological ledger of an event
+    Ledger { event_id: u64 },
+    /// Contributions to an event
+    Contributions { event_id: u64 },
+    /// Votes on an event's proposals
+    Votes {
+        event_id: u64,
+        #[arg(long)]
+        proposal: Option<u16>,
+    },
+    /// Settled proposals of an event
+    Settlements { event_id: u64 },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::load(&cli.store)
+        .with_context(|| format!("reading store {}", cli.store.display()))?;
+
+    match cli.command {
+        Command::Sync {
+            url,
+            follow,
+            interval,
+            record,
+        } => {
+            let source = RpcSource::new(url);
+            loop {
+                sync(&source, &mut store, record.as_deref())?;
+                store.save(&cli.store)?;
+                if !follow {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+        Command::Replay { files } => {
+            for file in files {
+                for encoded in
+                    read_recorded(&file).with_context(|| format!("reading {}", file.display()))?
+                {
+                    if let Some(transaction) = IndexedTransaction::from_encoded(&encoded)? {
+                        store.apply_transaction(&transaction);
+                    }
+                }
+            }
+            store.save(&cli.store)?;
+        }
+        Command::Events => {
+            for event in store.events() {
+                print_event(event);
+            }
+        }
+        Command::Ledger { event_id } => {
+            print_entries(cli.json, indexed(&store, event_id)?.ledger.iter())?;
+        }
+        Command::Contributions { event_id } => {
+            print_entries(cli.json, indexed(&store, event_id)?.contributions())?;
+        }
+        Command::Votes { event_id, proposal } => {
+            print_entries(cli.json, indexed(&store, event_id)?.votes(proposal))?;
+        }
+        Command::Settlements { event_id } => {
+            print_entries(cli.json, indexed(&store, event_id)?.settlements())?;
+        }
+    }
+    Ok(())
+}
+
+fn sync(source: &RpcSource, store: &mut Store, record: Option<&Path>) -> Result<()> {
+    let transactions = source.transactions_since(store.last_signature.as_deref())?;
+    if let Some(path) = record {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        for encoded in &transactions {
+            write_recorded(&mut writer, encoded)?;
+        }
+    }
+    for encoded in &transactions {
+        if let Some(transaction) = IndexedTransaction::from_encoded(encoded)? {
+            store.apply_transaction(&transaction);
+        }
+    }
+
+    let (slot, accounts) = source.program_accounts()?;
+    for (address, data) in accounts {
+        store.apply_account(address, slot, data);
+    }
+    println!(
+        "Indexed {} transactions up to slot {slot}",
+        transactions.len()
+    );
+    Ok(())
+}
+
+fn indexed(store: &Store, event_id: u64) -> Result<&EventIndex> {
+    store
+        .event(event_id)
+        .ok_or_else(|| anyhow!("event {event_id} is not indexed"))
+}
+
+fn print_event(index: &EventIndex) {
+    let contributed: u64 = index
+        .contributions()
+        .map(|entry| match entry.kind {
+            EntryKind::Contributed { amount, .. } => amount,
+            _ => 0,
+        })
+        .sum();
+    // the snapshot is missing until accounts were synced
+    let name = index
+        .event()
+        .map(|event| event.name)
+        .or_else(|| {
+            index.ledger.iter().find_map(|entry| match &entry.kind {
+                EntryKind::EventCreated { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+        })
+        .unwrap_or_default();
+    println!(
+        "{:>6} {:<44} {:<32} {:>8} entries {:>20} contributed",
+        index.event_id,
+        index.address.to_string(),
+        name,
+        index.ledger.len(),
+        contributed
+    );
+}
+
+fn print_entries<'a>(json: bool, entries: impl Iterator<Item = &'a LedgerEntry>) -> Result<()> {
+    if json {
+        let entries: Vec<&LedgerEntry> = entries.collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    for entry in entries {
+        let time = entry
+            .block_time
+            .map_or_else(|| "-".to_string(), |time| time.to_string());
+        println!(
+            "{:>10} {:>11} {} {}",
+            entry.slot, time, entry.signature, entry.kind
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::config::RpcTransactionConfig;
+use solana_signature::Signature;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
+
+use crate::{IndexerError, Result};
+
+// getSignaturesForAddress returns at most 1000 signatures per request
+const SIGNATURES_PAGE: usize = 1_000;
+
+// address and data of a program account
+pub type AccountData = (Pubkey, Vec<u8>);
+
+// balance of a token account before and after the transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenChange {
+    pub account: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub before: u64,
+    pub after: u64,
+}
+
+// A successful transaction reduced to what the ledger needs: the Payra
+// instructions it ran and how token balances moved.
+#[derive(Clone, Debug)]
+pub struct IndexedTransaction {
+    pub slot: u64,
+    pub signature: String,
+    pub block_time: Option<i64>,
+    pub instructions: Vec<Instruction>,
+    pub token_changes: Vec<TokenChange>,
+}
+
+impl IndexedTransaction {
+    // `None` for failed transactions, they changed nothing. Only top level
+    // instructions are kept, no program invokes Payra through CPI.
+    pub fn from_encoded(
+        encoded: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<Option<Self>> {
+        let invalid = |reason: &str| IndexerError::InvalidTransaction(reason.to_string());
+        let meta = encoded
+            .transaction
+            .meta
+            .as_ref()
+            .ok_or_else(|| invalid("without status meta"))?;
+        if meta.err.is_some() {
+            return Ok(None);
+        }
+        let transaction = encoded
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| invalid("not binary encoded"))?;
+        let signature = transaction
+            .signatures
+            .first()
+            .ok_or_else(|| invalid("without signature"))?
+            .to_string();
+
+        // address table lookups come after the static keys, writable first
+        let mut keys = transaction.message.static_account_keys().to_vec();
+        let loaded: Option<UiLoadedAddresses> = Option::from(meta.loaded_addresses.clone());
+        if let Some(loaded) = loaded {
+            for key in loaded.writable.iter().chain(&loaded.readonly) {
+                keys.push(
+                    key.parse()
+                        .map_err(|_| invalid("with an invalid loaded address"))?,
+                );
+            }
+        }
+        let key = |index: u8| {
+            keys.get(index as usize)
+                .copied()
+                .ok_or_else(|| invalid("with an account index out of range"))
+        };
+
+        let mut instructions = Vec::new();
+        for compiled in transaction.message.instructions() {
+            let program_id = key(compiled.program_id_index)?;
+            if program_id != payra::ID {
+                continue;
+            }
+            let accounts = compiled
+                .accounts
+                .iter()
+                .map(|index| Ok(AccountMeta::new_readonly(key(*index)?, false)))
+                .collect::<Result<Vec<_>>>()?;
+            instructions.push(Instruction {
+                program_id,
+                accounts,
+                data: compiled.data.clone(),
+            });
+        }
+
+        let pre: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.pre_token_balances.clone()).unwrap_or_default();
+        let post: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.post_token_balances.clone()).unwrap_or_default();
+        let mut token_changes = Vec::new();
+        // accounts created by the transaction only have a post balance
+        for balance in pre.iter().chain(&post) {
+            let account = key(balance.account_index)?;
+            if token_changes
+                .iter()
+                .any(|c: &TokenChange| c.account == account)
+            {
+                continue;
+            }
+            let amount = |balances: &[UiTransactionTokenBalance]| {
+                balances
+                    .iter()
+                    .find(|b| b.account_index == balance.account_index)
+                    .map_or(Ok(0), |b| b.ui_token_amount.amount.parse::<u64>())
+                    .map_err(|_| invalid("with an invalid token amount"))
+            };
+            let owner: Option<String> = Option::from(balance.owner.clone());
+            token_changes.push(TokenChange {
+                account,
+                owner: owner.and_then(|owner| owner.parse().ok()),
+                before: amount(&pre)?,
+                after: amount(&post)?,
+            });
+        }
+
+        Ok(Some(Self {
+            slot: encoded.slot,
+            signature,
+            block_time: encoded.block_time,
+            instructions,
+            token_changes,
+        }))
+    }
+
+    // net change of the token accounts owned by `owner`, an event for its vault
+    pub fn balance_change(&self, owner: &Pubkey) -> i128 {
+        self.token_changes
+            .iter()
+            .filter(|change| change.owner.as_ref() == Some(owner))
+            .map(|change| change.after as i128 - change.before as i128)
+            .sum()
+    }
+}
+
+// Recorded transactions are JSON lines, one getTransaction response (base64
+// encoding) per line, oldest first. `sync --record` writes them.
+pub fn read_recorded(path: &Path) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>> {
+    let mut transactions = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            transactions.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(transactions)
+}
+
+pub fn write_recorded(
+    writer: &mut impl Write,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<()> {
+    serde_json::to_writer(&mut *writer, transaction)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+pub struct RpcSource {
+    rpc: RpcClient,
+}
+
+impl RpcSource {
+    pub fn new(url: String) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        }
+    }
+
+    // transactions of the program after `until`, every one when `None`,
+    // oldest first
+    pub fn transactions_since(
+        &self,
+        until: Option<&str>,
+    ) -> Result<Vec<EncodedConfirmedTransactionWithStatusMeta>> {
+        let until = until
+            .map(|signature| {
+                Signature::from_str(signature)
+                    .map_err(|_| IndexerError::InvalidTransaction(signature.to_string()))
+            })
+            .transpose()?;
+
+        // pages come newest first
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                &payra::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(SIGNATURES_PAGE),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )?;
+            let full = page.len() == SIGNATURES_PAGE;
+            for status in page {
+                let signature = Signature::from_str(&status.signature)
+                    .map_err(|_| IndexerError::InvalidTransaction(status.signature.clone()))?;
+                before = Some(signature);
+                // failed transactions changed nothing
+                if status.err.is_none() {
+                    signatures.push(signature);
+                }
+            }
+            if !full {
+                break;
+            }
+        }
+
+        signatures
+            .iter()
+            .rev()
+            .map(|signature| {
+                Ok(self.rpc.get_transaction_with_config(
+                    signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )?)
+            })
+            .collect()
+    }
+
+    // current state of every program account, with the slot it was read at
+    pub fn program_accounts(&self) -> Result<(u64, Vec<AccountData>)> {
+        let slot = self.rpc.get_slot()?;
+        let accounts = self
+            .rpc
+            .get_program_accounts(&payra::ID)?
+            .into_iter()
+            .map(|(key, account)| (key, account.data))
+            .collect();
+        Ok((slot, accounts))
+    }
+}
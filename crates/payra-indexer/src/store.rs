@@ -0,0 +1,540 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use anchor_lang::prelude::Pubkey;
+use payra::{instruction as ix, Event, EventAccess, Participant, Proposal, ProposalType};
+use payra_client::pda;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decode::{decode_account, decode_instruction, PayraAccount, PayraInstruction},
+    encoding,
+    ledger::{overfunding_name, proposal_type_name, role_name},
+    EntryKind, IndexedTransaction, LedgerEntry, Result,
+};
+
+// ids tried when an event or proposal address was never seen created, e.g.
+// when indexing starts after the program has been in use
+const SEARCH_WINDOW: u64 = 1_024;
+
+// raw program account, decoded with the program's types when queried
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredAccount {
+    #[serde(with = "encoding::pubkey")]
+    pub address: Pubkey,
+    pub slot: u64,
+    #[serde(with = "encoding::base64")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ProposalIndex {
+    proposal_id: u16,
+    #[serde(with = "encoding::pubkey")]
+    address: Pubkey,
+    account: Option<StoredAccount>,
+}
+
+// everything indexed about one event
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventIndex {
+    pub event_id: u64,
+    #[serde(with = "encoding::pubkey")]
+    pub address: Pubkey,
+    pub ledger: Vec<LedgerEntry>,
+    account: Option<StoredAccount>,
+    proposals: Vec<ProposalIndex>,
+    participants: Vec<StoredAccount>,
+}
+
+impl EventIndex {
+    fn new(event_id: u64) -> Self {
+        Self {
+            event_id,
+            address: pda::event(event_id).0,
+            ledger: Vec::new(),
+            account: None,
+            proposals: Vec::new(),
+            participants: Vec::new(),
+        }
+    }
+
+    // latest account snapshot, `None` before the first account sync
+    pub fn event(&self) -> Option<Event> {
+        match decode_account(&self.account.as_ref()?.data)? {
+            PayraAccount::Event(event) => Some(event),
+            _ => None,
+        }
+    }
+
+    pub fn proposals(&self) -> Vec<Proposal> {
+        self.proposals
+            .iter()
+            .filter_map(
+                |proposal| match decode_account(&proposal.account.as_ref()?.data)? {
+                    PayraAccount::Proposal(proposal) => Some(proposal),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+
+    pub fn participants(&self) -> Vec<Participant> {
+        self.participants
+            .iter()
+            .filter_map(|account| match decode_account(&account.data)? {
+                PayraAccount::Participant(participant) => Some(participant),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn contributions(&self) -> impl Iterator<Item = &LedgerEntry> {
+        self.ledger
+            .iter()
+            .filter(|entry| matches!(entry.kind, EntryKind::Contributed { .. }))
+    }
+
+    // votes on one proposal, or on all of them
+    pub fn votes(&self, proposal_id: Option<u16>) -> impl Iterator<Item = &LedgerEntry> {
+        self.ledger.iter().filter(move |entry| {
+            matches!(entry.kind, EntryKind::Voted { .. })
+                && proposal_id.is_none_or(|id| entry.kind.proposal_id() == Some(id))
+        })
+    }
+
+    pub fn settlements(&self) -> impl Iterator<Item = &LedgerEntry> {
+        self.ledger
+            .iter()
+            .filter(|entry| entry.kind.is_settlement())
+    }
+
+    // amount proposed in a spending proposal, from its creation entry
+    fn proposed_amount(&self, proposal_id: u16) -> u64 {
+        self.ledger
+            .iter()
+            .find_map(|entry| match &entry.kind {
+                EntryKind::ProposalCreated {
+                    proposal_id: id,
+                    amount,
+                    ..
+                } if *id == proposal_id => Some(*amount),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    // tranches are released in order, the next one follows the releases
+    // already recorded
+    fn next_milestone(&self, proposal_id: u16) -> (u8, u64) {
+        let released = self
+            .ledger
+            .iter()
+            .filter(|entry| {
+                matches!(entry.kind, EntryKind::MilestoneReleased { proposal_id: id, .. } if id == proposal_id)
+            })
+            .count();
+        let amount = self
+            .ledger
+            .iter()
+            .find_map(|entry| match &entry.kind {
+                EntryKind::ProposalCreated {
+                    proposal_id: id,
+                    milestones,
+                    ..
+                } if *id == proposal_id => milestones.get(released).copied(),
+                _ => None,
+            })
+            .unwrap_or_default();
+        (released as u8, amount)
+    }
+
+    fn proposal_id(&mut self, address: &Pubkey) -> Option<u16> {
+        if let Some(proposal) = self.proposals.iter().find(|p| p.address == *address) {
+            return Some(proposal.proposal_id);
+        }
+        let proposal_id =
+            (0..SEARCH_WINDOW as u16).find(|id| pda::proposal(&self.address, *id).0 == *address)?;
+        let position = self
+            .proposals
+            .partition_point(|p| p.proposal_id < proposal_id);
+        self.proposals.insert(
+            position,
+            ProposalIndex {
+                proposal_id,
+                address: *address,
+                account: None,
+            },
+        );
+        Some(proposal_id)
+    }
+}
+
+// Indexed state of the program, kept in a JSON file between runs.
+// Transactions must be applied oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Store {
+    // newest applied transaction, syncing resumes after it
+    pub last_signature: Option<String>,
+    // every applied transaction, replaying one twice changes nothing
+    indexed: BTreeSet<String>,
+    // by event id
+    events: Vec<EventIndex>,
+}
+
+impl Store {
+    // an empty store when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    // written next to the store and renamed, an interrupted save keeps the
+    // previous file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn events(&self) -> &[EventIndex] {
+        &self.events
+    }
+
+    pub fn event(&self, event_id: u64) -> Option<&EventIndex> {
+        self.events
+            .binary_search_by_key(&event_id, |event| event.event_id)
+            .ok()
+            .map(|i| &self.events[i])
+    }
+
+    fn event_by_id(&mut self, event_id: u64) -> &mut EventIndex {
+        let position = match self
+            .events
+            .binary_search_by_key(&event_id, |event| event.event_id)
+        {
+            Ok(position) => position,
+            Err(position) => {
+                self.events.insert(position, EventIndex::new(event_id));
+                position
+            }
+        };
+        &mut self.events[position]
+    }
+
+    fn event_by_address(&mut self, address: &Pubkey) -> Option<&mut EventIndex> {
+        let event_id = match self.events.iter().find(|event| event.address == *address) {
+            Some(event) => event.event_id,
+            None => {
+                let next = self.events.last().map_or(0, |event| event.event_id + 1);
+                (0..next + SEARCH_WINDOW).find(|id| pda::event(*id).0 == *address)?
+            }
+        };
+        Some(self.event_by_id(event_id))
+    }
+
+    pub fn apply_transaction(&mut self, transaction: &IndexedTransaction) {
+        if !self.indexed.insert(transaction.signature.clone()) {
+            return;
+        }
+        for instruction in &transaction.instructions {
+            self.apply_instruction(transaction, &instruction.accounts, &instruction.data);
+        }
+        self.last_signature = Some(transaction.signature.clone());
+    }
+
+    fn apply_instruction(
+        &mut self,
+        transaction: &IndexedTransaction,
+        accounts: &[anchor_lang::prelude::AccountMeta],
+        data: &[u8],
+    ) {
+        let Some(instruction) = decode_instruction(data) else {
+            return;
+        };
+        let Some((event_position, proposal_position)) = account_positions(&instruction) else {
+            return;
+        };
+        let key = |position: usize| accounts.get(position).map(|meta| meta.pubkey);
+        let Some(event_key) = key(event_position) else {
+            return;
+        };
+        // the vault is the only token account owned by the event, its
+        // balance change belongs to the instruction unless several Payra
+        // instructions share the transaction
+        let vault_change =
+            (transaction.instructions.len() == 1).then(|| transaction.balance_change(&event_key));
+
+        let Some(event) = self.event_by_address(&event_key) else {
+            return;
+        };
+        let proposal_id = match proposal_position.and_then(key) {
+            Some(proposal_key) => match event.proposal_id(&proposal_key) {
+                Some(id) => Some(id),
+                None => return,
+            },
+            None => None,
+        };
+        let proposal = || proposal_id.unwrap_or_default();
+
+        use PayraInstruction as I;
+        let kind = match instruction {
+            I::CreateEvent(ix::CreateEvent { args }) => EntryKind::EventCreated {
+                creator: key(0).unwrap_or_default(),
+                name: args.name,
+                target_amount: args.target_amount,
+                deadline: args.deadline,
+            },
+            I::CloseEvent(_) => EntryKind::EventClosed {
+                creator: key(0).unwrap_or_default(),
+            },
+            I::SetContributionRules(ix::SetContributionRules { rules }) => {
+                EntryKind::ContributionRulesChanged {
+                    min_contribution: rules.min_contribution,
+                    max_per_participant: rules.max_per_participant,
+                    equal_share: rules.equal_share,
+                    overfunding: overfunding_name(rules.overfunding).to_string(),
+                    stretch_goal: rules.stretch_goal,
+                }
+            }
+            I::SetDues(ix::SetDues { amount, period }) => EntryKind::DuesSet { amount, period },
+            I::SetEventAccess(ix::SetEventAccess { access }) => match access {
+                EventAccess::Whitelist => EntryKind::AccessChanged {
+                    open: false,
+                    voting_threshold: 0,
+                },
+                EventAccess::Open { voting_threshold } => EntryKind::AccessChanged {
+                    open: true,
+                    voting_threshold,
+                },
+            },
+            I::Whitelist(ix::Whitelist { wallets_to_add }) => EntryKind::MembersAdded {
+                wallets: wallets_to_add,
+            },
+            I::JoinEvent(ix::JoinEvent { voucher }) => EntryKind::MemberJoined {
+                wallet: key(0).unwrap_or_default(),
+                role: role_name(voucher.role).to_string(),
+                issuer: voucher.issuer,
+                invite_id: voucher.invite_id,
+            },
+            I::SetMemberRole(ix::SetMemberRole { wallet, role }) => EntryKind::RoleChanged {
+                wallet,
+                role: role_name(role).to_string(),
+            },
+            I::CreateSession(ix::CreateSession {
+                session_key,
+                expires_at,
+            }) => EntryKind::SessionCreated {
+                member: key(0).unwrap_or_default(),
+                session_key,
+                expires_at,
+            },
+            I::Contribute(ix::Contribute { amount }) => EntryKind::Contributed {
+                wallet: key(0).unwrap_or_default(),
+                amount: vault_change.map_or(amount, |change| change.max(0) as u64),
+            },
+            I::CreateProposal(ix::CreateProposal { title, amount, .. }) => {
+                EntryKind::ProposalCreated {
+                    proposal_id: proposal(),
+                    creator: key(0).unwrap_or_default(),
+                    proposal_type: proposal_type_name(&ProposalType::Spending),
+                    title: Some(title),
+                    amount,
+                    milestones: Vec::new(),
+                }
+            }
+            I::CreateMilestoneProposal(ix::CreateMilestoneProposal {
+                title,
+                milestones,
+                release,
+                ..
+            }) => EntryKind::ProposalCreated {
+                proposal_id: proposal(),
+                creator: key(0).unwrap_or_default(),
+                proposal_type: proposal_type_name(&ProposalType::Milestones { release }),
+                title: Some(title),
+                amount: milestones.iter().sum(),
+                milestones,
+            },
+            I::CreateSettleProposal(_) => EntryKind::ProposalCreated {
+                proposal_id: proposal(),
+                creator: key(0).unwrap_or_default(),
+                proposal_type: proposal_type_name(&ProposalType::EventSettlement),
+                title: None,
+                amount: 0,
+                milestones: Vec::new(),
+            },
+            I::CreateGovernanceProposal(ix::CreateGovernanceProposal { proposal_type, .. }) => {
+                EntryKind::ProposalCreated {
+                    proposal_id: proposal(),
+                    creator: key(0).unwrap_or_default(),
+                    proposal_type: proposal_type_name(&proposal_type),
+                    title: None,
+                    amount: 0,
+                    milestones: Vec::new(),
+                }
+            }
+            I::Vote(ix::Vote { vote_choice }) => EntryKind::Voted {
+                proposal_id: proposal(),
+                voter: key(0).unwrap_or_default(),
+                approve: vote_choice,
+            },
+            // without the vault change a shared transaction is assumed to pay
+            // the proposed amount
+            I::SettleProposal(_) => EntryKind::SpendingSettled {
+                proposal_id: proposal(),
+                paid: vault_change.map_or_else(
+                    || event.proposed_amount(proposal()),
+                    |change| change.min(0).unsigned_abs() as u64,
+                ),
+            },
+            I::SettleGovernanceProposal(_) => EntryKind::GovernanceSettled {
+                proposal_id: proposal(),
+            },
+            I::ApproveMilestone(_) => EntryKind::MilestoneApproved {
+                proposal_id: proposal(),
+                approver: key(0).unwrap_or_default(),
+            },
+            I::ReleaseMilestone(_) => {
+                let (milestone, amount) = event.next_milestone(proposal());
+                EntryKind::MilestoneReleased {
+                    proposal_id: proposal(),
+                    milestone,
+                    paid: vault_change.map_or(amount, |change| change.min(0).unsigned_abs() as u64),
+                }
+            }
+            I::CancelMilestones(_) => EntryKind::MilestonesCancelled {
+                proposal_id: proposal(),
+            },
+            I::SettleEvent(_) => EntryKind::EventSettled {
+                proposal_id: proposal(),
+            },
+            I::Fractionalize(_) => EntryKind::NftFractionalized {
+                nft_mint: key(4).unwrap_or_default(),
+            },
+            I::ClaimShares(_) => EntryKind::SharesClaimed {
+                wallet: key(0).unwrap_or_default(),
+            },
+            I::Buyout(ix::Buyout { price }) => EntryKind::BoughtOut {
+                buyer: key(0).unwrap_or_default(),
+                price,
+            },
+            I::ApplyWithdrawAccountChange(_) => EntryKind::WithdrawAccountChanged {
+                withdraw_token_account: key(2).unwrap_or_default(),
+            },
+            I::ProposeCreatorTransfer(ix::ProposeCreatorTransfer { new_creator }) => {
+                EntryKind::CreatorTransferProposed { new_creator }
+            }
+            I::AcceptCreatorTransfer(_) => EntryKind::CreatorTransferAccepted {
+                new_creator: key(0).unwrap_or_default(),
+            },
+            _ => return,
+        };
+
+        event.ledger.push(LedgerEntry {
+            slot: transaction.slot,
+            signature: transaction.signature.clone(),
+            block_time: transaction.block_time,
+            kind,
+        });
+    }
+
+    // Keeps the newest snapshot of a program account. Accounts are matched
+    // to their event through the ids they store, proposals through their
+    // address.
+    pub fn apply_account(&mut self, address: Pubkey, slot: u64, data: Vec<u8>) {
+        let stored = StoredAccount {
+            address,
+            slot,
+            data,
+        };
+        let newer =
+            |current: &Option<StoredAccount>| current.as_ref().is_none_or(|c| c.slot <= slot);
+        match decode_account(&stored.data) {
+            Some(PayraAccount::Event(event)) => {
+                let index = self.event_by_id(event.event_id);
+                if index.address == address && newer(&index.account) {
+                    index.account = Some(stored);
+                }
+            }
+            Some(PayraAccount::Proposal(proposal)) => {
+                let Some(index) = self
+                    .events
+                    .iter_mut()
+                    .find(|event| pda::proposal(&event.address, proposal.proposal_id).0 == address)
+                else {
+                    return;
+                };
+                index.proposal_id(&address);
+                if let Some(entry) = index.proposals.iter_mut().find(|p| p.address == address) {
+                    if newer(&entry.account) {
+                        entry.account = Some(stored);
+                    }
+                }
+            }
+            Some(PayraAccount::Participant(participant)) => {
+                if pda::participant(participant.event_id, &participant.wallet).0 != address {
+                    return;
+                }
+                let index = self.event_by_id(participant.event_id);
+                match index.participants.iter_mut().find(|p| p.address == address) {
+                    Some(current) if current.slot <= slot => *current = stored,
+                    Some(_) => {}
+                    None => index.participants.push(stored),
+                }
+            }
+            Some(PayraAccount::EventCounter(_)) | None => {}
+        }
+    }
+}
+
+// Position of the event account and of the proposal account, if any, in
+// each instruction: the field order of its #[derive(Accounts)] struct.
+// Instructions that don't change an event are not indexed.
+fn account_positions(instruction: &PayraInstruction) -> Option<(usize, Option<usize>)> {
+    use PayraInstruction as I;
+    match instruction {
+        I::CreateEvent(_) => Some((2, None)),
+        I::Contribute(_) => Some((3, None)),
+        I::CloseEvent(_)
+        | I::SetContributionRules(_)
+        | I::SetDues(_)
+        | I::SetEventAccess(_)
+        | I::Whitelist(_)
+        | I::JoinEvent(_)
+        | I::CreateSession(_)
+        | I::SetMemberRole(_)
+        | I::ApplyWithdrawAccountChange(_)
+        | I::ProposeCreatorTransfer(_)
+        | I::AcceptCreatorTransfer(_)
+        | I::Fractionalize(_)
+        | I::ClaimShares(_)
+        | I::Buyout(_) => Some((1, None)),
+        I::Vote(_) | I::CreateGovernanceProposal(_) | I::CreateMilestoneProposal(_) => {
+            Some((1, Some(3)))
+        }
+        I::CreateProposal(_)
+        | I::CreateSettleProposal(_)
+        | I::SettleProposal(_)
+        | I::ReleaseMilestone(_) => Some((1, Some(4))),
+        I::SettleEvent(_)
+        | I::SettleGovernanceProposal(_)
+        | I::ApproveMilestone(_)
+        | I::CancelMilestones(_) => Some((1, Some(2))),
+        // redemptions only touch the fractional vault, revoking a session the
+        // session account
+        I::Initialize(_)
+        | I::RevokeSession(_)
+        | I::RedeemShares(_)
+        | I::RedeemNft(_)
+        | I::GetEventSummary(_)
+        | I::PreviewSettlement(_)
+        | I::GetParticipantBalance(_)
+        | I::GetDuesStatus(_)
+        | I::MigrateEvent(_)
+        | I::MigrateProposal(_)
+        | I::MigrateParticipant(_) => None,
+    }
+}
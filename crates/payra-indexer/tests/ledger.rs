@@ -0,0 +1,717 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, AccountSerialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use payra::{
+    ContributionRules, Event, EventAccess, EventArgs, FractionalStatus, FractionalVault,
+    InviteVoucher, MemberRole, MilestoneRelease, Proposal, ProposalType, SpendingShare,
+    EVENT_VERSION, FRACTIONAL_VAULT_VERSION, PROPOSAL_VERSION,
+};
+use payra_client::{instructions as ix, invite::SignedInvite, pda};
+use payra_indexer::{EntryKind, IndexedTransaction, Store, TokenChange};
+use serde_json::json;
+use solana_transaction::Transaction;
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+
+const EVENT_ID: u64 = 0;
+
+struct Fixture {
+    creator: Pubkey,
+    member: Pubkey,
+    mint: Pubkey,
+    event: Event,
+    slot: u64,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let creator = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let event = Event {
+            version: EVENT_VERSION,
+            event_id: EVENT_ID,
+            creator,
+            pending_creator: None,
+            mint,
+            withdraw_token_account: Pubkey::new_unique(),
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
+            target_amount: 1_000,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
+            total_contributed: 0,
+            total_spent: 0,
+            whitelist: vec![member],
+            roles: vec![MemberRole::Member],
+            contributors: Vec::new(),
+            is_cancelled: false,
+            is_finalized: false,
+            deadline: 100,
+            proposal_count: 0,
+            name: "trip".to_string(),
+            bump: pda::event(EVENT_ID).1,
+        };
+        Self {
+            creator,
+            member,
+            mint,
+            event,
+            slot: 0,
+        }
+    }
+
+    fn event_key(&self) -> Pubkey {
+        pda::event(EVENT_ID).0
+    }
+
+    // transaction moving the vault balance by `before -> after`
+    fn transaction(
+        &mut self,
+        instructions: Vec<Instruction>,
+        vault: Option<(u64, u64)>,
+    ) -> IndexedTransaction {
+        self.slot += 1;
+        let event = self.event_key();
+        IndexedTransaction {
+            slot: self.slot,
+            signature: format!("signature-{}", self.slot),
+            block_time: Some(self.slot as i64 * 10),
+            instructions,
+            token_changes: vault
+                .map(|(before, after)| TokenChange {
+                    account: pda::event_vault(&event, &self.mint),
+                    owner: Some(event),
+                    before,
+                    after,
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn spending(&self, amount: u64) -> Proposal {
+        Proposal {
+            version: PROPOSAL_VERSION,
+            proposal_id: self.event.proposal_count,
+            title: "dinner".to_string(),
+            proposal_type: ProposalType::Spending,
+            amount,
+            spendings: vec![SpendingShare {
+                wallet: self.member,
+                percentage: 100,
+            }],
+            milestones: Vec::new(),
+            yes_votes: Vec::new(),
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: self.creator,
+            deadline: 200,
+            settled: false,
+            cancelled: false,
+            fractional_vault: None,
+            bump: pda::proposal(&self.event_key(), self.event.proposal_count).1,
+        }
+    }
+
+    // a full event lifecycle: creation, two contributions (the second one
+    // trimmed by the program), an approved and a vetoed spending, settlement
+    fn lifecycle(&mut self) -> Vec<IndexedTransaction> {
+        let (creator, member, mint) = (self.creator, self.member, self.mint);
+        let mut transactions = vec![
+            self.transaction(
+                vec![ix::create_event(
+                    creator,
+                    EVENT_ID,
+                    mint,
+                    self.event.withdraw_token_account,
+                    EventArgs {
+                        name: "trip".to_string(),
+                        deadline: 100,
+                        target_amount: 1_000,
+                    },
+                )],
+                None,
+            ),
+            self.transaction(vec![ix::whitelist(creator, EVENT_ID, vec![member])], None),
+            self.transaction(
+                vec![ix::contribute(creator, EVENT_ID, mint, 600)],
+                Some((0, 600)),
+            ),
+            self.transaction(
+                vec![ix::contribute(member, EVENT_ID, mint, 700)],
+                Some((600, 1_000)),
+            ),
+        ];
+
+        for (approve, paid) in [(true, 300), (false, 0)] {
+            let proposal = self.spending(300);
+            transactions.push(self.transaction(
+                vec![ix::create_proposal(
+                    creator,
+                    &self.event,
+                    proposal.title.clone(),
+                    proposal.amount,
+                    proposal.spendings.clone(),
+                    proposal.deadline,
+                )],
+                None,
+            ));
+            for voter in [creator, member] {
+                transactions.push(self.transaction(
+                    vec![ix::vote(
+                        voter,
+                        EVENT_ID,
+                        proposal.proposal_id,
+                        mint,
+                        approve,
+                    )],
+                    None,
+                ));
+            }
+            let vault = (1_000, 1_000 - paid);
+            transactions.push(self.transaction(
+                vec![ix::settle_proposal(creator, &self.event, &proposal)],
+                Some(vault),
+            ));
+            self.event.proposal_count += 1;
+        }
+
+        let settle_id = self.event.proposal_count;
+        transactions.push(self.transaction(
+            vec![ix::create_settle_proposal(creator, &self.event, 300)],
+            None,
+        ));
+        self.event.proposal_count += 1;
+        transactions.push(self.transaction(
+            vec![ix::settle_event(creator, &self.event, settle_id)],
+            None,
+        ));
+        transactions
+    }
+}
+
+fn store_of(transactions: &[IndexedTransaction]) -> Store {
+    let mut store = Store::default();
+    for transaction in transactions {
+        store.apply_transaction(transaction);
+    }
+    store
+}
+
+#[test]
+fn builds_the_ledger_of_an_event() {
+    let mut fixture = Fixture::new();
+    let store = store_of(&fixture.lifecycle());
+    let index = store.event(EVENT_ID).unwrap();
+    assert_eq!(index.address, fixture.event_key());
+
+    let kinds: Vec<&str> = index
+        .ledger
+        .iter()
+        .map(|entry| match entry.kind {
+            EntryKind::EventCreated { .. } => "created",
+            EntryKind::MembersAdded { .. } => "members",
+            EntryKind::Contributed { .. } => "contributed",
+            EntryKind::ProposalCreated { .. } => "proposed",
+            EntryKind::Voted { .. } => "voted",
+            EntryKind::SpendingSettled { .. } => "spent",
+            EntryKind::EventSettled { .. } => "settled",
+            _ => "other",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "created",
+            "members",
+            "contributed",
+            "contributed",
+            "proposed",
+            "voted",
+            "voted",
+            "spent",
+            "proposed",
+            "voted",
+            "voted",
+            "spent",
+            "proposed",
+            "settled",
+        ]
+    );
+    // entries keep the order the transactions ran in
+    assert!(index.ledger.windows(2).all(|w| w[0].slot < w[1].slot));
+}
+
+#[test]
+fn amounts_come_from_the_vault_balance() {
+    let mut fixture = Fixture::new();
+    let store = store_of(&fixture.lifecycle());
+    let index = store.event(EVENT_ID).unwrap();
+
+    let contributions: Vec<(Pubkey, u64)> = index
+        .contributions()
+        .map(|entry| match entry.kind {
+            EntryKind::Contributed { wallet, amount } => (wallet, amount),
+            _ => unreachable!(),
+        })
+        .collect();
+    // the member asked for 700 but only 400 were left to the target
+    assert_eq!(
+        contributions,
+        [(fixture.creator, 600), (fixture.member, 400)]
+    );
+
+    let settlements: Vec<&EntryKind> = index.settlements().map(|entry| &entry.kind).collect();
+    assert_eq!(
+        settlements,
+        [
+            &EntryKind::SpendingSettled {
+                proposal_id: 0,
+                paid: 300
+            },
+            &EntryKind::SpendingSettled {
+                proposal_id: 1,
+                paid: 0
+            },
+            &EntryKind::EventSettled { proposal_id: 2 },
+        ]
+    );
+}
+
+#[test]
+fn records_milestone_releases() {
+    let mut fixture = Fixture::new();
+    let (creator, member, mint) = (fixture.creator, fixture.member, fixture.mint);
+    let mut proposal = fixture.spending(300);
+    proposal.proposal_type = ProposalType::Milestones {
+        release: MilestoneRelease::Approval,
+    };
+    let transactions = vec![
+        fixture.transaction(
+            vec![ix::create_event(
+                creator,
+                EVENT_ID,
+                mint,
+                fixture.event.withdraw_token_account,
+                EventArgs {
+                    name: "trip".to_string(),
+                    deadline: 100,
+                    target_amount: 1_000,
+                },
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::create_milestone_proposal(
+                creator,
+                &fixture.event,
+                "renovation".to_string(),
+                vec![100, 200],
+                MilestoneRelease::Approval,
+                proposal.spendings.clone(),
+                proposal.deadline,
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::release_milestone(creator, &fixture.event, &proposal)],
+            Some((600, 500)),
+        ),
+        // sharing the transaction with the approval, the tranche amount comes
+        // from the proposal creation
+        fixture.transaction(
+            vec![
+                ix::approve_milestone(member, EVENT_ID, 0),
+                ix::release_milestone(creator, &fixture.event, &proposal),
+            ],
+            Some((500, 300)),
+        ),
+        fixture.transaction(vec![ix::cancel_milestones(creator, EVENT_ID, 0)], None),
+    ];
+    let store = store_of(&transactions);
+    let index = store.event(EVENT_ID).unwrap();
+
+    let settlements: Vec<&EntryKind> = index.settlements().map(|entry| &entry.kind).collect();
+    assert_eq!(
+        settlements,
+        [
+            &EntryKind::MilestoneReleased {
+                proposal_id: 0,
+                milestone: 0,
+                paid: 100
+            },
+            &EntryKind::MilestoneReleased {
+                proposal_id: 0,
+                milestone: 1,
+                paid: 200
+            },
+            &EntryKind::MilestonesCancelled { proposal_id: 0 },
+        ]
+    );
+    assert!(index.ledger.iter().any(|entry| entry.kind
+        == EntryKind::MilestoneApproved {
+            proposal_id: 0,
+            approver: member
+        }));
+}
+
+#[test]
+fn records_fractional_ownership() {
+    let mut fixture = Fixture::new();
+    let (creator, member, mint) = (fixture.creator, fixture.member, fixture.mint);
+    let nft_mint = Pubkey::new_unique();
+    let buyer = Pubkey::new_unique();
+    let (vault_key, bump) = pda::fractional_vault(&fixture.event_key(), &nft_mint);
+    let (share_mint, share_mint_bump) = pda::share_mint(&vault_key);
+    let vault = FractionalVault {
+        version: FRACTIONAL_VAULT_VERSION,
+        event: fixture.event_key(),
+        mint,
+        nft_mint,
+        share_mint,
+        proposal: pda::proposal(&fixture.event_key(), 0).0,
+        total_shares: 1_000,
+        redeemed_shares: 0,
+        reserve_price: 500,
+        claimed: Vec::new(),
+        status: FractionalStatus::Active,
+        bump,
+        share_mint_bump,
+    };
+    let transactions = vec![
+        fixture.transaction(
+            vec![ix::create_event(
+                creator,
+                EVENT_ID,
+                mint,
+                fixture.event.withdraw_token_account,
+                EventArgs {
+                    name: "trip".to_string(),
+                    deadline: 100,
+                    target_amount: 1_000,
+                },
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::fractionalize(creator, &fixture.event, 0, nft_mint)],
+            None,
+        ),
+        fixture.transaction(vec![ix::claim_shares(member, EVENT_ID, &nft_mint)], None),
+        fixture.transaction(vec![ix::buyout(buyer, &vault, 800)], None),
+        // redemptions don't name the event, they stay out of its ledger
+        fixture.transaction(vec![ix::redeem_shares(member, &vault, 400)], None),
+    ];
+    let store = store_of(&transactions);
+    let index = store.event(EVENT_ID).unwrap();
+
+    let kinds: Vec<&EntryKind> = index.ledger[1..].iter().map(|entry| &entry.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            &EntryKind::NftFractionalized { nft_mint },
+            &EntryKind::SharesClaimed { wallet: member },
+            &EntryKind::BoughtOut { buyer, price: 800 },
+        ]
+    );
+}
+
+#[test]
+fn records_members_joining_with_an_invite() {
+    let mut fixture = Fixture::new();
+    let (creator, mint) = (fixture.creator, fixture.mint);
+    let joiner = Pubkey::new_unique();
+    let invite = SignedInvite {
+        voucher: InviteVoucher {
+            event: fixture.event_key(),
+            issuer: creator,
+            invite_id: 1,
+            role: MemberRole::Observer,
+            max_uses: 3,
+            expires_at: 100,
+        },
+        signature: [0; 64],
+    };
+    // sources only keep Payra instructions, the ed25519 verification is dropped
+    let [_, join] = ix::join_event(joiner, &invite).unwrap();
+    let transactions = vec![
+        fixture.transaction(
+            vec![ix::create_event(
+                creator,
+                EVENT_ID,
+                mint,
+                fixture.event.withdraw_token_account,
+                EventArgs {
+                    name: "trip".to_string(),
+                    deadline: 100,
+                    target_amount: 1_000,
+                },
+            )],
+            None,
+        ),
+        fixture.transaction(vec![join], None),
+    ];
+    let store = store_of(&transactions);
+    let index = store.event(EVENT_ID).unwrap();
+
+    assert_eq!(
+        index.ledger.last().map(|entry| &entry.kind),
+        Some(&EntryKind::MemberJoined {
+            wallet: joiner,
+            role: "observer".to_string(),
+            issuer: creator,
+            invite_id: 1
+        })
+    );
+}
+
+#[test]
+fn records_access_changes() {
+    let mut fixture = Fixture::new();
+    let (creator, mint) = (fixture.creator, fixture.mint);
+    let transactions = vec![
+        fixture.transaction(
+            vec![ix::create_event(
+                creator,
+                EVENT_ID,
+                mint,
+                fixture.event.withdraw_token_account,
+                EventArgs {
+                    name: "trip".to_string(),
+                    deadline: 100,
+                    target_amount: 1_000,
+                },
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::set_event_access(
+                creator,
+                EVENT_ID,
+                EventAccess::Open {
+                    voting_threshold: 50,
+                },
+            )],
+            None,
+        ),
+    ];
+    let store = store_of(&transactions);
+    let index = store.event(EVENT_ID).unwrap();
+
+    assert_eq!(
+        index.ledger.last().map(|entry| &entry.kind),
+        Some(&EntryKind::AccessChanged {
+            open: true,
+            voting_threshold: 50
+        })
+    );
+}
+
+#[test]
+fn records_votes_signed_with_a_session_key() {
+    let mut fixture = Fixture::new();
+    let (creator, member, mint) = (fixture.creator, fixture.member, fixture.mint);
+    let session_key = Pubkey::new_unique();
+    let proposal = fixture.spending(300);
+    let transactions = vec![
+        fixture.transaction(
+            vec![ix::create_event(
+                creator,
+                EVENT_ID,
+                mint,
+                fixture.event.withdraw_token_account,
+                EventArgs {
+                    name: "trip".to_string(),
+                    deadline: 100,
+                    target_amount: 1_000,
+                },
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::create_proposal(
+                creator,
+                &fixture.event,
+                proposal.title.clone(),
+                proposal.amount,
+                proposal.spendings.clone(),
+                proposal.deadline,
+            )],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::create_session(member, EVENT_ID, session_key, 50)],
+            None,
+        ),
+        fixture.transaction(
+            vec![ix::session_vote(
+                session_key,
+                member,
+                EVENT_ID,
+                proposal.proposal_id,
+                mint,
+                true,
+            )],
+            None,
+        ),
+    ];
+    let store = store_of(&transactions);
+    let index = store.event(EVENT_ID).unwrap();
+    let kinds: Vec<&EntryKind> = index.ledger.iter().map(|entry| &entry.kind).collect();
+
+    assert_eq!(
+        kinds[2..],
+        [
+            &EntryKind::SessionCreated {
+                member,
+                session_key,
+                expires_at: 50
+            },
+            // the vote counts for the member, not the key that signed it
+            &EntryKind::Voted {
+                proposal_id: proposal.proposal_id,
+                voter: member,
+                approve: true
+            },
+        ]
+    );
+}
+
+#[test]
+fn filters_votes_by_proposal() {
+    let mut fixture = Fixture::new();
+    let store = store_of(&fixture.lifecycle());
+    let index = store.event(EVENT_ID).unwrap();
+
+    assert_eq!(index.votes(None).count(), 4);
+    let vetoed: Vec<(Pubkey, bool)> = index
+        .votes(Some(1))
+        .map(|entry| match entry.kind {
+            EntryKind::Voted { voter, approve, .. } => (voter, approve),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(vetoed, [(fixture.creator, false), (fixture.member, false)]);
+}
+
+#[test]
+fn replaying_a_transaction_changes_nothing() {
+    let mut fixture = Fixture::new();
+    let transactions = fixture.lifecycle();
+    let mut store = store_of(&transactions);
+    let entries = store.event(EVENT_ID).unwrap().ledger.len();
+
+    for transaction in &transactions {
+        store.apply_transaction(transaction);
+    }
+    assert_eq!(store.event(EVENT_ID).unwrap().ledger.len(), entries);
+    assert_eq!(
+        store.last_signature,
+        transactions.last().map(|t| t.signature.clone())
+    );
+}
+
+#[test]
+fn store_survives_a_roundtrip() {
+    let mut fixture = Fixture::new();
+    let mut store = store_of(&fixture.lifecycle());
+    let mut data = Vec::new();
+    fixture.event.try_serialize(&mut data).unwrap();
+    store.apply_account(fixture.event_key(), 42, data);
+
+    let path = std::env::temp_dir().join(format!("payra-index-{}.json", std::process::id()));
+    store.save(&path).unwrap();
+    let loaded = Store::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let (before, after) = (
+        store.event(EVENT_ID).unwrap(),
+        loaded.event(EVENT_ID).unwrap(),
+    );
+    assert_eq!(before.ledger, after.ledger);
+    assert_eq!(loaded.last_signature, store.last_signature);
+    assert_eq!(after.event().unwrap().name, "trip");
+    assert_eq!(after.proposals().len(), 0);
+}
+
+#[test]
+fn keeps_the_newest_account_snapshot() {
+    let mut fixture = Fixture::new();
+    let mut store = Store::default();
+    let snapshot = |event: &Event| {
+        let mut data = Vec::new();
+        event.try_serialize(&mut data).unwrap();
+        data
+    };
+
+    store.apply_account(fixture.event_key(), 10, snapshot(&fixture.event));
+    fixture.event.name = "older".to_string();
+    store.apply_account(fixture.event_key(), 5, snapshot(&fixture.event));
+    // an event stored under another address is ignored
+    store.apply_account(Pubkey::new_unique(), 20, snapshot(&fixture.event));
+
+    let index = store.event(EVENT_ID).unwrap();
+    assert_eq!(index.event().unwrap().name, "trip");
+}
+
+#[test]
+fn decodes_rpc_transactions() {
+    let fixture = Fixture::new();
+    let event = fixture.event_key();
+    let vault = pda::event_vault(&event, &fixture.mint);
+    let transaction = Transaction::new_with_payer(
+        &[ix::contribute(fixture.creator, EVENT_ID, fixture.mint, 250)],
+        Some(&fixture.creator),
+    );
+    let keys = &transaction.message.account_keys;
+    let vault_index = keys.iter().position(|key| *key == vault).unwrap();
+    let balance = |amount: &str| {
+        json!([{
+            "accountIndex": vault_index,
+            "mint": fixture.mint.to_string(),
+            "owner": event.to_string(),
+            "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "uiTokenAmount": {
+                "amount": amount,
+                "decimals": 6,
+                "uiAmount": null,
+                "uiAmountString": amount,
+            },
+        }])
+    };
+    let meta = |err: serde_json::Value| {
+        json!({
+            "err": err,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": balance("100"),
+            "postTokenBalances": balance("350"),
+        })
+    };
+    let encoded = |err| -> EncodedConfirmedTransactionWithStatusMeta {
+        serde_json::from_value(json!({
+            "slot": 7,
+            "blockTime": 70,
+            "transaction": [
+                STANDARD.encode(bincode::serialize(&transaction).unwrap()),
+                "base64",
+            ],
+            "meta": meta(err),
+        }))
+        .unwrap()
+    };
+
+    let indexed = IndexedTransaction::from_encoded(&encoded(serde_json::Value::Null))
+        .unwrap()
+        .unwrap();
+    assert_eq!(indexed.slot, 7);
+    assert_eq!(indexed.block_time, Some(70));
+    assert_eq!(indexed.instructions.len(), 1);
+    assert_eq!(indexed.balance_change(&event), 250);
+
+    // failed transactions are skipped
+    let failed = encoded(json!({ "InstructionError": [0, { "Custom": 6000 }] }));
+    assert!(IndexedTransaction::from_encoded(&failed).unwrap().is_none());
+}
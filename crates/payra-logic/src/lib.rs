@@ -0,0 +1,346 @@
+//! Accounting and governance rules of the Payra program, kept free of
+//! accounts and of anchor. The crate is `no_std` so they only depend on
+//! `core`, the tests alone link std. The program re-exports it as
+//! `payra::logic`, handlers map a `None` / `false` to the matching PayraError.
+
+#![cfg_attr(not(test), no_std)]
+
+// spending shares of a proposal must add up to this
+pub const FULL_SPLIT: u32 = 100;
+
+pub fn total_percentage(percentages: impl IntoIterator<Item = u8>) -> u32 {
+    percentages.into_iter().map(u32::from).sum()
+}
+
+pub fn is_full_split(percentages: impl IntoIterator<Item = u8>) -> bool {
+    total_percentage(percentages) == FULL_SPLIT
+}
+
+// `percentage` of `amount`, rounded down
+pub fn share_floor(amount: u64, percentage: u8) -> Option<u64> {
+    u64::try_from(amount as u128 * percentage as u128 / FULL_SPLIT as u128).ok()
+}
+
+// Share of the wallet at `index` in a full split. Rounding down leaves less
+// than one unit per share, that remainder goes one unit each to the first
+// shares so the shares always add up to `amount`.
+pub fn split_share(amount: u64, percentages: &[u8], index: usize) -> Option<u64> {
+    if !is_full_split(percentages.iter().copied()) {
+        return None;
+    }
+    let mut floored: u64 = 0;
+    for percentage in percentages {
+        floored = floored.checked_add(share_floor(amount, *percentage)?)?;
+    }
+    let remainder = amount.checked_sub(floored)?;
+    let share = share_floor(amount, *percentages.get(index)?)?;
+    share.checked_add(u64::from((index as u64) < remainder))
+}
+
+// what a participant is owed back once the event settles, negative when they
+// spent more than they put in
+pub fn net_owed(contributed: u64, spent: u64) -> Option<i64> {
+    i64::try_from(contributed as i128 - spent as i128).ok()
+}
+
+// contribution expected from each member of an equal share event
+pub fn equal_share(target: u64, members: usize) -> Option<u64> {
+    target.checked_div(u64::try_from(members).ok()?)
+}
+
+// Dues periods started by `now`, the running one included. A period is
+// `period` seconds long, the first one starts at `start`.
+pub fn periods_started(start: i64, period: i64, now: i64) -> Option<u32> {
+    if period <= 0 {
+        return None;
+    }
+    if now < start {
+        return Some(0);
+    }
+    let elapsed = (now as i128 - start as i128) / period as i128;
+    u32::try_from(elapsed + 1).ok()
+}
+
+// number of periods a contribution pays for, it must cover whole periods
+pub fn dues_periods(amount: u64, dues: u64) -> Option<u32> {
+    if amount.checked_rem(dues) != Some(0) {
+        return None;
+    }
+    u32::try_from(amount / dues).ok()
+}
+
+// Part of the buyout proceeds paid for `shares` out of the `outstanding`
+// ones. Rounding down leaves the dust in the proceeds, the last holder gets it.
+pub fn redemption_payout(proceeds: u64, shares: u64, outstanding: u64) -> Option<u64> {
+    if shares > outstanding {
+        return None;
+    }
+    if shares == outstanding {
+        return Some(proceeds);
+    }
+    let payout = (proceeds as u128) * (shares as u128) / (outstanding as u128);
+    u64::try_from(payout).ok()
+}
+
+// Layout of an ed25519 program instruction: a count and a padding byte, then
+// one 14 byte offsets entry per signature. An instruction index of u16::MAX
+// points at the ed25519 instruction's own data.
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+const ED25519_SAME_INSTRUCTION: u16 = u16::MAX;
+
+// Public key and message of an ed25519 program instruction verifying a single
+// signature carried in its own data. The runtime rejects the transaction when
+// the signature is wrong, so a parsed instruction means the message was signed.
+pub fn ed25519_signed_message(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.first() != Some(&1) {
+        return None;
+    }
+    let offsets = data.get(ED25519_OFFSETS_START..ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE)?;
+    let field = |i: usize| u16::from_le_bytes([offsets[2 * i], offsets[2 * i + 1]]);
+    let [_, signature_ix, public_key, public_key_ix, message, message_size, message_ix] =
+        [0, 1, 2, 3, 4, 5, 6].map(field);
+    if [signature_ix, public_key_ix, message_ix] != [ED25519_SAME_INSTRUCTION; 3] {
+        return None;
+    }
+
+    let public_key = data.get(public_key as usize..public_key as usize + 32)?;
+    let message = data.get(message as usize..message as usize + message_size as usize)?;
+    Some((public_key, message))
+}
+
+pub fn has_voted<K: PartialEq>(yes_votes: &[K], no_votes: &[K], voter: &K) -> bool {
+    yes_votes.contains(voter) || no_votes.contains(voter)
+}
+
+// votes on a proposal against the number of wallets allowed to vote on it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tally {
+    pub yes: usize,
+    pub no: usize,
+    pub eligible: usize,
+}
+
+impl Tally {
+    pub fn votes_cast(&self) -> usize {
+        self.yes.saturating_add(self.no)
+    }
+
+    // deadline passed or everyone voted
+    pub fn can_settle(&self, now: i64, deadline: i64) -> bool {
+        now > deadline || self.votes_cast() == self.eligible
+    }
+
+    // 50% or more of the eligible voters said yes
+    pub fn has_majority(&self) -> bool {
+        self.eligible > 0 && (self.yes as u128) * 2 >= self.eligible as u128
+    }
+
+    // a single no rejects a spending proposal
+    pub fn is_vetoed(&self) -> bool {
+        self.no > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // 1 to 10 non-zero percentages adding up to 100
+    fn full_split() -> impl Strategy<Value = Vec<u8>> {
+        prop::collection::btree_set(1u8..100, 0..10).prop_map(|cuts| {
+            let mut percentages = Vec::new();
+            let mut last = 0;
+            for cut in cuts.into_iter().chain([100]) {
+                percentages.push(cut - last);
+                last = cut;
+            }
+            percentages
+        })
+    }
+
+    #[test]
+    fn splits_rounding_remainder_over_the_first_shares() {
+        let percentages = [34, 33, 33];
+        let shares: Vec<u64> = (0..3)
+            .map(|i| split_share(100, &percentages, i).unwrap())
+            .collect();
+        assert_eq!(shares, vec![34, 33, 33]);
+
+        let shares: Vec<u64> = (0..3)
+            .map(|i| split_share(10, &percentages, i).unwrap())
+            .collect();
+        assert_eq!(shares, vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn rejects_splits_not_adding_up_to_100() {
+        assert_eq!(split_share(100, &[50, 40], 0), None);
+        assert_eq!(split_share(100, &[60, 60], 0), None);
+        assert_eq!(split_share(100, &[50, 50], 2), None);
+    }
+
+    #[test]
+    fn majority_needs_half_of_the_eligible_voters() {
+        let tally = |yes, no, eligible| Tally { yes, no, eligible };
+        assert!(tally(1, 1, 2).has_majority());
+        assert!(!tally(1, 2, 3).has_majority());
+        assert!(tally(2, 0, 3).has_majority());
+        assert!(!tally(0, 0, 0).has_majority());
+    }
+
+    #[test]
+    fn settles_after_the_deadline_or_once_everyone_voted() {
+        let tally = Tally {
+            yes: 1,
+            no: 0,
+            eligible: 2,
+        };
+        assert!(!tally.can_settle(100, 100));
+        assert!(tally.can_settle(101, 100));
+        assert!(Tally { no: 1, ..tally }.can_settle(0, 100));
+    }
+
+    #[test]
+    fn equal_share_of_no_members_is_undefined() {
+        assert_eq!(equal_share(100, 0), None);
+        assert_eq!(equal_share(100, 3), Some(33));
+    }
+
+    #[test]
+    fn redemption_pays_pro_rata() {
+        assert_eq!(redemption_payout(1_000, 1, 3), Some(333));
+        assert_eq!(redemption_payout(667, 2, 2), Some(667));
+        assert_eq!(redemption_payout(1_000, 4, 3), None);
+    }
+
+    // same layout as the ed25519 instructions built by the SDKs
+    fn ed25519_data(public_key: &[u8; 32], message: &[u8], instruction_index: u16) -> Vec<u8> {
+        let mut data = vec![1, 0];
+        let (public_key_offset, signature_offset) = (16u16, 48u16);
+        let message_offset = signature_offset + 64;
+        for field in [
+            signature_offset,
+            instruction_index,
+            public_key_offset,
+            instruction_index,
+            message_offset,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(public_key);
+        data.extend_from_slice(&[7; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn reads_the_signed_message() {
+        let data = ed25519_data(&[3; 32], b"invite", u16::MAX);
+        assert_eq!(
+            ed25519_signed_message(&data),
+            Some((&[3u8; 32][..], &b"invite"[..]))
+        );
+    }
+
+    #[test]
+    fn ignores_signatures_over_other_instructions() {
+        assert_eq!(
+            ed25519_signed_message(&ed25519_data(&[3; 32], b"invite", 0)),
+            None
+        );
+        let mut two_signatures = ed25519_data(&[3; 32], b"invite", u16::MAX);
+        two_signatures[0] = 2;
+        assert_eq!(ed25519_signed_message(&two_signatures), None);
+        assert_eq!(ed25519_signed_message(&[1, 0, 16]), None);
+    }
+
+    #[test]
+    fn counts_the_running_dues_period() {
+        assert_eq!(periods_started(100, 10, 99), Some(0));
+        assert_eq!(periods_started(100, 10, 100), Some(1));
+        assert_eq!(periods_started(100, 10, 109), Some(1));
+        assert_eq!(periods_started(100, 10, 110), Some(2));
+        assert_eq!(periods_started(100, 0, 110), None);
+    }
+
+    #[test]
+    fn dues_cover_whole_periods() {
+        assert_eq!(dues_periods(300, 100), Some(3));
+        assert_eq!(dues_periods(250, 100), None);
+        assert_eq!(dues_periods(100, 0), None);
+    }
+
+    proptest! {
+        #[test]
+        fn shares_add_up_to_the_amount(amount in any::<u64>(), percentages in full_split()) {
+            let mut total: u128 = 0;
+            for i in 0..percentages.len() {
+                let share = split_share(amount, &percentages, i).unwrap();
+                prop_assert!(share <= amount);
+                total += share as u128;
+            }
+            prop_assert_eq!(total, amount as u128);
+        }
+
+        #[test]
+        fn share_floor_never_overflows(amount in any::<u64>(), percentage in 0u8..=100) {
+            let share = share_floor(amount, percentage).unwrap();
+            prop_assert!(share <= amount);
+        }
+
+        #[test]
+        fn net_owed_fits_or_is_none(contributed in any::<u64>(), spent in any::<u64>()) {
+            let exact = contributed as i128 - spent as i128;
+            match net_owed(contributed, spent) {
+                Some(owed) => prop_assert_eq!(owed as i128, exact),
+                None => prop_assert!(exact > i64::MAX as i128 || exact < i64::MIN as i128),
+            }
+        }
+
+        #[test]
+        fn majority_matches_the_percentage_rule(eligible in 1usize..=11, yes in 0usize..=11) {
+            let yes = yes.min(eligible);
+            let tally = Tally { yes, no: 0, eligible };
+            prop_assert_eq!(tally.has_majority(), yes * 100 / eligible >= 50);
+        }
+
+        #[test]
+        fn periods_started_never_decrease(
+            start in any::<i64>(),
+            period in 1i64..=i64::MAX,
+            now in any::<i64>(),
+            later in 0i64..1_000_000,
+        ) {
+            let before = periods_started(start, period, now);
+            let after = periods_started(start, period, now.saturating_add(later));
+            if let (Some(before), Some(after)) = (before, after) {
+                prop_assert!(before <= after);
+            }
+        }
+
+        #[test]
+        fn redemptions_pay_out_the_whole_proceeds(
+            proceeds in any::<u64>(),
+            holdings in prop::collection::vec(1u64..1_000_000, 1..10),
+        ) {
+            let mut outstanding: u64 = holdings.iter().sum();
+            let mut left = proceeds;
+            for shares in holdings {
+                let payout = redemption_payout(left, shares, outstanding).unwrap();
+                left -= payout;
+                outstanding -= shares;
+            }
+            prop_assert_eq!(left, 0);
+        }
+
+        #[test]
+        fn percentages_total_never_overflows(percentages in prop::collection::vec(any::<u8>(), 0..64)) {
+            let expected: u32 = percentages.iter().map(|p| *p as u32).sum();
+            prop_assert_eq!(total_percentage(percentages), expected);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Solana Pay transaction request server: a wallet scanning the `solana:`
+//! link of an event gets a `contribute` transaction to sign, partially signed
+//! by the sponsor paying its fees and rent when the server has one.
+//!
+//! GET  /contribute/{event_id}            label and icon shown by the wallet
+//! POST /contribute/{event_id}?amount=N   {"account"} -> {"transaction", "message"}
+
+pub mod request;
+
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use anchor_spl::{associated_token::get_associated_token_address, token::TokenAccount};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use payra_client::{
+    payra::{Event, Participant},
+    pda,
+};
+use serde::{Deserialize, Serialize};
+use solana_keypair::Keypair;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use tower_http::cors::CorsLayer;
+
+use crate::request::{check_contribution, contribution_transaction};
+
+const ICON: &str = include_str!("icon.svg");
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayError {
+    #[error("invalid account: {0}")]
+    InvalidAccount(String),
+    #[error("{0}")]
+    Rejected(String),
+    #[error("event {0} not found")]
+    EventNotFound(u64),
+    #[error("rpc error: {0}")]
+    Rpc(Box<solana_rpc_client_api::client_error::Error>),
+    #[error("{0}")]
+    Client(#[from] payra_client::ClientError),
+}
+
+impl From<solana_rpc_client_api::client_error::Error> for PayError {
+    fn from(err: solana_rpc_client_api::client_error::Error) -> Self {
+        PayError::Rpc(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, PayError>;
+
+// errors carry a message wallets can show
+impl IntoResponse for PayError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PayError::InvalidAccount(_) | PayError::Rejected(_) => StatusCode::BAD_REQUEST,
+            PayError::EventNotFound(_) => StatusCode::NOT_FOUND,
+            PayError::Rpc(_) | PayError::Client(_) => StatusCode::BAD_GATEWAY,
+        };
+        let message = self.to_string();
+        (status, Json(serde_json::json!({ "message": message }))).into_response()
+    }
+}
+
+pub struct PayState {
+    pub rpc: RpcClient,
+    pub label: String,
+    // url wallets reach the server at, the icon is served from it
+    pub public_url: String,
+    // pays fees and rent for contributors who hold no SOL
+    pub sponsor: Option<Keypair>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Metadata {
+    pub label: String,
+    pub icon: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AccountRequest {
+    pub account: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TransactionResponse {
+    // base64 of the serialized transaction, only signed by the sponsor
+    pub transaction: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub struct AmountQuery {
+    // base units of the event mint
+    pub amount: u64,
+}
+
+pub fn router(state: Arc<PayState>) -> Router {
+    Router::new()
+        .route("/contribute/{event_id}", get(metadata).post(transaction))
+        .route("/icon.svg", get(icon))
+        // browser wallets fetch the endpoint cross origin
+        .layer(CorsLayer::permissive())
+        .with_state(state)
+}
+
+async fn metadata(State(state): State<Arc<PayState>>) -> Json<Metadata> {
+    Json(Metadata {
+        label: state.label.clone(),
+        icon: format!("{}/icon.svg", state.public_url.trim_end_matches('/')),
+    })
+}
+
+async fn icon() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "image/svg+xml")], ICON)
+}
+
+async fn transaction(
+    State(state): State<Arc<PayState>>,
+    Path(event_id): Path<u64>,
+    Query(AmountQuery { amount }): Query<AmountQuery>,
+    Json(body): Json<AccountRequest>,
+) -> Result<Json<TransactionResponse>> {
+    let account: Pubkey = body
+        .account
+        .parse()
+        .map_err(|_| PayError::InvalidAccount(body.account.clone()))?;
+
+    let event = fetch_event(&state.rpc, event_id).await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+
+    let token_account = get_associated_token_address(&account, &event.mint);
+    let participant = pda::participant(event_id, &account).0;
+    let mut accounts = state
+        .rpc
+        .get_multiple_accounts(&[token_account, participant])
+        .await?
+        .into_iter();
+    let balance = accounts
+        .next()
+        .flatten()
+        .map(|account| TokenAccount::try_deserialize(&mut account.data.as_slice()))
+        .transpose()
+        .map_err(|_| PayError::InvalidAccount(token_account.to_string()))?
+        .map(|token_account| token_account.amount);
+    let participant: Option<Participant> = accounts
+        .next()
+        .flatten()
+        .map(|account| payra_client::decode(&account.data))
+        .transpose()?;
+    check_contribution(&event, participant.as_ref(), balance, &account, amount, now)?;
+
+    let blockhash = state.rpc.get_latest_blockhash().await?;
+    let tx = contribution_transaction(&event, account, amount, state.sponsor.as_ref(), blockhash);
+    // serializing into memory can't fail
+    let serialized = bincode::serialize(&tx).expect("transaction serializes");
+
+    Ok(Json(TransactionResponse {
+        transaction: BASE64_STANDARD.encode(serialized),
+        message: format!("Contribute {amount} to {}", event.name),
+    }))
+}
+
+async fn fetch_event(rpc: &RpcClient, event_id: u64) -> Result<Event> {
+    let account = rpc
+        .get_multiple_accounts(&[pda::event(event_id).0])
+        .await?
+        .pop()
+        .flatten()
+        .ok_or(PayError::EventNotFound(event_id))?;
+    Ok(payra_client::decode(&account.data)?)
+}
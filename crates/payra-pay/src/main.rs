@@ -0,0 +1,81 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use clap::{Parser, Subcommand};
+use payra_pay::{request::link, router, PayState};
+use solana_commitment_config::CommitmentConfig;
+use solana_keypair::read_keypair_file;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+
+#[derive(Parser)]
+#[command(
+    name = "payra-pay",
+    about = "Solana Pay transaction requests for Payra contributions"
+)]
+struct Cli {
+    /// Url wallets reach the server at, used for links and the icon
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8080")]
+    public_url: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve transaction requests
+    Serve {
+        #[arg(short = 'u', long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: SocketAddr,
+        /// Label shown by the wallet
+        #[arg(long, default_value = "Payra")]
+        label: String,
+        /// Keypair paying fees and rent on behalf of contributors
+        #[arg(long)]
+        sponsor: Option<String>,
+    },
+    /// Print the solana: link to encode in a QR code
+    Link {
+        event_id: u64,
+        /// Amount in base units of the event mint
+        amount: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Serve {
+            url,
+            bind,
+            label,
+            sponsor,
+        } => {
+            let sponsor = sponsor
+                .map(|path| {
+                    read_keypair_file(&path)
+                        .map_err(|err| anyhow!("failed to read keypair {path}: {err}"))
+                })
+                .transpose()?;
+            let state = Arc::new(PayState {
+                rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+                label,
+                public_url: cli.public_url,
+                sponsor,
+            });
+            let listener = tokio::net::TcpListener::bind(bind)
+                .await
+                .with_context(|| format!("binding {bind}"))?;
+            println!("Serving transaction requests on {bind}");
+            axum::serve(listener, router(state)).await?;
+        }
+        Command::Link { event_id, amount } => {
+            println!("{}", link(&cli.public_url, event_id, amount));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use payra_client::{
+    instructions,
+    payra::{validation::validate_contribution, Event, Participant},
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{PayError, Result};
+
+// Everything the program would reject is checked before a transaction is
+// built, so the wallet shows the reason instead of a failed simulation. The
+// checks are the program's own, `participant` is the wallet's participant
+// account once it has contributed and `balance` what its token account of
+// the event mint holds, none when it has no such account.
+pub fn check_contribution(
+    event: &Event,
+    participant: Option<&Participant>,
+    balance: Option<u64>,
+    account: &Pubkey,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    let contribution =
+        validate_contribution(event, account, participant, amount, now).map_err(rejection)?;
+    match balance {
+        None => Err(PayError::Rejected(
+            "this wallet has no token account for the event mint".to_string(),
+        )),
+        Some(balance) if balance < contribution.amount => Err(PayError::Rejected(
+            "this wallet holds less than the amount".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// program errors carry the message of their PayraError variant
+fn rejection(err: anchor_lang::error::Error) -> PayError {
+    match err {
+        anchor_lang::error::Error::AnchorError(err) => PayError::Rejected(err.error_msg),
+        err => PayError::Rejected(err.to_string()),
+    }
+}
+
+// `contribute` transaction signed by the scanning wallet, whose token account
+// must already hold the amount. The participant account is created by the
+// program on the first contribution. Without a sponsor the wallet pays for it
+// and the fees, with one the sponsor does and signs first, so the wallet only
+// has to approve the token transfer.
+pub fn contribution_transaction(
+    event: &Event,
+    account: Pubkey,
+    amount: u64,
+    sponsor: Option<&Keypair>,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let payer = sponsor.map_or(account, |sponsor| sponsor.pubkey());
+    let ix = instructions::sponsored_contribute(payer, account, event.event_id, event.mint, amount);
+
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&payer));
+    tx.message.recent_blockhash = recent_blockhash;
+    if let Some(sponsor) = sponsor {
+        tx.partial_sign(&[sponsor], recent_blockhash);
+    }
+    tx
+}
+
+// `solana:` link of the contribution endpoint of an event, what the QR code
+// encodes. The endpoint url is percent encoded as the spec requires once it
+// carries a query.
+pub fn link(public_url: &str, event_id: u64, amount: u64) -> String {
+    let endpoint = format!(
+        "{}/contribute/{event_id}?amount={amount}",
+        public_url.trim_end_matches('/')
+    );
+    format!("solana:{}", percent_encode(&endpoint))
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() * 3);
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,342 @@
+use std::sync::Arc;
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use payra_client::{
+    payra::{
+        ContributionRules, Dues, Event, EventAccess, MemberRole, OverfundingMode, Participant,
+        EVENT_VERSION, PARTICIPANT_VERSION,
+    },
+    pda,
+};
+use payra_pay::{
+    request::{check_contribution, contribution_transaction, link},
+    router, Metadata, PayError, PayState,
+};
+use solana_keypair::Keypair;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_signer::Signer;
+use tower::ServiceExt;
+
+const EVENT_ID: u64 = 7;
+// what the wallets hold of the event mint
+const BALANCE: Option<u64> = Some(1_000);
+
+fn event(member: Pubkey, observer: Pubkey) -> Event {
+    Event {
+        version: EVENT_VERSION,
+        event_id: EVENT_ID,
+        creator: Pubkey::new_unique(),
+        pending_creator: None,
+        mint: Pubkey::new_unique(),
+        withdraw_token_account: Pubkey::new_unique(),
+        pending_withdraw_token_account: None,
+        withdraw_change_available_at: 0,
+        target_amount: 1_000,
+        contribution_rules: ContributionRules::default(),
+        dues: None,
+        access: EventAccess::Whitelist,
+        total_contributed: 0,
+        total_spent: 0,
+        whitelist: vec![member, observer],
+        roles: vec![MemberRole::Member, MemberRole::Observer],
+        contributors: Vec::new(),
+        is_cancelled: false,
+        is_finalized: false,
+        deadline: 100,
+        proposal_count: 0,
+        name: "trip".to_string(),
+        bump: pda::event(EVENT_ID).1,
+    }
+}
+
+#[test]
+fn builds_an_unsigned_contribution_paid_by_the_wallet() {
+    let member = Pubkey::new_unique();
+    let event = event(member, Pubkey::new_unique());
+    let blockhash = Hash::new_unique();
+
+    let tx = contribution_transaction(&event, member, 250, None, blockhash);
+    assert_eq!(tx.message.account_keys[0], member);
+    assert_eq!(tx.message.recent_blockhash, blockhash);
+    assert_eq!(tx.signatures.len(), 1);
+    assert!(tx.signatures.iter().all(|sig| *sig == Default::default()));
+    assert_eq!(tx.message.instructions.len(), 1);
+    assert_eq!(tx.message.program_id(0), Some(&payra_client::PROGRAM_ID));
+    // the participant account is created by the program itself
+    let participant = pda::participant(EVENT_ID, &member).0;
+    assert!(tx.message.account_keys.contains(&participant));
+}
+
+#[test]
+fn sponsor_pays_and_signs_first() {
+    let member = Pubkey::new_unique();
+    let event = event(member, Pubkey::new_unique());
+    let sponsor = Keypair::new();
+
+    let tx = contribution_transaction(&event, member, 250, Some(&sponsor), Hash::new_unique());
+    assert_eq!(tx.message.account_keys[0], sponsor.pubkey());
+    assert_eq!(tx.message.account_keys[1], member);
+    // the sponsor signed, the wallet still has to
+    assert_eq!(tx.signatures.len(), 2);
+    assert!(tx.signatures[0].verify(sponsor.pubkey().as_ref(), &tx.message_data()));
+    assert_eq!(tx.signatures[1], Default::default());
+    // the member is only a signer, never charged
+    assert!(!tx.message.is_maybe_writable(1, None));
+}
+
+#[test]
+fn rejects_contributions_the_program_would_refuse() {
+    let (member, observer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut event = event(member, observer);
+    let rejected = |result| matches!(result, Err(PayError::Rejected(_)));
+
+    assert!(check_contribution(&event, None, BALANCE, &member, 250, 50).is_ok());
+    assert!(check_contribution(&event, None, BALANCE, &event.creator, 250, 50).is_ok());
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 0, 50
+    )));
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 250, 100
+    )));
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &observer, 250, 50
+    )));
+    assert!(rejected(check_contribution(
+        &event,
+        None,
+        BALANCE,
+        &Pubkey::new_unique(),
+        250,
+        50
+    )));
+    event.dues = Some(Dues {
+        amount: 100,
+        period: 10,
+        start: 0,
+    });
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 250, 50
+    )));
+    assert!(check_contribution(&event, None, BALANCE, &member, 200, 50).is_ok());
+    event.is_cancelled = true;
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 200, 50
+    )));
+}
+
+fn participant(wallet: Pubkey, contributed: u64, periods_paid: u32) -> Participant {
+    Participant {
+        version: PARTICIPANT_VERSION,
+        event: pda::event(EVENT_ID).0,
+        event_id: EVENT_ID,
+        wallet,
+        contributed,
+        spent: 0,
+        refunded: false,
+        bump: pda::participant(EVENT_ID, &wallet).1,
+        net_owed: 0,
+        periods_paid,
+    }
+}
+
+#[test]
+fn applies_the_event_contribution_rules() {
+    let (member, observer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut event = event(member, observer);
+    let rejected = |result| matches!(result, Err(PayError::Rejected(_)));
+    let paid = participant(member, 300, 0);
+
+    event.contribution_rules = ContributionRules {
+        min_contribution: 50,
+        max_per_participant: 400,
+        ..Default::default()
+    };
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 40, 50
+    )));
+    assert!(check_contribution(&event, None, BALANCE, &member, 400, 50).is_ok());
+    // the maximum counts what the wallet already gave
+    assert!(rejected(check_contribution(
+        &event,
+        Some(&paid),
+        BALANCE,
+        &member,
+        150,
+        50
+    )));
+    assert!(check_contribution(&event, Some(&paid), BALANCE, &member, 100, 50).is_ok());
+
+    // creator and member split the 1_000 target, installments up to 500
+    event.contribution_rules = ContributionRules {
+        equal_share: true,
+        ..Default::default()
+    };
+    assert!(check_contribution(&event, None, BALANCE, &member, 200, 50).is_ok());
+    assert!(check_contribution(&event, Some(&paid), BALANCE, &member, 200, 50).is_ok());
+    assert!(rejected(check_contribution(
+        &event,
+        Some(&paid),
+        BALANCE,
+        &member,
+        201,
+        50
+    )));
+
+    event.total_contributed = 900;
+    event.contribution_rules = ContributionRules {
+        overfunding: OverfundingMode::Reject,
+        ..Default::default()
+    };
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 101, 50
+    )));
+    assert!(check_contribution(&event, None, BALANCE, &member, 100, 50).is_ok());
+    // a partial fill only takes what is left
+    event.contribution_rules.overfunding = OverfundingMode::PartialFill;
+    assert!(check_contribution(&event, None, BALANCE, &member, 500, 50).is_ok());
+    event.total_contributed = 1_000;
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 1, 50
+    )));
+}
+
+#[test]
+fn dues_stop_at_the_deadline() {
+    let member = Pubkey::new_unique();
+    let mut event = event(member, Pubkey::new_unique());
+    let rejected = |result| matches!(result, Err(PayError::Rejected(_)));
+    // periods start at 0, 40 and 80 before the deadline at 100
+    event.dues = Some(Dues {
+        amount: 100,
+        period: 40,
+        start: 0,
+    });
+
+    assert!(check_contribution(&event, None, BALANCE, &member, 300, 50).is_ok());
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &member, 400, 50
+    )));
+    let paid = participant(member, 200, 2);
+    assert!(check_contribution(&event, Some(&paid), BALANCE, &member, 100, 50).is_ok());
+    assert!(rejected(check_contribution(
+        &event,
+        Some(&paid),
+        BALANCE,
+        &member,
+        200,
+        50
+    )));
+}
+
+#[test]
+fn the_wallet_must_hold_the_amount() {
+    let member = Pubkey::new_unique();
+    let mut event = event(member, Pubkey::new_unique());
+    let rejected = |result| matches!(result, Err(PayError::Rejected(_)));
+
+    // no token account of the mint, nothing to transfer from
+    assert!(rejected(check_contribution(
+        &event, None, None, &member, 250, 50
+    )));
+    assert!(rejected(check_contribution(
+        &event,
+        None,
+        Some(249),
+        &member,
+        250,
+        50
+    )));
+    assert!(check_contribution(&event, None, Some(250), &member, 250, 50).is_ok());
+
+    // a partial fill only transfers what is left to fund
+    event.total_contributed = 900;
+    event.contribution_rules.overfunding = OverfundingMode::PartialFill;
+    assert!(check_contribution(&event, None, Some(100), &member, 250, 50).is_ok());
+}
+
+#[test]
+fn open_events_accept_anyone_but_observers() {
+    let (member, observer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut event = event(member, observer);
+    event.access = EventAccess::Open {
+        voting_threshold: 100,
+    };
+
+    assert!(check_contribution(&event, None, BALANCE, &Pubkey::new_unique(), 250, 50).is_ok());
+    assert!(matches!(
+        check_contribution(&event, None, BALANCE, &observer, 250, 50),
+        Err(PayError::Rejected(_))
+    ));
+}
+
+#[test]
+fn open_events_reject_what_would_not_fit() {
+    let member = Pubkey::new_unique();
+    let mut event = event(member, Pubkey::new_unique());
+    let rejected = |result| matches!(result, Err(PayError::Rejected(_)));
+    event.access = EventAccess::Open {
+        voting_threshold: 100,
+    };
+
+    let donor = Pubkey::new_unique();
+    // only wallets that already contributed once the contributors are full
+    event.contributors = (0..20).map(|_| Pubkey::new_unique()).collect();
+    assert!(rejected(check_contribution(
+        &event, None, BALANCE, &donor, 10, 50
+    )));
+    let contributor = event.contributors[0];
+    let paid = participant(contributor, 10, 0);
+    assert!(check_contribution(&event, Some(&paid), BALANCE, &contributor, 10, 50).is_ok());
+}
+
+#[test]
+fn link_encodes_the_endpoint() {
+    assert_eq!(
+        link("https://pay.example.com/", 7, 250),
+        "solana:https%3A%2F%2Fpay.example.com%2Fcontribute%2F7%3Famount%3D250"
+    );
+}
+
+fn state() -> Arc<PayState> {
+    Arc::new(PayState {
+        rpc: RpcClient::new_mock("succeeds".to_string()),
+        label: "Payra".to_string(),
+        public_url: "https://pay.example.com".to_string(),
+        sponsor: None,
+    })
+}
+
+#[tokio::test]
+async fn serves_label_and_icon() {
+    let response = router(state())
+        .oneshot(Request::get("/contribute/7").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["access-control-allow-origin"], "*");
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let metadata: Metadata = serde_json::from_slice(&body).unwrap();
+    assert_eq!(metadata.label, "Payra");
+    assert_eq!(metadata.icon, "https://pay.example.com/icon.svg");
+}
+
+#[tokio::test]
+async fn rejects_an_invalid_account() {
+    let response = router(state())
+        .oneshot(
+            Request::post("/contribute/7?amount=250")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"account":"not a key"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["message"], "invalid account: not a key");
+}
@@ -2,4 +2,27 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
-pub const MAX_PARTICIPANTS: u8 = 10;
\ No newline at end of file
+pub const MAX_PARTICIPANTS: u8 = 10;
+// wallets with a participant account, the voters and open event donors, all
+// passed to settle_event in one transaction
+pub const MAX_CONTRIBUTORS: u8 = 20;
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_TITLE_LEN: usize = 32;
+pub const MAX_MILESTONES: u8 = 5;
+
+// current on-chain layout versions, bump when the account layout changes
+pub const EVENT_VERSION: u8 = 1;
+pub const PROPOSAL_VERSION: u8 = 1;
+pub const PARTICIPANT_VERSION: u8 = 1;
+pub const FRACTIONAL_VAULT_VERSION: u8 = 1;
+pub const INVITE_VERSION: u8 = 1;
+pub const SESSION_VERSION: u8 = 1;
+
+// delay between approving a new withdraw account and being able to apply it
+pub const WITHDRAW_CHANGE_TIMELOCK: i64 = 2 * 24 * 60 * 60;
+
+// longest a session key can vote for a member
+pub const MAX_SESSION_DURATION: i64 = 7 * 24 * 60 * 60;
+
+// prepended to a serialized invite voucher to form the message the issuer signs
+pub const INVITE_MESSAGE_PREFIX: &[u8] = b"payra invite:";
@@ -59,5 +59,121 @@ pub enum PayraError {
     #[msg("trying to add duplicate wallet to whitelist")]
     DuplicateWallet,
     #[msg("wallet not found in whitelist")]
-    NotWhitelisted
+    NotWhitelisted,
+
+    #[msg("account has an unknown layout version")]
+    InvalidAccountVersion,
+    #[msg("account is already on the current layout version")]
+    AlreadyMigrated,
+
+    #[msg("proposal type not supported by this instruction")]
+    InvalidProposalType,
+    #[msg("target amount must be greater than zero")]
+    InvalidTargetAmount,
+    #[msg("token account mint doesn't match the event mint")]
+    InvalidMint,
+    #[msg("no withdraw account change is pending")]
+    NoPendingWithdrawChange,
+    #[msg("withdraw account change is still timelocked")]
+    WithdrawChangeTimelocked,
+    #[msg("new creator must be a different wallet")]
+    InvalidNewCreator,
+
+    #[msg("contribution is below the event minimum")]
+    ContributionTooSmall,
+    #[msg("contribution exceeds the per participant maximum")]
+    ContributionTooLarge,
+    #[msg("contribution would bring the total past the equal share")]
+    InvalidEqualShare,
+    #[msg("minimum contribution can't exceed the per participant maximum")]
+    InvalidContributionRules,
+    #[msg("contribution rules can't change once contributions started")]
+    ContributionsAlreadyStarted,
+    #[msg("contribution exceeds the event funding cap")]
+    FundingCapExceeded,
+
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("title too long")]
+    TitleTooLong,
+    #[msg("every spending share needs a percentage above zero")]
+    ZeroPercentage,
+    #[msg("wallet listed twice in spending shares")]
+    DuplicateShareWallet,
+    #[msg("spending share wallet is not a contributing member of the event")]
+    ShareWalletNotWhitelisted,
+    #[msg("too many spending shares")]
+    TooManyShares,
+    #[msg("invalid wallet")]
+    InvalidWallet,
+    #[msg("account is not a proposal of this event")]
+    InvalidProposalAccount,
+
+    #[msg("dues period must be greater than zero")]
+    InvalidDuesPeriod,
+    #[msg("contribution must pay a whole number of dues periods")]
+    InvalidDuesAmount,
+    #[msg("contribution pays dues past the event deadline")]
+    DuesBeyondDeadline,
+    #[msg("event has no dues")]
+    NoDues,
+
+    #[msg("milestone proposals need 1 to 5 milestones, each above zero")]
+    InvalidMilestones,
+    #[msg("milestone proposal hasn't been approved")]
+    MilestonesNotApproved,
+    #[msg("every milestone has been released")]
+    NoPendingMilestone,
+    #[msg("next milestone hasn't been approved yet")]
+    MilestoneNotApproved,
+
+    #[msg("mint is not a non fungible token")]
+    NotAnNft,
+    #[msg("contributions are still open")]
+    ContributionsStillOpen,
+    #[msg("nothing to claim")]
+    NothingToClaim,
+    #[msg("shares already claimed")]
+    SharesAlreadyClaimed,
+    #[msg("NFT is no longer in escrow")]
+    NftNotInEscrow,
+    #[msg("buyout price is below the reserve price")]
+    BuyoutBelowReserve,
+    #[msg("NFT hasn't been bought out")]
+    NotBoughtOut,
+    #[msg("redeeming the NFT takes every share")]
+    NotAllShares,
+
+    #[msg("invite voucher is not for this event")]
+    InvalidInvite,
+    #[msg("invite voucher has expired")]
+    InviteExpired,
+    #[msg("invite voucher has no uses left")]
+    InviteUsedUp,
+    #[msg("invite voucher isn't signed by an admin of the event")]
+    InvalidInviteSignature,
+
+    #[msg("session key is not authorized to vote for this member")]
+    InvalidSession,
+    #[msg("session key has expired")]
+    SessionExpired,
+    #[msg("session expiry must be in the future and within the maximum duration")]
+    InvalidSessionExpiry,
+
+    #[msg("a wallet that contributed can't be made an observer")]
+    ContributorCannotBeObserver,
+    #[msg("equal share voters can't change once contributions started")]
+    EqualShareVotersFrozen,
+    #[msg("NFT has to be paid by a settled spending proposal of the event")]
+    NftNotPaidByEvent,
+    #[msg("event has reached the maximum number of contributors")]
+    TooManyContributors,
+    #[msg("spending proposal already paid for a fractionalized NFT")]
+    ProposalAlreadyFractionalized,
+    #[msg("no reserve price has been voted for the NFT yet")]
+    ReservePriceNotSet,
+    #[msg("fractional vault is not one of this event")]
+    InvalidFractionalVault,
+    #[msg("rent can only be refunded to the account that paid it")]
+    InvalidRentPayer,
 }
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{error::PayraError, Event};
+
+#[derive(Accounts)]
+pub struct ApplyWithdrawAccountChange<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        constraint = Some(new_withdraw_token_account.key()) == event.pending_withdraw_token_account @ PayraError::InvalidWithdrawAccount,
+        constraint = new_withdraw_token_account.mint == event.mint @ PayraError::InvalidMint
+    )]
+    pub new_withdraw_token_account: Account<'info, TokenAccount>,
+}
+
+impl<'info> ApplyWithdrawAccountChange<'info> {
+    pub fn apply_withdraw_account_change(&mut self) -> Result<()> {
+        let event = &mut self.event;
+        let new_account = event
+            .pending_withdraw_token_account
+            .ok_or(PayraError::NoPendingWithdrawChange)?;
+
+        require!(
+            Clock::get()?.unix_timestamp >= event.withdraw_change_available_at,
+            PayraError::WithdrawChangeTimelocked
+        );
+
+        event.withdraw_token_account = new_account;
+        event.pending_withdraw_token_account = None;
+        event.withdraw_change_available_at = 0;
+
+        msg!("Withdraw account changed: {}", new_account);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, MemberRole, MilestoneRelease, Proposal};
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", event.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+impl<'info> ApproveMilestone<'info> {
+    // approves the next tranche, the ones after it are approved once it is released
+    pub fn approve_milestone(&mut self) -> Result<()> {
+        let proposal = &mut self.proposal;
+        let approver = self.approver.key();
+
+        let (release, _) = proposal.pending_milestone()?;
+        require!(
+            release == MilestoneRelease::Approval,
+            PayraError::InvalidProposalType
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        // the wallets sharing the expense approve, like they voted on it
+        require!(
+            self.event.has_role(&approver, MemberRole::Member)
+                && proposal.spendings.iter().any(|s| s.wallet == approver),
+            PayraError::NotAuthorizedToVote
+        );
+        require!(
+            !proposal.milestone_approvals.contains(&approver),
+            PayraError::AlreadyVoted
+        );
+
+        proposal.milestone_approvals.push(approver);
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, MemberRole, Proposal};
+
+#[derive(Accounts)]
+pub struct CancelMilestones<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", event.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+impl<'info> CancelMilestones<'info> {
+    // Stops the payouts of a milestone proposal. Tranches never leave the
+    // vault before they are released, so what wasn't released yet simply
+    // stays in the pool.
+    pub fn cancel_milestones(&mut self) -> Result<()> {
+        require!(
+            self.event.has_role(&self.admin.key(), MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+
+        let proposal = &mut self.proposal;
+        proposal.pending_milestone()?;
+
+        proposal.cancelled = true;
+        proposal.milestone_approvals.clear();
+
+        msg!(
+            "Milestones of proposal {} cancelled, {} returned to the pool",
+            proposal.proposal_id,
+            proposal.amount - proposal.released_amount()
+        );
+        Ok(())
+    }
+}
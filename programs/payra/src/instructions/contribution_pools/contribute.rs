@@ -4,11 +4,15 @@ use anchor_spl::{
     token::{transfer, transfer_checked, Mint, Token, TokenAccount, Transfer, TransferChecked},
 };
 
-use crate::{error::PayraError, Event, Participant};
+use crate::{
+    error::PayraError,
+    validation::{validate_contribution, validate_new_members, Contribution},
+    Event, EventAccess, MemberRole, Participant, PARTICIPANT_VERSION,
+};
 
 #[derive(Accounts)]
 pub struct Contribute<'info> {
-    #[account(mut)]
+    // only signs the token transfer, rent is on the payer
     pub contributor: Signer<'info>,
 
     pub mint: Account<'info, Mint>,
@@ -34,9 +38,13 @@ pub struct Contribute<'info> {
     )]
     pub event_vault: Account<'info, TokenAccount>,
 
+    // the contributor or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init_if_needed,
-        payer = contributor,
+        payer = payer,
         seeds = [b"participant", event.event_id.to_le_bytes().as_ref(), contributor.key().as_ref()],
         space = 8 + Participant::INIT_SPACE,
         bump
@@ -51,20 +59,21 @@ pub struct Contribute<'info> {
 impl<'info> Contribute<'info> {
     pub fn contribute(&mut self, amount: u64, bumps: &ContributeBumps) -> Result<()> {
         let clock = Clock::get()?;
-        // Ensure the deadline has not passed
-        require!(
-            clock.unix_timestamp < self.event.deadline,
-            PayraError::DeadlineAlreadyReached
-        );
-
-        // check whitelisted address
-        require!(
-            self.event.whitelist.contains(&self.contributor.key()) || self.contributor.key() == self.event.creator,
-            PayraError::NotWhitelisted
-        );
-
-        // check whether event is not cancelled 
-        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+        // a fresh participant account is zeroed, so it counts as none
+        let contributor = self.contributor.key();
+        let participant =
+            (self.participant.wallet != Pubkey::default()).then_some(&*self.participant);
+        let Contribution {
+            amount,
+            contributed: participant_total,
+            periods_paid,
+        } = validate_contribution(
+            &self.event,
+            &contributor,
+            participant,
+            amount,
+            clock.unix_timestamp,
+        )?;
 
         // transfer tokens from contributor -> event vault
         let cpi_accounts = TransferChecked {
@@ -81,6 +90,7 @@ impl<'info> Contribute<'info> {
         if self.participant.wallet == Pubkey::default() {
             // account freshly created; initialize
             self.participant.set_inner(Participant {
+                version: PARTICIPANT_VERSION,
                 event: self.event.key(),
                 event_id: self.event.event_id,
                 wallet: self.contributor.key(),
@@ -88,15 +98,13 @@ impl<'info> Contribute<'info> {
                 spent: 0,
                 refunded: false,
                 bump: bumps.participant,
-                net_owed: 0
+                net_owed: 0,
+                periods_paid,
             });
         } else {
             // account exists, update contributed amount by adding
-            self.participant.contributed = self
-                .participant
-                .contributed
-                .checked_add(amount)
-                .ok_or(PayraError::ContributionOverflow)?;
+            self.participant.contributed = participant_total;
+            self.participant.periods_paid = periods_paid;
         }
         
         // update total contributed
@@ -106,6 +114,24 @@ impl<'info> Contribute<'info> {
             .checked_add(amount)
             .ok_or(PayraError::ContributionOverflow)?;
 
+        if !self.event.contributors.contains(&contributor) {
+            self.event.contributors.push(contributor);
+        }
+
+        // open event contributors get a vote once they reach the threshold,
+        // when the whitelist is full they stay donors without one
+        if let EventAccess::Open { voting_threshold } = self.event.access {
+            if participant_total >= voting_threshold && self.event.role_of(&contributor).is_none() {
+                if validate_new_members(&self.event, &[contributor]).is_ok() {
+                    self.event.add_member(contributor, MemberRole::Member);
+                    msg!("{} is now a member and can vote", contributor);
+                } else {
+                    msg!("{} reached the voting threshold, no seat left", contributor);
+                }
+            }
+        }
+
+        msg!("Contributed: {}", amount);
         Ok(())
     }
 }
@@ -2,7 +2,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
 
-use crate::{error::PayraError, Event, EventArgs, EventCounter, Participant};
+use crate::{
+    error::PayraError, validation::validate_name, ContributionRules, Event, EventAccess, EventArgs,
+    EventCounter, Participant, EVENT_VERSION,
+};
 
 #[derive(Accounts)]
 pub struct CreateEvent<'info> {
@@ -51,8 +54,8 @@ pub struct CreateEvent<'info> {
 
 impl<'info> CreateEvent<'info> {
     pub fn create_event(&mut self, args: EventArgs, bumps: &CreateEventBumps) -> Result<u64> {
-        // name length check
-        require!(args.name.len() <= 32, PayraError::NameTooLong);
+        validate_name(&args.name)?;
+        require!(args.target_amount > 0, PayraError::InvalidTargetAmount);
 
         // deadline validity check
         let now = Clock::get()?.unix_timestamp;
@@ -60,14 +63,24 @@ impl<'info> CreateEvent<'info> {
 
         // fill the data in event account
         self.event.set_inner(Event {
+            version: EVENT_VERSION,
             event_id: self.event_counter.count,
             creator: self.creator.key(),
+            pending_creator: None,
+            mint: self.mint.key(),
             withdraw_token_account: self.withdraw_token_account.key(),
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
             target_amount: args.target_amount,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
             total_contributed: 0,
             proposal_count: 0,
             total_spent: 0,
             whitelist: Vec::new(),
+            roles: Vec::new(),
+            contributors: Vec::new(),
             is_cancelled: false,
             is_finalized: false,
             deadline: args.deadline,
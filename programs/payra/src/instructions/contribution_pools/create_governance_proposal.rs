@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    error::PayraError, validation::validate_amount, Event, FractionalStatus, FractionalVault,
+    MemberRole, Proposal, ProposalType, PROPOSAL_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct CreateGovernanceProposal<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    // the creator or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", event.key().as_ref(), event.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // only for ChangeWithdrawAccount, the proposed account
+    pub new_withdraw_token_account: Option<Account<'info, TokenAccount>>,
+
+    // only for ReservePrice, the vault of the NFT
+    pub fractional_vault: Option<Account<'info, FractionalVault>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateGovernanceProposal<'info> {
+    pub fn create_governance_proposal(
+        &mut self,
+        proposal_type: ProposalType,
+        deadline: i64,
+        bumps: CreateGovernanceProposalBumps,
+    ) -> Result<()> {
+        // Check whitelist or allow event creator
+        require!(
+            self.event.has_role(&self.creator.key(), MemberRole::Member),
+            PayraError::NotWhitelisted
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(deadline > now, PayraError::InvalidDeadline);
+
+        let title = match proposal_type {
+            ProposalType::ExtendDeadline { new_deadline } => {
+                require!(
+                    new_deadline > self.event.deadline && new_deadline > now,
+                    PayraError::InvalidDeadline
+                );
+                "Extend deadline"
+            }
+            ProposalType::ChangeTarget { new_target_amount } => {
+                require!(new_target_amount > 0, PayraError::InvalidTargetAmount);
+                "Change target"
+            }
+            ProposalType::ChangeWithdrawAccount {
+                new_withdraw_token_account,
+            } => {
+                let account = self
+                    .new_withdraw_token_account
+                    .as_ref()
+                    .ok_or(PayraError::InvalidWithdrawAccount)?;
+                require_keys_eq!(
+                    account.key(),
+                    new_withdraw_token_account,
+                    PayraError::InvalidWithdrawAccount
+                );
+                require_keys_eq!(account.mint, self.event.mint, PayraError::InvalidMint);
+                require_keys_neq!(
+                    new_withdraw_token_account,
+                    self.event.withdraw_token_account,
+                    PayraError::InvalidWithdrawAccount
+                );
+                "Change withdraw account"
+            }
+            ProposalType::ReservePrice { vault, price } => {
+                validate_amount(price)?;
+                let account = self
+                    .fractional_vault
+                    .as_ref()
+                    .ok_or(PayraError::InvalidFractionalVault)?;
+                require!(
+                    account.key() == vault && account.event == self.event.key(),
+                    PayraError::InvalidFractionalVault
+                );
+                require!(
+                    account.status == FractionalStatus::Active,
+                    PayraError::NftNotInEscrow
+                );
+                "Set reserve price"
+            }
+            _ => return err!(PayraError::InvalidProposalType),
+        };
+
+        self.proposal.set_inner(Proposal {
+            version: PROPOSAL_VERSION,
+            title: String::from(title),
+            amount: 0,
+            spendings: Vec::new(),
+            milestones: Vec::new(),
+            proposal_type,
+            yes_votes: Vec::new(),
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: self.creator.key(),
+            proposal_id: self.event.proposal_count,
+            deadline,
+            settled: false,
+            cancelled: false,
+            fractional_vault: None,
+            bump: bumps.proposal,
+        });
+
+        self.event.proposal_count = self
+            .event
+            .proposal_count
+            .checked_add(1)
+            .ok_or(PayraError::ProposalCounterOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PayraError,
+    validation::{validate_milestones, validate_spendings, validate_title},
+    Event, MemberRole, Milestone, MilestoneRelease, Proposal, ProposalType, SpendingShare,
+    PROPOSAL_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct CreateMilestoneProposal<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    // the creator or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", event.key().as_ref(), event.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateMilestoneProposal<'info> {
+    pub fn create_milestone_proposal(
+        &mut self,
+        title: String,
+        milestones: Vec<u64>,
+        release: MilestoneRelease,
+        spendings: Vec<SpendingShare>,
+        deadline: i64,
+        bumps: CreateMilestoneProposalBumps,
+    ) -> Result<()> {
+        // same roles as a regular spending proposal
+        require!(
+            self.event.has_role(&self.creator.key(), MemberRole::Treasurer),
+            PayraError::Unauthorised
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        validate_title(&title)?;
+        let amount = validate_milestones(&milestones)?;
+        validate_spendings(&self.event, &spendings)?;
+        require!(
+            deadline > Clock::get()?.unix_timestamp,
+            PayraError::InvalidDeadline
+        );
+
+        self.proposal.set_inner(Proposal {
+            version: PROPOSAL_VERSION,
+            title,
+            amount,
+            spendings,
+            milestones: milestones
+                .into_iter()
+                .map(|amount| Milestone {
+                    amount,
+                    released: false,
+                })
+                .collect(),
+            proposal_type: ProposalType::Milestones { release },
+            yes_votes: Vec::new(),
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: self.creator.key(),
+            proposal_id: self.event.proposal_count,
+            deadline,
+            settled: false,
+            cancelled: false,
+            fractional_vault: None,
+            bump: bumps.proposal,
+        });
+
+        self.event.proposal_count = self
+            .event
+            .proposal_count
+            .checked_add(1)
+            .ok_or(PayraError::ProposalCounterOverflow)?;
+
+        Ok(())
+    }
+}
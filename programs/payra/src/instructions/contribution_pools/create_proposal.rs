@@ -4,11 +4,14 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{error::PayraError, Event, Proposal, ProposalType, SpendingShare};
+use crate::{
+    error::PayraError,
+    validation::{validate_amount, validate_spendings, validate_title},
+    Event, MemberRole, Proposal, ProposalType, SpendingShare, PROPOSAL_VERSION,
+};
 
 #[derive(Accounts)]
 pub struct CreateProposal<'info> {
-    #[account(mut)]
     pub creator: Signer<'info>,
 
     #[account(
@@ -20,9 +23,13 @@ pub struct CreateProposal<'info> {
 
     pub mint: Account<'info, Mint>,
 
+    // the creator or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
-        payer = creator,
+        payer = payer,
         space = 8 + Proposal::INIT_SPACE,
         seeds = [b"proposal", event.key().as_ref(), event.proposal_count.to_le_bytes().as_ref()],
         bump
@@ -43,35 +50,36 @@ impl<'info> CreateProposal<'info> {
         deadline: i64,
         bumps: CreateProposalBumps,
     ) -> Result<()> {
-        // Check whitelist or allow event creator
+        // Spending proposals are reserved to treasurers, admins and the creator
         require!(
-            self.event.whitelist.contains(&self.creator.key())
-                || self.event.creator == self.creator.key(),
-            PayraError::NotWhitelisted
+            self.event.has_role(&self.creator.key(), MemberRole::Treasurer),
+            PayraError::Unauthorised
         );
 
-        require!(spendings.len() > 0, PayraError::NoParticipants);
-        require!(
-            spendings.iter().map(|s| s.percentage as u16).sum::<u16>() == 100,
-            PayraError::InvalidPercentage
-        );
+        validate_title(&title)?;
+        validate_amount(amount)?;
+        validate_spendings(&self.event, &spendings)?;
         require!(
             deadline > Clock::get()?.unix_timestamp,
             PayraError::InvalidDeadline
         );
 
         self.proposal.set_inner(Proposal {
-            title: title,
+            version: PROPOSAL_VERSION,
+            title,
             amount,
             spendings,
+            milestones: Vec::new(),
             proposal_type: ProposalType::Spending,
             yes_votes: Vec::new(),
             no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
             creator: self.creator.key(),
             proposal_id: self.event.proposal_count,
             deadline,
             settled: false,
             cancelled: false,
+            fractional_vault: None,
             bump: bumps.proposal,
         });
 
@@ -4,11 +4,12 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{error::PayraError, Event, Proposal, ProposalType, SpendingShare};
+use crate::{
+    error::PayraError, Event, MemberRole, Proposal, ProposalType, SpendingShare, PROPOSAL_VERSION,
+};
 
 #[derive(Accounts)]
 pub struct CreateSettleProposal<'info> {
-    #[account(mut)]
     pub creator: Signer<'info>,
 
     #[account(
@@ -20,9 +21,13 @@ pub struct CreateSettleProposal<'info> {
 
     pub mint: Account<'info, Mint>,
 
+    // the creator or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
-        payer = creator,
+        payer = payer,
         space = 8 + Proposal::INIT_SPACE,
         seeds = [b"proposal", event.key().as_ref(), event.proposal_count.to_le_bytes().as_ref()],
         bump
@@ -42,8 +47,7 @@ impl<'info> CreateSettleProposal<'info> {
     ) -> Result<()> {
         // Check whitelist or allow event creator
         require!(
-            self.event.whitelist.contains(&self.creator.key())
-                || self.event.creator == self.creator.key(),
+            self.event.has_role(&self.creator.key(), MemberRole::Member),
             PayraError::NotWhitelisted
         );
 
@@ -53,17 +57,21 @@ impl<'info> CreateSettleProposal<'info> {
         );
 
         self.proposal.set_inner(Proposal {
+            version: PROPOSAL_VERSION,
             title: String::from("Settlement"),
             amount: 0,
             spendings: Vec::new(),
+            milestones: Vec::new(),
             proposal_type: ProposalType::EventSettlement,
             yes_votes: Vec::new(),
             no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
             creator: self.creator.key(),
             proposal_id: self.event.proposal_count,
             deadline,
             settled: false,
             cancelled: false,
+            fractional_vault: None,
             bump: bumps.proposal,
         });
 
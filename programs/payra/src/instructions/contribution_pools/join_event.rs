@@ -0,0 +1,90 @@
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions};
+
+use crate::{
+    error::PayraError,
+    validation::{validate_invite_signature, validate_new_members},
+    Event, Invite, InviteVoucher, MemberRole, INVITE_VERSION,
+};
+
+#[derive(Accounts)]
+#[instruction(voucher: InviteVoucher)]
+pub struct JoinEvent<'info> {
+    pub member: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    // the member or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Invite::INIT_SPACE,
+        seeds = [
+            b"invite",
+            event.key().as_ref(),
+            voucher.issuer.as_ref(),
+            voucher.invite_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub invite: Account<'info, Invite>,
+
+    /// CHECK: instructions sysvar, holds the ed25519 verification of the voucher
+    #[account(address = instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinEvent<'info> {
+    // Adds the signer to the whitelist with the voucher of an admin, verified
+    // by the ed25519 instruction placed right before this one.
+    pub fn join_event(&mut self, voucher: InviteVoucher, bumps: &JoinEventBumps) -> Result<()> {
+        let event = &mut self.event;
+        require_keys_eq!(voucher.event, event.key(), PayraError::InvalidInvite);
+
+        // vouchers stop working once their issuer is no longer an admin
+        require!(
+            event.has_role(&voucher.issuer, MemberRole::Admin),
+            PayraError::InvalidInviteSignature
+        );
+        if voucher.issuer != event.creator {
+            require!(voucher.role != MemberRole::Admin, PayraError::Unauthorised);
+        }
+        validate_invite_signature(
+            &self.instructions.to_account_info(),
+            &voucher.issuer,
+            &voucher.message()?,
+        )?;
+
+        require!(
+            Clock::get()?.unix_timestamp < voucher.expires_at,
+            PayraError::InviteExpired
+        );
+        let invite = &mut self.invite;
+        require!(invite.uses < voucher.max_uses, PayraError::InviteUsedUp);
+
+        let member = self.member.key();
+        validate_new_members(event, &[member])?;
+        event.add_member(member, voucher.role);
+
+        invite.set_inner(Invite {
+            version: INVITE_VERSION,
+            event: event.key(),
+            issuer: voucher.issuer,
+            invite_id: voucher.invite_id,
+            uses: invite.uses + 1,
+            bump: bumps.invite,
+        });
+
+        msg!("{} joined with invite {}", member, voucher.invite_id);
+        Ok(())
+    }
+}
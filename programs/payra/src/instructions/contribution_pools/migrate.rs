@@ -0,0 +1,198 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    error::PayraError, unrecorded_spending, upgrade_event, upgrade_participant, upgrade_proposal,
+    Event, Participant, Proposal,
+};
+
+// Accounts for migrate_participant. The target is unchecked on purpose: an
+// outdated account can't be deserialized into the current layout, so
+// ownership and discriminator are verified by hand.
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    // pays for the extra rent after realloc, anyone can migrate
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: owner and discriminator are validated in the handler
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProposal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // migrated first, it counts the spendings v0 paid out in total_spent
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: owner and discriminator are validated in the handler, the
+    /// address against the event
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateEvent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: owner and discriminator are validated in the handler
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    // the event's current withdraw token account, v0 events recover their
+    // mint from it
+    pub withdraw_token_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateEvent<'info> {
+    // remaining accounts: the participant address of every voter, created or
+    // not, in the order of EventV0::voters
+    pub fn migrate_event(&mut self, participants: &[AccountInfo]) -> Result<()> {
+        let account = self.account.to_account_info();
+        check_discriminator(&account, Event::DISCRIMINATOR)?;
+
+        let withdraw = (
+            self.withdraw_token_account.key(),
+            self.withdraw_token_account.mint,
+        );
+        // only the program creates accounts there, on a first contribution
+        let participants: Vec<(Pubkey, bool)> = participants
+            .iter()
+            .map(|info| {
+                (
+                    info.key(),
+                    info.owner == &crate::ID && !info.data_is_empty(),
+                )
+            })
+            .collect();
+        let event = upgrade_event(&account.try_borrow_data()?, Some(withdraw), &participants)?;
+        write(
+            &self.payer,
+            &account,
+            &self.system_program,
+            &event,
+            8 + Event::INIT_SPACE,
+        )?;
+
+        msg!("Event migrated: {}", event.event_id);
+        Ok(())
+    }
+}
+
+impl<'info> MigrateProposal<'info> {
+    pub fn migrate_proposal(&mut self) -> Result<()> {
+        let account = self.account.to_account_info();
+        check_discriminator(&account, Proposal::DISCRIMINATOR)?;
+
+        let proposal = upgrade_proposal(&account.try_borrow_data()?)?;
+        let event_key = self.event.key();
+        let address = Pubkey::create_program_address(
+            &[
+                b"proposal",
+                event_key.as_ref(),
+                proposal.proposal_id.to_le_bytes().as_ref(),
+                &[proposal.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| PayraError::InvalidProposalAccount)?;
+        require_keys_eq!(account.key(), address, PayraError::InvalidProposalAccount);
+
+        // a proposal is only migrated once, so is its spending counted
+        self.event.total_spent = self
+            .event
+            .total_spent
+            .checked_add(unrecorded_spending(&proposal))
+            .ok_or(PayraError::MathOverflow)?;
+        write(
+            &self.payer,
+            &account,
+            &self.system_program,
+            &proposal,
+            8 + Proposal::INIT_SPACE,
+        )?;
+
+        msg!("Proposal migrated: {}", proposal.proposal_id);
+        Ok(())
+    }
+}
+
+impl<'info> Migrate<'info> {
+    pub fn migrate_participant(&mut self) -> Result<()> {
+        let account = self.account.to_account_info();
+        check_discriminator(&account, Participant::DISCRIMINATOR)?;
+
+        let participant = upgrade_participant(&account.try_borrow_data()?)?;
+        write(
+            &self.payer,
+            &account,
+            &self.system_program,
+            &participant,
+            8 + Participant::INIT_SPACE,
+        )?;
+
+        msg!("Participant migrated: {}", participant.wallet);
+        Ok(())
+    }
+}
+
+fn check_discriminator(account: &AccountInfo, discriminator: &[u8]) -> Result<()> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        PayraError::InvalidAccountVersion
+    );
+    Ok(())
+}
+
+// grow the account to the current layout size, top up rent and write the
+// upgraded state (discriminator included) from the start of the buffer
+fn write<'info, T: AccountSerialize>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    state: &T,
+    space: usize,
+) -> Result<()> {
+    if space > account.data_len() {
+        let required = Rent::get()?.minimum_balance(space);
+        let missing = required.saturating_sub(account.lamports());
+        if missing > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    Transfer {
+                        from: payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                missing,
+            )?;
+        }
+        account.resize(space)?;
+    }
+
+    let mut data = account.try_borrow_mut_data()?;
+    data.fill(0);
+    state.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
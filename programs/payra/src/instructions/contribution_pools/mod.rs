@@ -2,18 +2,50 @@ pub mod create_event;
 pub mod close_event;
 pub mod contribute;
 pub mod whitelist;
+pub mod join_event;
+pub mod set_member_role;
+pub mod set_contribution_rules;
+pub mod set_dues;
+pub mod set_event_access;
 pub mod create_proposal;
 pub mod vote;
+pub mod session;
 pub mod settle_proposal;
 pub mod create_settle_proposal;
 pub mod settle_event;
+pub mod migrate;
+pub mod create_governance_proposal;
+pub mod settle_governance_proposal;
+pub mod apply_withdraw_account_change;
+pub mod transfer_creator;
+pub mod create_milestone_proposal;
+pub mod approve_milestone;
+pub mod release_milestone;
+pub mod cancel_milestones;
+pub mod views;
 
 pub use create_event::*;
 pub use close_event::*;
 pub use contribute::*;
 pub use whitelist::*;
+pub use join_event::*;
+pub use set_member_role::*;
+pub use set_contribution_rules::*;
+pub use set_dues::*;
+pub use set_event_access::*;
 pub use create_proposal::*;
 pub use vote::*;
+pub use session::*;
 pub use settle_proposal::*;
 pub use create_settle_proposal::*;
-pub use settle_event::*;
\ No newline at end of file
+pub use settle_event::*;
+pub use migrate::*;
+pub use create_governance_proposal::*;
+pub use settle_governance_proposal::*;
+pub use apply_withdraw_account_change::*;
+pub use transfer_creator::*;
+pub use create_milestone_proposal::*;
+pub use approve_milestone::*;
+pub use release_milestone::*;
+pub use cancel_milestones::*;
+pub use views::*;
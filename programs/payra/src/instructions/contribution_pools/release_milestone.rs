@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{error::PayraError, logic::Tally, Event, MilestoneRelease, Participant, Proposal};
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = event,
+    )]
+    pub event_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", event.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        constraint = withdraw_account.key() == event.withdraw_token_account @ PayraError::InvalidWithdrawAccount
+    )]
+    pub withdraw_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Pays the next tranche of an approved milestone proposal out of the vault
+// and charges it to the wallets sharing the expense, passed as remaining
+// accounts like for settle_proposal.
+pub fn release_milestone_handler(ctx: Context<ReleaseMilestone>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let (release, index) = proposal.pending_milestone()?;
+    require!(!ctx.accounts.event.is_cancelled, PayraError::EventCancelled);
+
+    match release {
+        MilestoneRelease::Approval => {
+            let tally = Tally {
+                yes: proposal.milestone_approvals.len(),
+                no: 0,
+                eligible: proposal.spendings.len(),
+            };
+            require!(tally.has_majority(), PayraError::MilestoneNotApproved);
+        }
+        MilestoneRelease::Attestation => {
+            require_keys_eq!(
+                ctx.accounts.signer.key(),
+                ctx.accounts.event.creator,
+                PayraError::Unauthorised
+            );
+        }
+    }
+
+    let amount = proposal.milestones[index].amount;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.event_vault.to_account_info(),
+        to: ctx.accounts.withdraw_account.to_account_info(),
+        authority: ctx.accounts.event.to_account_info(),
+    };
+
+    let event_seeds: &[&[u8]] = &[
+        b"event",
+        &ctx.accounts.event.event_id.to_le_bytes(),
+        &[ctx.accounts.event.bump],
+    ];
+    let signer_seeds = &[event_seeds];
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    require!(
+        ctx.remaining_accounts.len() == proposal.spendings.len(),
+        PayraError::InvalidParticipantAccounts
+    );
+
+    let event_key = ctx.accounts.event.key();
+    let event_id = ctx.accounts.event.event_id;
+    let mut charged = vec![false; proposal.spendings.len()];
+    for acc_info in ctx.remaining_accounts.iter() {
+        let mut participant_data =
+            Participant::load_checked(acc_info, &event_key, event_id, ctx.program_id)?;
+
+        let share = proposal
+            .spendings
+            .iter()
+            .position(|s| s.wallet == participant_data.wallet)
+            .ok_or(PayraError::InvalidParticipantWallet)?;
+        require!(!charged[share], PayraError::InvalidParticipantAccounts);
+        charged[share] = true;
+
+        participant_data.spent = participant_data
+            .spent
+            .checked_add(proposal.share_of(amount, share)?)
+            .ok_or(PayraError::MathOverflow)?;
+
+        participant_data.store(acc_info)?;
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.total_spent = event
+        .total_spent
+        .checked_add(amount)
+        .ok_or(PayraError::MathOverflow)?;
+
+    proposal.milestones[index].released = true;
+    proposal.milestone_approvals.clear();
+
+    msg!("Milestone {} of proposal {} released: {}", index, proposal.proposal_id, amount);
+    Ok(())
+}
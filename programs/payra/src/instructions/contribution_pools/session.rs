@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, MemberRole, Session, MAX_SESSION_DURATION, SESSION_VERSION};
+
+#[derive(Accounts)]
+pub struct CreateSession<'info> {
+    pub member: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    // the member or their sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Session::INIT_SPACE,
+        seeds = [b"session", event.key().as_ref(), member.key().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, Session>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    pub member: Signer<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"session", session.event.as_ref(), member.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, Session>,
+
+    /// CHECK: whoever paid the session rent, it goes back to them
+    #[account(mut, address = session.rent_payer @ PayraError::InvalidRentPayer)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+impl<'info> CreateSession<'info> {
+    // Lets `session_key` vote for the member on this event until `expires_at`.
+    // The key signs its own transactions, so it needs lamports for the fees.
+    pub fn create_session(
+        &mut self,
+        session_key: Pubkey,
+        expires_at: i64,
+        bumps: &CreateSessionBumps,
+    ) -> Result<()> {
+        let member = self.member.key();
+        require!(
+            self.event.has_role(&member, MemberRole::Member),
+            PayraError::NotAuthorizedToVote
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            expires_at > now && expires_at - now <= MAX_SESSION_DURATION,
+            PayraError::InvalidSessionExpiry
+        );
+
+        // a replaced session keeps refunding whoever paid its rent
+        let rent_payer = if self.session.member == member {
+            self.session.rent_payer
+        } else {
+            self.payer.key()
+        };
+        self.session.set_inner(Session {
+            version: SESSION_VERSION,
+            event: self.event.key(),
+            member,
+            session_key,
+            expires_at,
+            rent_payer,
+            bump: bumps.session,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> RevokeSession<'info> {
+    // the account is closed by the constraint, its rent goes back to whoever
+    // paid it
+    pub fn revoke_session(&mut self) -> Result<()> {
+        msg!("session {} revoked", self.session.session_key);
+        Ok(())
+    }
+}
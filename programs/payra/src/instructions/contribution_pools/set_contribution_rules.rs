@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, ContributionRules, Event, EventAccess, MemberRole};
+
+#[derive(Accounts)]
+pub struct SetContributionRules<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+impl<'info> SetContributionRules<'info> {
+    pub fn set_contribution_rules(&mut self, rules: ContributionRules) -> Result<()> {
+        require!(
+            self.event.has_role(&self.admin.key(), MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        // rules can't change under the feet of people who already paid
+        require!(
+            self.event.total_contributed == 0,
+            PayraError::ContributionsAlreadyStarted
+        );
+
+        require!(
+            rules.max_per_participant == 0 || rules.min_contribution <= rules.max_per_participant,
+            PayraError::InvalidContributionRules
+        );
+        require!(
+            rules.stretch_goal == 0 || rules.stretch_goal >= self.event.target_amount,
+            PayraError::InvalidContributionRules
+        );
+        // dues already fix what every member pays, open events have no
+        // fixed set of members to split the target between
+        require!(
+            !(rules.equal_share
+                && (self.event.dues.is_some() || self.event.access != EventAccess::Whitelist)),
+            PayraError::InvalidContributionRules
+        );
+
+        self.event.contribution_rules = rules;
+        Ok(())
+    }
+}
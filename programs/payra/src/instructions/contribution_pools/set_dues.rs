@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, validation::validate_amount, Dues, Event, MemberRole};
+
+#[derive(Accounts)]
+pub struct SetDues<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+impl<'info> SetDues<'info> {
+    // the first period starts now, `period` is in seconds
+    pub fn set_dues(&mut self, amount: u64, period: i64) -> Result<()> {
+        require!(
+            self.event.has_role(&self.admin.key(), MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        // like the contribution rules, dues are fixed before anyone paid
+        require!(
+            self.event.total_contributed == 0,
+            PayraError::ContributionsAlreadyStarted
+        );
+
+        validate_amount(amount)?;
+        require!(period > 0, PayraError::InvalidDuesPeriod);
+        require!(
+            !self.event.contribution_rules.equal_share,
+            PayraError::InvalidContributionRules
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < self.event.deadline, PayraError::DeadlineAlreadyReached);
+
+        self.event.dues = Some(Dues {
+            amount,
+            period,
+            start: now,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, validation::validate_amount, Event, EventAccess, MemberRole};
+
+#[derive(Accounts)]
+pub struct SetEventAccess<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+impl<'info> SetEventAccess<'info> {
+    pub fn set_event_access(&mut self, access: EventAccess) -> Result<()> {
+        require!(
+            self.event.has_role(&self.admin.key(), MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+        require!(!self.event.is_cancelled, PayraError::EventCancelled);
+
+        // like the contribution rules, fixed before anyone paid
+        require!(
+            self.event.total_contributed == 0,
+            PayraError::ContributionsAlreadyStarted
+        );
+
+        if let EventAccess::Open { voting_threshold } = access {
+            validate_amount(voting_threshold)?;
+            // equal shares are split between a known set of members
+            require!(
+                !self.event.contribution_rules.equal_share,
+                PayraError::InvalidContributionRules
+            );
+        }
+
+        self.event.access = access;
+        Ok(())
+    }
+}
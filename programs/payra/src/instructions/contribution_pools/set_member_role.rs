@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, MemberRole, Participant};
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetMemberRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    /// CHECK: the wallet's participant account, only read once it exists
+    #[account(
+        seeds = [b"participant", event.event_id.to_le_bytes().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub participant: UncheckedAccount<'info>,
+}
+
+impl<'info> SetMemberRole<'info> {
+    pub fn set_member_role(&mut self, wallet: Pubkey, role: MemberRole) -> Result<()> {
+        let event = &mut self.event;
+        let admin = self.admin.key();
+        require!(
+            event.has_role(&admin, MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+
+        let index = event
+            .whitelist
+            .iter()
+            .position(|w| *w == wallet)
+            .ok_or(PayraError::NotWhitelisted)?;
+
+        // only the creator can grant or take away the admin role
+        if admin != event.creator {
+            require!(
+                role != MemberRole::Admin && event.roles[index] != MemberRole::Admin,
+                PayraError::Unauthorised
+            );
+        }
+
+        // observers aren't settled, their contributions would be lost
+        if role < MemberRole::Member && self.participant.owner == &crate::ID {
+            let participant =
+                Participant::try_deserialize(&mut &self.participant.data.borrow()[..])?;
+            require!(
+                participant.contributed == 0,
+                PayraError::ContributorCannotBeObserver
+            );
+        }
+
+        let voters = event.eligible_voters();
+        event.roles[index] = role;
+        require!(
+            !event.voters_frozen() || event.eligible_voters() == voters,
+            PayraError::EqualShareVotersFrozen
+        );
+        Ok(())
+    }
+}
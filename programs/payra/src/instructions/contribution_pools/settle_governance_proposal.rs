@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PayraError, Event, FractionalStatus, FractionalVault, Proposal, ProposalType,
+    WITHDRAW_CHANGE_TIMELOCK,
+};
+
+#[derive(Accounts)]
+pub struct SettleGovernanceProposal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", event.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // only for ReservePrice, the vault of the NFT
+    #[account(mut)]
+    pub fractional_vault: Option<Account<'info, FractionalVault>>,
+}
+
+impl<'info> SettleGovernanceProposal<'info> {
+    pub fn settle_governance_proposal(&mut self) -> Result<()> {
+        let proposal = &mut self.proposal;
+        let event = &mut self.event;
+
+        require!(
+            proposal.proposal_type.is_governance(),
+            PayraError::InvalidProposalType
+        );
+
+        // Can't settle twice
+        require!(!proposal.settled, PayraError::AlreadySettled);
+        require!(!event.is_cancelled, PayraError::EventCancelled);
+
+        // Same voting rules as the event settlement: deadline passed OR all
+        // have voted, then 50% or more of the eligible voters said yes
+        let clock = Clock::get()?;
+        let tally = proposal.tally(event.eligible_voters());
+        require!(
+            tally.can_settle(clock.unix_timestamp, proposal.deadline),
+            PayraError::TooEarlyToSettle
+        );
+
+        if !tally.has_majority() {
+            proposal.cancelled = true;
+            proposal.settled = true;
+            return Ok(());
+        }
+
+        match proposal.proposal_type {
+            ProposalType::ExtendDeadline { new_deadline } => {
+                // the proposal may have been settled late, don't move the deadline backwards
+                require!(
+                    new_deadline > event.deadline && new_deadline > clock.unix_timestamp,
+                    PayraError::InvalidDeadline
+                );
+                event.deadline = new_deadline;
+            }
+            ProposalType::ChangeTarget { new_target_amount } => {
+                event.target_amount = new_target_amount;
+            }
+            ProposalType::ChangeWithdrawAccount {
+                new_withdraw_token_account,
+            } => {
+                // queued, applied with apply_withdraw_account_change after the timelock
+                event.pending_withdraw_token_account = Some(new_withdraw_token_account);
+                event.withdraw_change_available_at = clock
+                    .unix_timestamp
+                    .checked_add(WITHDRAW_CHANGE_TIMELOCK)
+                    .ok_or(PayraError::MathOverflow)?;
+            }
+            ProposalType::ReservePrice { vault, price } => {
+                let account = self
+                    .fractional_vault
+                    .as_mut()
+                    .ok_or(PayraError::InvalidFractionalVault)?;
+                require_keys_eq!(account.key(), vault, PayraError::InvalidFractionalVault);
+                // the NFT may have been redeemed while the vote was open
+                require!(
+                    account.status == FractionalStatus::Active,
+                    PayraError::NftNotInEscrow
+                );
+                account.reserve_price = price;
+            }
+            _ => return err!(PayraError::InvalidProposalType),
+        }
+
+        proposal.settled = true;
+
+        Ok(())
+    }
+}
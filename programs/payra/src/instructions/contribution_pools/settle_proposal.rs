@@ -4,7 +4,7 @@ use anchor_spl::{
     token::{transfer, Mint, Token, TokenAccount, Transfer},
 };
 
-use crate::{error::PayraError, Event, Participant, Proposal};
+use crate::{error::PayraError, Event, Participant, Proposal, ProposalType};
 
 #[derive(Accounts)]
 pub struct SettleProposal<'info> {
@@ -44,28 +44,41 @@ pub struct SettleProposal<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn settle_proposal_handler<'a>(ctx: Context<SettleProposal>) -> Result<()> {
+pub fn settle_proposal_handler(ctx: Context<SettleProposal>) -> Result<()> {
     let clock = Clock::get()?;
     let proposal = &mut ctx.accounts.proposal;
 
+    require!(
+        proposal.proposal_type.is_spending(),
+        PayraError::InvalidProposalType
+    );
+
     // Can't settle twice
     require!(!proposal.settled, PayraError::AlreadySettled);
+    // funds of a cancelled event stay in the vault
+    require!(!ctx.accounts.event.is_cancelled, PayraError::EventCancelled);
 
     // Can only settle if deadline passed OR all have voted
-    let total_participants = proposal.spendings.len();
-    let total_votes = proposal.yes_votes.len() + proposal.no_votes.len();
+    let tally = proposal.tally(proposal.spendings.len());
     require!(
-        clock.unix_timestamp > proposal.deadline || total_votes == total_participants,
+        tally.can_settle(clock.unix_timestamp, proposal.deadline),
         PayraError::TooEarlyToSettle
     );
 
     // if any NO votes, reject proposal
-    if !proposal.no_votes.is_empty() {
+    if tally.is_vetoed() {
         proposal.cancelled = true;
         proposal.settled = true;
         return Ok(());
     }
 
+    // an approved milestone proposal pays nothing yet, each tranche is
+    // released on its own with release_milestone
+    if matches!(proposal.proposal_type, ProposalType::Milestones { .. }) {
+        proposal.settled = true;
+        return Ok(());
+    }
+
     let cpi_accounts = Transfer {
         from: ctx.accounts.event_vault.to_account_info(),
         to: ctx.accounts.withdraw_account.to_account_info(),
@@ -77,7 +90,7 @@ pub fn settle_proposal_handler<'a>(ctx: Context<SettleProposal>) -> Result<()> {
         &ctx.accounts.event.event_id.to_le_bytes(),
         &[ctx.accounts.event.bump],
     ];
-    let signer_seeds = &[&event_seeds[..]];
+    let signer_seeds = &[event_seeds];
     transfer(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -92,38 +105,40 @@ pub fn settle_proposal_handler<'a>(ctx: Context<SettleProposal>) -> Result<()> {
         PayraError::InvalidParticipantAccounts
     );
 
-    for i in 0..ctx.remaining_accounts.len() {
-        let acc = &ctx.remaining_accounts[i];
-        let mut participant_data: Participant = Participant::try_deserialize(
-            &mut &acc.data.borrow()[..]
-        )?;
-    
-        require!(
-            participant_data.event == ctx.accounts.event.key(),
-            PayraError::InvalidParticipantEvent
-        );
-    
-        let spending_entry = proposal
+    // a wallet passed twice would be charged twice while another share is
+    // skipped, so each share is consumed once
+    let event_key = ctx.accounts.event.key();
+    let event_id = ctx.accounts.event.event_id;
+    let mut charged = vec![false; proposal.spendings.len()];
+    for acc_info in ctx.remaining_accounts.iter() {
+        let mut participant_data =
+            Participant::load_checked(acc_info, &event_key, event_id, ctx.program_id)?;
+
+        let index = proposal
             .spendings
             .iter()
-            .find(|s| s.wallet == participant_data.wallet)
+            .position(|s| s.wallet == participant_data.wallet)
             .ok_or(PayraError::InvalidParticipantWallet)?;
-    
-        let spending_amount = proposal
-            .amount
-            .checked_mul(spending_entry.percentage as u64)
-            .ok_or(PayraError::MathOverflow)?
-            .checked_div(100)
-            .ok_or(PayraError::MathOverflow)?;
-    
+        require!(!charged[index], PayraError::InvalidParticipantAccounts);
+        charged[index] = true;
+
+        let spending_amount = proposal.spending_share(index)?;
+
         participant_data.spent = participant_data
             .spent
             .checked_add(spending_amount)
             .ok_or(PayraError::MathOverflow)?;
-    
-        participant_data.try_serialize(&mut &mut acc.data.borrow_mut()[..])?;
+
+        participant_data.store(acc_info)?;
     }
-    
+
+    // paid out of the vault, settlement nets it against the contributions
+    let event = &mut ctx.accounts.event;
+    event.total_spent = event
+        .total_spent
+        .checked_add(proposal.amount)
+        .ok_or(PayraError::MathOverflow)?;
+
     proposal.settled = true;
 
     Ok(())
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, MemberRole, MAX_PARTICIPANTS};
+
+#[derive(Accounts)]
+pub struct ProposeCreatorTransfer<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCreatorTransfer<'info> {
+    pub new_creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+impl<'info> ProposeCreatorTransfer<'info> {
+    // `None` withdraws a pending transfer
+    pub fn propose_creator_transfer(&mut self, new_creator: Option<Pubkey>) -> Result<()> {
+        require_keys_eq!(
+            self.creator.key(),
+            self.event.creator,
+            PayraError::Unauthorised
+        );
+
+        if let Some(new_creator) = new_creator {
+            require_keys_neq!(new_creator, self.event.creator, PayraError::InvalidNewCreator);
+        }
+
+        self.event.pending_creator = new_creator;
+        Ok(())
+    }
+}
+
+impl<'info> AcceptCreatorTransfer<'info> {
+    pub fn accept_creator_transfer(&mut self) -> Result<()> {
+        let event = &mut self.event;
+        let new_creator = self.new_creator.key();
+
+        require!(
+            event.pending_creator == Some(new_creator),
+            PayraError::Unauthorised
+        );
+
+        // the new creator becomes the implicit member, the previous one stays
+        // in the group as a regular whitelisted member
+        let voters = event.eligible_voters();
+        event.remove_member(&new_creator);
+        require!(
+            event.whitelist.len() < MAX_PARTICIPANTS as usize,
+            PayraError::WhitelistFull
+        );
+        let previous_creator = event.creator;
+        event.add_member(previous_creator, MemberRole::Member);
+        require!(
+            !event.voters_frozen() || event.eligible_voters() == voters,
+            PayraError::EqualShareVotersFrozen
+        );
+
+        event.creator = new_creator;
+        event.pending_creator = None;
+
+        msg!("Event creator changed: {} -> {}", previous_creator, new_creator);
+        Ok(())
+    }
+}
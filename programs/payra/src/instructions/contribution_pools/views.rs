@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, Participant, Proposal};
+
+// read-only instructions, meant to be simulated: the returned value is
+// written with set_return_data
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct EventSummary {
+    pub event_id: u64,
+    pub name: String,
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub target_amount: u64,
+    pub funding_cap: u64,
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    // still held by the vault
+    pub available: u64,
+    pub target_reached: bool,
+    pub deadline: i64,
+    pub deadline_passed: bool,
+    pub is_cancelled: bool,
+    pub is_finalized: bool,
+    pub members: u8,
+    pub voters: u8,
+    pub proposal_count: u16,
+    // ids of the proposals still open, neither settled nor cancelled
+    pub open_proposals: Vec<u16>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ParticipantBalance {
+    pub wallet: Pubkey,
+    pub contributed: u64,
+    pub spent: u64,
+    // what settle_event records, contributed - spent
+    pub net_owed: i64,
+    pub refunded: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SettlementPreview {
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    // one entry per voter and donor, zeroed for voters that never contributed
+    pub balances: Vec<ParticipantBalance>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MemberDues {
+    pub wallet: Pubkey,
+    pub periods_paid: u32,
+    // periods due but not paid yet, zero when up to date or paid ahead
+    pub periods_behind: u32,
+    pub amount_behind: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DuesStatus {
+    pub amount: u64,
+    pub period: i64,
+    pub start: i64,
+    // periods started so far, the running one included
+    pub periods_due: u32,
+    // one entry per voter
+    pub members: Vec<MemberDues>,
+}
+
+#[derive(Accounts)]
+pub struct GetEventSummary<'info> {
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// remaining accounts: proposals of the event to report on
+pub fn get_event_summary_handler(ctx: Context<GetEventSummary>) -> Result<EventSummary> {
+    let event = &ctx.accounts.event;
+    let event_key = event.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut open_proposals = Vec::new();
+    for acc_info in ctx.remaining_accounts.iter() {
+        require_keys_eq!(
+            *acc_info.owner,
+            *ctx.program_id,
+            PayraError::InvalidProposalAccount
+        );
+        let proposal = Proposal::try_deserialize(&mut &acc_info.data.borrow()[..])?;
+        let expected_pda = Pubkey::create_program_address(
+            &[
+                b"proposal",
+                event_key.as_ref(),
+                proposal.proposal_id.to_le_bytes().as_ref(),
+                &[proposal.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PayraError::InvalidProposalAccount)?;
+        require_keys_eq!(
+            acc_info.key(),
+            expected_pda,
+            PayraError::InvalidProposalAccount
+        );
+
+        if !proposal.settled && !proposal.cancelled {
+            open_proposals.push(proposal.proposal_id);
+        }
+    }
+
+    Ok(EventSummary {
+        event_id: event.event_id,
+        name: event.name.clone(),
+        creator: event.creator,
+        mint: event.mint,
+        target_amount: event.target_amount,
+        funding_cap: event.funding_cap(),
+        total_contributed: event.total_contributed,
+        total_spent: event.total_spent,
+        available: event
+            .total_contributed
+            .checked_sub(event.total_spent)
+            .ok_or(PayraError::MathOverflow)?,
+        target_reached: event.total_contributed >= event.target_amount,
+        deadline: event.deadline,
+        deadline_passed: now > event.deadline,
+        is_cancelled: event.is_cancelled,
+        is_finalized: event.is_finalized,
+        members: event.whitelist.len() as u8,
+        voters: event.eligible_voters() as u8,
+        proposal_count: event.proposal_count,
+        open_proposals,
+    })
+}
+
+#[derive(Accounts)]
+pub struct PreviewSettlement<'info> {
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// remaining accounts: participant accounts of the contributors, the wallets
+// settle_event settles. A missing one counts as nothing contributed or spent
+pub fn preview_settlement_handler(ctx: Context<PreviewSettlement>) -> Result<SettlementPreview> {
+    let event = &ctx.accounts.event;
+
+    // accounts not created yet are still owned by the system program
+    let event_key = event.key();
+    let participants = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|acc| acc.owner == ctx.program_id)
+        .map(|acc| Participant::load_checked(acc, &event_key, event.event_id, ctx.program_id))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut balances = Vec::new();
+    for wallet in event.contributors.iter().copied() {
+        balances.push(match participants.iter().find(|p| p.wallet == wallet) {
+            Some(p) => ParticipantBalance::from_participant(p)?,
+            None => ParticipantBalance {
+                wallet,
+                contributed: 0,
+                spent: 0,
+                net_owed: 0,
+                refunded: false,
+            },
+        });
+    }
+
+    Ok(SettlementPreview {
+        total_contributed: event.total_contributed,
+        total_spent: event.total_spent,
+        balances,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetDuesStatus<'info> {
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+}
+
+// remaining accounts: participant accounts of the voters, as for
+// preview_settlement. Periods are only due up to the deadline.
+pub fn get_dues_status_handler(ctx: Context<GetDuesStatus>) -> Result<DuesStatus> {
+    let event = &ctx.accounts.event;
+    let dues = event.dues.ok_or(PayraError::NoDues)?;
+    let now = Clock::get()?.unix_timestamp;
+    let periods_due = dues
+        .periods_due(now.min(event.deadline.saturating_sub(1)))
+        .ok_or(PayraError::MathOverflow)?;
+
+    let event_key = event.key();
+    let participants = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|acc| acc.owner == ctx.program_id)
+        .map(|acc| Participant::load_checked(acc, &event_key, event.event_id, ctx.program_id))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut members = Vec::new();
+    for wallet in event.voters() {
+        let periods_paid = participants
+            .iter()
+            .find(|p| p.wallet == wallet)
+            .map_or(0, |p| p.periods_paid);
+        let periods_behind = periods_due.saturating_sub(periods_paid);
+        members.push(MemberDues {
+            wallet,
+            periods_paid,
+            periods_behind,
+            amount_behind: dues
+                .amount
+                .checked_mul(periods_behind as u64)
+                .ok_or(PayraError::MathOverflow)?,
+        });
+    }
+
+    Ok(DuesStatus {
+        amount: dues.amount,
+        period: dues.period,
+        start: dues.start,
+        periods_due,
+        members,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetParticipantBalance<'info> {
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        seeds = [b"participant", event.event_id.to_le_bytes().as_ref(), participant.wallet.as_ref()],
+        bump = participant.bump,
+        constraint = participant.event == event.key() @ PayraError::InvalidParticipantEvent
+    )]
+    pub participant: Account<'info, Participant>,
+}
+
+impl<'info> GetParticipantBalance<'info> {
+    pub fn get_participant_balance(&self) -> Result<ParticipantBalance> {
+        ParticipantBalance::from_participant(&self.participant)
+    }
+}
+
+impl ParticipantBalance {
+    fn from_participant(participant: &Participant) -> Result<Self> {
+        Ok(Self {
+            wallet: participant.wallet,
+            contributed: participant.contributed,
+            spent: participant.spent,
+            net_owed: participant.projected_net_owed()?,
+            refunded: participant.refunded,
+        })
+    }
+}
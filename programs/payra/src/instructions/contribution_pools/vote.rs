@@ -4,12 +4,15 @@ use anchor_spl::{
     token::{Mint, Token},
 };
 
-use crate::{error::PayraError, program::Payra, Event, Proposal, ProposalType};
+use crate::{
+    error::PayraError, logic::has_voted, program::Payra, Event, MemberRole, Proposal, ProposalType,
+    Session,
+};
 
 #[derive(Accounts)]
 pub struct Vote<'info> {
-    #[account(mut)]
-    pub voter: Signer<'info>,
+    /// CHECK: the member voting, signs unless a session key signs for them
+    pub voter: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -30,31 +33,57 @@ pub struct Vote<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    // ephemeral key the voter authorized with `create_session`
+    pub session_key: Option<Signer<'info>>,
+
+    #[account(
+        seeds = [b"session", event.key().as_ref(), voter.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, Session>>,
 }
 
 impl<'info> Vote<'info> {
     pub fn vote(&mut self, vote_choice: bool) -> Result<()> {
         let clock = Clock::get()?;
+        if !self.voter.is_signer {
+            let (Some(session_key), Some(session)) = (&self.session_key, &self.session) else {
+                return err!(PayraError::InvalidSession);
+            };
+            require_keys_eq!(
+                session_key.key(),
+                session.session_key,
+                PayraError::InvalidSession
+            );
+            require!(
+                clock.unix_timestamp < session.expires_at,
+                PayraError::SessionExpired
+            );
+        }
+
         let proposal = &mut self.proposal;
         require!(
             clock.unix_timestamp <= proposal.deadline,
             PayraError::ProposalExpired
         );
 
-        if let ProposalType::Spending = proposal.proposal_type {
-            let allowed = proposal
-                .spendings
-                .iter()
-                .any(|s| s.wallet == self.voter.key());
-            require!(allowed, PayraError::NotAuthorizedToVote);
-        }
+        // observers never vote, spending proposals are voted by the people
+        // sharing the expense, everything else by the whole event
+        let allowed = self.event.has_role(&self.voter.key(), MemberRole::Member)
+            && match proposal.proposal_type {
+                ProposalType::Spending | ProposalType::Milestones { .. } => proposal
+                    .spendings
+                    .iter()
+                    .any(|s| s.wallet == self.voter.key()),
+                _ => true,
+            };
+        require!(allowed, PayraError::NotAuthorizedToVote);
 
-        let already_voted = proposal
-            .yes_votes
-            .iter()
-            .chain(proposal.no_votes.iter())
-            .any(|pk| pk == &self.voter.key());
-        require!(!already_voted, PayraError::AlreadyVoted);
+        require!(
+            !has_voted(&proposal.yes_votes, &proposal.no_votes, &self.voter.key()),
+            PayraError::AlreadyVoted
+        );
 
         match vote_choice {
             true => proposal.yes_votes.push(self.voter.key()),
@@ -4,7 +4,9 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{error::PayraError, Event, Participant};
+use crate::{
+    error::PayraError, validation::validate_new_members, Event, MemberRole, Participant,
+};
 
 #[derive(Accounts)]
 pub struct Whitelist<'info> {
@@ -21,31 +23,16 @@ pub struct Whitelist<'info> {
 
 impl<'info> Whitelist<'info> {
     pub fn whitelist(&mut self, wallets_to_add: Vec<Pubkey>) -> Result<()> {
-        require_keys_eq!(
-            self.creator.key(),
-            self.event.creator,
-            PayraError::Unauthorised
-        );
-
+        // creator or admins manage members
         require!(
-            self.event.whitelist.len() + wallets_to_add.len() <= 10,
-            PayraError::WhitelistFull
+            self.event.has_role(&self.creator.key(), MemberRole::Admin),
+            PayraError::Unauthorised
         );
 
-        let mut batch = wallets_to_add.clone();
-        batch.sort();
-        batch.dedup();
-        require!(
-            batch.len() == wallets_to_add.len(),
-            PayraError::DuplicateWallet
-        );
+        validate_new_members(&self.event, &wallets_to_add)?;
 
         for wallet in wallets_to_add {
-            require!(
-                !self.event.whitelist.contains(&wallet),
-                PayraError::DuplicateWallet
-            );
-            self.event.whitelist.push(wallet);
+            self.event.add_member(wallet, MemberRole::Member);
         }
 
         Ok(())
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::PayraError, Event, FractionalStatus, FractionalVault};
+
+#[derive(Accounts)]
+pub struct Buyout<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(constraint = mint.key() == event.mint @ PayraError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(constraint = nft_mint.key() == fractional_vault.nft_mint @ PayraError::NotAnNft)]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"fractional", event.key().as_ref(), nft_mint.key().as_ref()],
+        bump = fractional_vault.bump
+    )]
+    pub fractional_vault: Account<'info, FractionalVault>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = fractional_vault,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    // buyout proceeds, redeemed by the share holders
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = fractional_vault,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = buyer,
+    )]
+    pub buyer_payment: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_nft_account: Account<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Buyout<'info> {
+    // anyone paying at least the reserve price voted by the members takes
+    // the NFT out of escrow
+    pub fn buyout(&mut self, price: u64) -> Result<()> {
+        let vault = &mut self.fractional_vault;
+        require!(
+            vault.status == FractionalStatus::Active,
+            PayraError::NftNotInEscrow
+        );
+        require!(vault.reserve_price > 0, PayraError::ReservePriceNotSet);
+        require!(price >= vault.reserve_price, PayraError::BuyoutBelowReserve);
+
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.buyer_payment.to_account_info(),
+                    to: self.proceeds.to_account_info(),
+                    authority: self.buyer.to_account_info(),
+                },
+            ),
+            price,
+        )?;
+
+        let event_key = self.event.key();
+        let nft_mint = vault.nft_mint;
+        let vault_seeds: &[&[u8]] = &[
+            b"fractional",
+            event_key.as_ref(),
+            nft_mint.as_ref(),
+            &[vault.bump],
+        ];
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.escrow.to_account_info(),
+                    to: self.buyer_nft_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            1,
+        )?;
+
+        vault.status = FractionalStatus::BoughtOut {
+            buyer: self.buyer.key(),
+            price,
+        };
+        msg!("NFT {} bought out for {}", nft_mint, price);
+        Ok(())
+    }
+}
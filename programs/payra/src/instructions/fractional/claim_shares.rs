@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+};
+
+use crate::{error::PayraError, Event, FractionalVault, Participant};
+
+#[derive(Accounts)]
+pub struct ClaimShares<'info> {
+    // pays the rent of their share token account, they own it and get it
+    // back when closing it
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    #[account(
+        seeds = [b"participant", event.event_id.to_le_bytes().as_ref(), claimer.key().as_ref()],
+        bump = participant.bump
+    )]
+    pub participant: Account<'info, Participant>,
+
+    #[account(
+        mut,
+        seeds = [b"fractional", event.key().as_ref(), fractional_vault.nft_mint.as_ref()],
+        bump = fractional_vault.bump
+    )]
+    pub fractional_vault: Account<'info, FractionalVault>,
+
+    #[account(
+        mut,
+        seeds = [b"shares", fractional_vault.key().as_ref()],
+        bump = fractional_vault.share_mint_bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        associated_token::mint = share_mint,
+        associated_token::authority = claimer,
+    )]
+    pub claimer_shares: Account<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimShares<'info> {
+    // mints one share per base unit the participant contributed, still
+    // possible after a buyout so late claimers redeem their part
+    pub fn claim_shares(&mut self) -> Result<()> {
+        let vault = &mut self.fractional_vault;
+        let claimer = self.claimer.key();
+
+        require!(
+            !vault.claimed.contains(&claimer),
+            PayraError::SharesAlreadyClaimed
+        );
+        let shares = self.participant.contributed;
+        require!(shares > 0, PayraError::NothingToClaim);
+
+        let event_key = self.event.key();
+        let nft_mint = vault.nft_mint;
+        let vault_seeds: &[&[u8]] = &[
+            b"fractional",
+            event_key.as_ref(),
+            nft_mint.as_ref(),
+            &[vault.bump],
+        ];
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.share_mint.to_account_info(),
+                    to: self.claimer_shares.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            shares,
+        )?;
+
+        vault.claimed.push(claimer);
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::PayraError, Event, FractionalStatus, FractionalVault, MemberRole, Proposal,
+    ProposalType, FRACTIONAL_VAULT_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct Fractionalize<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+
+    // the spending proposal that paid the event funds out for the NFT
+    #[account(
+        mut,
+        seeds = [b"proposal", event.key().as_ref(), proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // shares use the decimals of the event mint, one share per base unit
+    #[account(constraint = mint.key() == event.mint @ PayraError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    // where the admin holds the NFT bought for the group
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = admin,
+    )]
+    pub admin_nft_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FractionalVault::INIT_SPACE,
+        seeds = [b"fractional", event.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub fractional_vault: Account<'info, FractionalVault>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"shares", fractional_vault.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = fractional_vault,
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = nft_mint,
+        associated_token::authority = fractional_vault,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Fractionalize<'info> {
+    // Moves the NFT into escrow once contributions are closed, the shares are
+    // then claimed by each participant for what they contributed. The NFT
+    // can't be matched to the payment on chain, so the admin names the
+    // settled spending proposal that paid for it, which can't back another
+    // NFT. No buyout is accepted before the members vote a reserve price.
+    pub fn fractionalize(&mut self, bumps: &FractionalizeBumps) -> Result<()> {
+        let event = &self.event;
+        require!(
+            event.has_role(&self.admin.key(), MemberRole::Admin),
+            PayraError::Unauthorised
+        );
+        require!(!event.is_cancelled, PayraError::EventCancelled);
+        require!(
+            Clock::get()?.unix_timestamp >= event.deadline,
+            PayraError::ContributionsStillOpen
+        );
+        require!(event.total_contributed > 0, PayraError::NothingToClaim);
+        let proposal = &self.proposal;
+        require!(
+            matches!(proposal.proposal_type, ProposalType::Spending)
+                && proposal.settled
+                && !proposal.cancelled,
+            PayraError::NftNotPaidByEvent
+        );
+        require!(
+            proposal.fractional_vault.is_none(),
+            PayraError::ProposalAlreadyFractionalized
+        );
+        require!(
+            self.nft_mint.decimals == 0 && self.nft_mint.supply == 1,
+            PayraError::NotAnNft
+        );
+
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.admin_nft_account.to_account_info(),
+                    to: self.escrow.to_account_info(),
+                    authority: self.admin.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        self.fractional_vault.set_inner(FractionalVault {
+            version: FRACTIONAL_VAULT_VERSION,
+            event: event.key(),
+            mint: event.mint,
+            nft_mint: self.nft_mint.key(),
+            share_mint: self.share_mint.key(),
+            proposal: proposal.key(),
+            total_shares: event.total_contributed,
+            redeemed_shares: 0,
+            reserve_price: 0,
+            claimed: Vec::new(),
+            status: FractionalStatus::Active,
+            bump: bumps.fractional_vault,
+            share_mint_bump: bumps.share_mint,
+        });
+
+        self.proposal.fractional_vault = Some(self.fractional_vault.key());
+
+        msg!(
+            "NFT {} paid by proposal {} fractionalized into {} shares",
+            self.nft_mint.key(),
+            self.proposal.proposal_id,
+            self.event.total_contributed
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+pub mod fractionalize;
+pub mod claim_shares;
+pub mod buyout;
+pub mod redeem_shares;
+pub mod redeem_nft;
+
+pub use fractionalize::*;
+pub use claim_shares::*;
+pub use buyout::*;
+pub use redeem_shares::*;
+pub use redeem_nft::*;
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::PayraError, FractionalStatus, FractionalVault};
+
+#[derive(Accounts)]
+pub struct RedeemNft<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(constraint = nft_mint.key() == fractional_vault.nft_mint @ PayraError::NotAnNft)]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"fractional", fractional_vault.event.as_ref(), nft_mint.key().as_ref()],
+        bump = fractional_vault.bump
+    )]
+    pub fractional_vault: Account<'info, FractionalVault>,
+
+    #[account(
+        mut,
+        seeds = [b"shares", fractional_vault.key().as_ref()],
+        bump = fractional_vault.share_mint_bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = holder,
+    )]
+    pub holder_shares: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = fractional_vault,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = nft_mint,
+        associated_token::authority = holder,
+    )]
+    pub holder_nft_account: Account<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RedeemNft<'info> {
+    // a holder who gathered every share burns them and takes the NFT
+    pub fn redeem_nft(&mut self) -> Result<()> {
+        let vault = &mut self.fractional_vault;
+        require!(
+            vault.status == FractionalStatus::Active,
+            PayraError::NftNotInEscrow
+        );
+        // unclaimed shares count, they can't be gathered before being claimed
+        require!(
+            self.holder_shares.amount == vault.total_shares,
+            PayraError::NotAllShares
+        );
+
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.share_mint.to_account_info(),
+                    from: self.holder_shares.to_account_info(),
+                    authority: self.holder.to_account_info(),
+                },
+            ),
+            vault.total_shares,
+        )?;
+
+        let vault_seeds: &[&[u8]] = &[
+            b"fractional",
+            vault.event.as_ref(),
+            vault.nft_mint.as_ref(),
+            &[vault.bump],
+        ];
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.escrow.to_account_info(),
+                    to: self.holder_nft_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            1,
+        )?;
+
+        vault.redeemed_shares = vault.total_shares;
+        vault.status = FractionalStatus::Redeemed {
+            holder: self.holder.key(),
+        };
+        Ok(())
+    }
+}
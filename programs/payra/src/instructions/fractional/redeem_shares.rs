@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::PayraError, logic::redemption_payout, FractionalStatus, FractionalVault};
+
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"fractional", fractional_vault.event.as_ref(), fractional_vault.nft_mint.as_ref()],
+        bump = fractional_vault.bump
+    )]
+    pub fractional_vault: Account<'info, FractionalVault>,
+
+    #[account(
+        mut,
+        seeds = [b"shares", fractional_vault.key().as_ref()],
+        bump = fractional_vault.share_mint_bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = holder,
+    )]
+    pub holder_shares: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address(&fractional_vault.key(), &fractional_vault.mint)
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = holder_payment.mint == fractional_vault.mint @ PayraError::InvalidMint
+    )]
+    pub holder_payment: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> RedeemShares<'info> {
+    // burns shares against their part of the buyout proceeds
+    pub fn redeem_shares(&mut self, shares: u64) -> Result<()> {
+        let vault = &mut self.fractional_vault;
+        require!(
+            matches!(vault.status, FractionalStatus::BoughtOut { .. }),
+            PayraError::NotBoughtOut
+        );
+        require!(shares > 0, PayraError::ZeroAmount);
+
+        let payout = redemption_payout(self.proceeds.amount, shares, vault.outstanding_shares())
+            .ok_or(PayraError::MathOverflow)?;
+
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.share_mint.to_account_info(),
+                    from: self.holder_shares.to_account_info(),
+                    authority: self.holder.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        let vault_seeds: &[&[u8]] = &[
+            b"fractional",
+            vault.event.as_ref(),
+            vault.nft_mint.as_ref(),
+            &[vault.bump],
+        ];
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.proceeds.to_account_info(),
+                    to: self.holder_payment.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            payout,
+        )?;
+
+        vault.redeemed_shares = vault
+            .redeemed_shares
+            .checked_add(shares)
+            .ok_or(PayraError::MathOverflow)?;
+        Ok(())
+    }
+}
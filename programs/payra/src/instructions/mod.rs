@@ -1,5 +1,7 @@
 pub mod contribution_pools;
+pub mod fractional;
 pub mod initialize;
 
 pub use contribution_pools::*;
+pub use fractional::*;
 pub use initialize::*;
@@ -5,7 +5,11 @@
 pub mod constants;
 pub mod error;
 pub mod instructions;
+pub mod settlement;
 pub mod state;
+pub mod validation;
+
+pub use payra_logic as logic;
 
 use anchor_lang::prelude::*;
 
@@ -31,6 +35,21 @@ pub mod payra {
         ctx.accounts.close_event()
     }
 
+    pub fn set_contribution_rules(
+        ctx: Context<SetContributionRules>,
+        rules: ContributionRules,
+    ) -> Result<()> {
+        ctx.accounts.set_contribution_rules(rules)
+    }
+
+    pub fn set_dues(ctx: Context<SetDues>, amount: u64, period: i64) -> Result<()> {
+        ctx.accounts.set_dues(amount, period)
+    }
+
+    pub fn set_event_access(ctx: Context<SetEventAccess>, access: EventAccess) -> Result<()> {
+        ctx.accounts.set_event_access(access)
+    }
+
     pub fn contribute(ctx: Context<Contribute>, amount: u64) -> Result<()> {
         ctx.accounts.contribute(amount, &ctx.bumps)
     }
@@ -39,6 +58,14 @@ pub mod payra {
         ctx.accounts.whitelist(wallets_to_add)
     }
 
+    pub fn join_event(ctx: Context<JoinEvent>, voucher: InviteVoucher) -> Result<()> {
+        ctx.accounts.join_event(voucher, &ctx.bumps)
+    }
+
+    pub fn set_member_role(ctx: Context<SetMemberRole>, wallet: Pubkey, role: MemberRole) -> Result<()> {
+        ctx.accounts.set_member_role(wallet, role)
+    }
+
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         title: String,
@@ -54,6 +81,18 @@ pub mod payra {
         ctx.accounts.vote(vote_choice)
     }
 
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.create_session(session_key, expires_at, &ctx.bumps)
+    }
+
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        ctx.accounts.revoke_session()
+    }
+
     pub fn settle_proposal(ctx: Context<SettleProposal>) -> Result<()> {
         settle_proposal_handler(ctx)
     }
@@ -65,4 +104,107 @@ pub mod payra {
     pub fn settle_event(ctx: Context<SettleEvent>) -> Result<()>{ 
         settle_event_handler(ctx)
     }
+
+    pub fn create_governance_proposal(
+        ctx: Context<CreateGovernanceProposal>,
+        proposal_type: ProposalType,
+        deadline: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_governance_proposal(proposal_type, deadline, ctx.bumps)
+    }
+
+    pub fn settle_governance_proposal(ctx: Context<SettleGovernanceProposal>) -> Result<()> {
+        ctx.accounts.settle_governance_proposal()
+    }
+
+    pub fn apply_withdraw_account_change(ctx: Context<ApplyWithdrawAccountChange>) -> Result<()> {
+        ctx.accounts.apply_withdraw_account_change()
+    }
+
+    pub fn propose_creator_transfer(
+        ctx: Context<ProposeCreatorTransfer>,
+        new_creator: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.propose_creator_transfer(new_creator)
+    }
+
+    pub fn accept_creator_transfer(ctx: Context<AcceptCreatorTransfer>) -> Result<()> {
+        ctx.accounts.accept_creator_transfer()
+    }
+
+    pub fn create_milestone_proposal(
+        ctx: Context<CreateMilestoneProposal>,
+        title: String,
+        milestones: Vec<u64>,
+        release: MilestoneRelease,
+        spendings: Vec<SpendingShare>,
+        deadline: i64,
+    ) -> Result<()> {
+        ctx.accounts.create_milestone_proposal(
+            title, milestones, release, spendings, deadline, ctx.bumps,
+        )
+    }
+
+    pub fn approve_milestone(ctx: Context<ApproveMilestone>) -> Result<()> {
+        ctx.accounts.approve_milestone()
+    }
+
+    pub fn release_milestone(ctx: Context<ReleaseMilestone>) -> Result<()> {
+        release_milestone_handler(ctx)
+    }
+
+    pub fn cancel_milestones(ctx: Context<CancelMilestones>) -> Result<()> {
+        ctx.accounts.cancel_milestones()
+    }
+
+    pub fn fractionalize(ctx: Context<Fractionalize>) -> Result<()> {
+        ctx.accounts.fractionalize(&ctx.bumps)
+    }
+
+    pub fn claim_shares(ctx: Context<ClaimShares>) -> Result<()> {
+        ctx.accounts.claim_shares()
+    }
+
+    pub fn buyout(ctx: Context<Buyout>, price: u64) -> Result<()> {
+        ctx.accounts.buyout(price)
+    }
+
+    pub fn redeem_shares(ctx: Context<RedeemShares>, shares: u64) -> Result<()> {
+        ctx.accounts.redeem_shares(shares)
+    }
+
+    pub fn redeem_nft(ctx: Context<RedeemNft>) -> Result<()> {
+        ctx.accounts.redeem_nft()
+    }
+
+    pub fn get_event_summary(ctx: Context<GetEventSummary>) -> Result<EventSummary> {
+        get_event_summary_handler(ctx)
+    }
+
+    pub fn preview_settlement(ctx: Context<PreviewSettlement>) -> Result<SettlementPreview> {
+        preview_settlement_handler(ctx)
+    }
+
+    pub fn get_participant_balance(
+        ctx: Context<GetParticipantBalance>,
+    ) -> Result<ParticipantBalance> {
+        ctx.accounts.get_participant_balance()
+    }
+
+    pub fn get_dues_status(ctx: Context<GetDuesStatus>) -> Result<DuesStatus> {
+        get_dues_status_handler(ctx)
+    }
+
+    pub fn migrate_event(ctx: Context<MigrateEvent>) -> Result<()> {
+        ctx.accounts.migrate_event(ctx.remaining_accounts)
+    }
+
+    pub fn migrate_proposal(ctx: Context<MigrateProposal>) -> Result<()> {
+        ctx.accounts.migrate_proposal()
+    }
+
+    pub fn migrate_participant(ctx: Context<Migrate>) -> Result<()> {
+        ctx.accounts.migrate_participant()
+    }
 }
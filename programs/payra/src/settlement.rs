@@ -0,0 +1,462 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::PayraError, Event, Participant, Proposal, ProposalType};
+
+// Settlement engine shared by settle_event and off-chain clients. `settle`
+// records the net owed of every contributor, `dry_run` replays the pending
+// proposals on copies of the participant accounts and runs it on them.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Balance {
+    pub wallet: Pubkey,
+    pub contributed: u64,
+    pub spent: u64,
+    // positive when the group owes the wallet, negative when it owes the group
+    pub net_owed: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SettlementTransfer {
+    // None for the event vault
+    pub from: Option<Pubkey>,
+    pub to: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SettlementOutcome {
+    // spending proposals applied on top of the settled ones
+    pub applied_proposals: Vec<u16>,
+    // left in the vault once the applied proposals are paid out
+    pub vault_balance: u64,
+    pub balances: Vec<Balance>,
+    pub transfers: Vec<SettlementTransfer>,
+}
+
+// which pending spending proposals the dry run treats as approved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingProposals<'a> {
+    Ignore,
+    AssumeAll,
+    Assume(&'a [u16]),
+}
+
+impl PendingProposals<'_> {
+    // spendings not settled yet and milestone proposals with tranches left to
+    // release, approved or not
+    fn includes(&self, proposal: &Proposal) -> bool {
+        let pending = !proposal.cancelled
+            && match proposal.proposal_type {
+                ProposalType::Spending => !proposal.settled,
+                ProposalType::Milestones { .. } => proposal.next_milestone().is_some(),
+                _ => false,
+            };
+        pending
+            && match self {
+                PendingProposals::Ignore => false,
+                PendingProposals::AssumeAll => true,
+                PendingProposals::Assume(ids) => ids.contains(&proposal.proposal_id),
+            }
+    }
+}
+
+// payouts still to come from a pending proposal: the whole spending or the
+// tranches not released yet
+fn pending_payouts(proposal: &Proposal) -> Vec<u64> {
+    match proposal.proposal_type {
+        ProposalType::Milestones { .. } => proposal
+            .milestones
+            .iter()
+            .filter(|m| !m.released)
+            .map(|m| m.amount)
+            .collect(),
+        _ => vec![proposal.amount],
+    }
+}
+
+// What settle_event records: `participants` must hold one account per
+// contributor, in any order, and get their net owed set.
+pub fn settle(event: &Event, participants: &mut [Participant]) -> Result<Vec<Balance>> {
+    require!(
+        participants.len() == event.contributors.len(),
+        PayraError::InvalidParticipantAccounts
+    );
+
+    // each account must belong to a contributor not seen yet, so with the
+    // length check every contributor is covered exactly once
+    let mut pending = event.contributors.clone();
+    participants
+        .iter_mut()
+        .map(|p| {
+            let index = pending
+                .iter()
+                .position(|wallet| *wallet == p.wallet)
+                .ok_or(PayraError::InvalidParticipantWallet)?;
+            pending.swap_remove(index);
+
+            p.net_owed = p.projected_net_owed()?;
+            Ok(Balance {
+                wallet: p.wallet,
+                contributed: p.contributed,
+                spent: p.spent,
+                net_owed: p.net_owed,
+            })
+        })
+        .collect()
+}
+
+// Balances settle_event would record once the selected pending proposals
+// pass, and the transfers that even them out: the vault pays out first, then
+// wallets that spent more than they contributed pay the others back.
+// Contributors missing from `participants` are counted with nothing
+// contributed.
+pub fn dry_run(
+    event: &Event,
+    proposals: &[Proposal],
+    participants: &[Participant],
+    pending: PendingProposals,
+) -> Result<SettlementOutcome> {
+    let mut accounts: Vec<Participant> = event
+        .contributors
+        .iter()
+        .map(|wallet| {
+            participants
+                .iter()
+                .find(|p| p.wallet == *wallet)
+                .cloned()
+                .unwrap_or_else(|| Participant {
+                    version: crate::PARTICIPANT_VERSION,
+                    event: Pubkey::default(),
+                    event_id: event.event_id,
+                    wallet: *wallet,
+                    contributed: 0,
+                    spent: 0,
+                    refunded: false,
+                    bump: 0,
+                    net_owed: 0,
+                    periods_paid: 0,
+                })
+        })
+        .collect();
+
+    let mut vault_balance = event
+        .total_contributed
+        .checked_sub(event.total_spent)
+        .ok_or(PayraError::MathOverflow)?;
+    let mut applied_proposals = Vec::new();
+
+    // charged like settle_proposal and release_milestone do, each tranche
+    // split on its own
+    for proposal in proposals.iter().filter(|p| pending.includes(p)) {
+        for payout in pending_payouts(proposal) {
+            for (index, share) in proposal.spendings.iter().enumerate() {
+                let participant = accounts
+                    .iter_mut()
+                    .find(|p| p.wallet == share.wallet)
+                    .ok_or(PayraError::InvalidParticipantWallet)?;
+                participant.spent = participant
+                    .spent
+                    .checked_add(proposal.share_of(payout, index)?)
+                    .ok_or(PayraError::MathOverflow)?;
+            }
+            vault_balance = vault_balance
+                .checked_sub(payout)
+                .ok_or(PayraError::MathOverflow)?;
+        }
+        applied_proposals.push(proposal.proposal_id);
+    }
+
+    let balances = settle(event, &mut accounts)?;
+
+    Ok(SettlementOutcome {
+        applied_proposals,
+        vault_balance,
+        transfers: transfers(&balances, vault_balance),
+        balances,
+    })
+}
+
+// greedy matching of creditors, largest first, against the vault and then
+// the debtors, largest first
+fn transfers(balances: &[Balance], vault_balance: u64) -> Vec<SettlementTransfer> {
+    let mut creditors: Vec<(Pubkey, u64)> = balances
+        .iter()
+        .filter(|b| b.net_owed > 0)
+        .map(|b| (b.wallet, b.net_owed.unsigned_abs()))
+        .collect();
+    let mut debtors: Vec<(Option<Pubkey>, u64)> = balances
+        .iter()
+        .filter(|b| b.net_owed < 0)
+        .map(|b| (Some(b.wallet), b.net_owed.unsigned_abs()))
+        .collect();
+    creditors.sort_by_key(|c| std::cmp::Reverse(c.1));
+    debtors.sort_by_key(|d| std::cmp::Reverse(d.1));
+    if vault_balance > 0 {
+        debtors.insert(0, (None, vault_balance));
+    }
+
+    let mut transfers = Vec::new();
+    let mut debtors = debtors.into_iter();
+    let mut current = debtors.next();
+    for (to, mut owed) in creditors {
+        while owed > 0 {
+            let Some((from, available)) = current.as_mut() else {
+                return transfers;
+            };
+            let amount = owed.min(*available);
+            transfers.push(SettlementTransfer {
+                from: *from,
+                to,
+                amount,
+            });
+            owed -= amount;
+            *available -= amount;
+            if *available == 0 {
+                current = debtors.next();
+            }
+        }
+    }
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ContributionRules, EventAccess, MemberRole, Milestone, MilestoneRelease, SpendingShare,
+        EVENT_VERSION, PROPOSAL_VERSION,
+    };
+
+    // creator and one member, both contributed
+    fn event(member: Pubkey, creator: Pubkey, contributed: u64, spent: u64) -> Event {
+        Event {
+            version: EVENT_VERSION,
+            event_id: 1,
+            creator,
+            pending_creator: None,
+            mint: Pubkey::new_unique(),
+            withdraw_token_account: Pubkey::new_unique(),
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
+            target_amount: contributed,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
+            total_contributed: contributed,
+            total_spent: spent,
+            whitelist: vec![member],
+            roles: vec![MemberRole::Member],
+            contributors: vec![member, creator],
+            is_cancelled: false,
+            is_finalized: false,
+            deadline: 100,
+            proposal_count: 0,
+            name: "trip".to_string(),
+            bump: 255,
+        }
+    }
+
+    fn participant(wallet: Pubkey, contributed: u64, spent: u64) -> Participant {
+        Participant {
+            version: crate::PARTICIPANT_VERSION,
+            event: Pubkey::default(),
+            event_id: 1,
+            wallet,
+            contributed,
+            spent,
+            refunded: false,
+            bump: 255,
+            net_owed: 0,
+            periods_paid: 0,
+        }
+    }
+
+    // split 50/50 between `wallets`
+    fn spending(proposal_id: u16, amount: u64, wallets: [Pubkey; 2]) -> Proposal {
+        Proposal {
+            version: PROPOSAL_VERSION,
+            proposal_id,
+            title: "dinner".to_string(),
+            proposal_type: ProposalType::Spending,
+            amount,
+            spendings: wallets
+                .iter()
+                .map(|wallet| SpendingShare {
+                    wallet: *wallet,
+                    percentage: 50,
+                })
+                .collect(),
+            milestones: Vec::new(),
+            yes_votes: Vec::new(),
+            no_votes: Vec::new(),
+            milestone_approvals: Vec::new(),
+            creator: wallets[0],
+            deadline: 100,
+            settled: false,
+            cancelled: false,
+            fractional_vault: None,
+            bump: 255,
+        }
+    }
+
+    fn balance(wallet: Pubkey, contributed: u64, spent: u64) -> Balance {
+        Balance {
+            wallet,
+            contributed,
+            spent,
+            net_owed: contributed as i64 - spent as i64,
+        }
+    }
+
+    #[test]
+    fn vault_pays_back_first_then_the_debtors() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let balances = [
+            balance(a, 500, 100),
+            balance(b, 0, 300),
+            balance(c, 200, 100),
+        ];
+
+        // 100 left in the vault, a is owed 400 and c 100, b owes 300
+        assert_eq!(
+            transfers(&balances, 100),
+            vec![
+                SettlementTransfer {
+                    from: None,
+                    to: a,
+                    amount: 100,
+                },
+                SettlementTransfer {
+                    from: Some(b),
+                    to: a,
+                    amount: 300,
+                },
+            ]
+        );
+        assert!(transfers(&[balance(a, 100, 100)], 0).is_empty());
+    }
+
+    #[test]
+    fn applies_only_the_pending_proposals_assumed() {
+        let (member, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = event(member, creator, 400, 100);
+        let participants = [participant(member, 200, 50), participant(creator, 200, 50)];
+        let mut settled = spending(0, 100, [member, creator]);
+        settled.settled = true;
+        let mut cancelled = spending(1, 60, [member, creator]);
+        cancelled.cancelled = true;
+        let proposals = [
+            settled,
+            cancelled,
+            spending(2, 101, [member, creator]),
+            spending(3, 40, [creator, member]),
+        ];
+
+        let run = |pending| dry_run(&event, &proposals, &participants, pending).unwrap();
+
+        let ignored = run(PendingProposals::Ignore);
+        assert!(ignored.applied_proposals.is_empty());
+        assert_eq!(ignored.vault_balance, 300);
+        assert_eq!(
+            ignored.balances,
+            [balance(member, 200, 50), balance(creator, 200, 50)]
+        );
+
+        // the rounding remainder goes to the first share, like settle_proposal
+        let assumed = run(PendingProposals::Assume(&[1, 2]));
+        assert_eq!(assumed.applied_proposals, [2]);
+        assert_eq!(assumed.vault_balance, 199);
+        assert_eq!(
+            assumed.balances,
+            [balance(member, 200, 101), balance(creator, 200, 100)]
+        );
+
+        let all = run(PendingProposals::AssumeAll);
+        assert_eq!(all.applied_proposals, [2, 3]);
+        assert_eq!(all.vault_balance, 159);
+        assert_eq!(
+            all.balances,
+            [balance(member, 200, 121), balance(creator, 200, 120)]
+        );
+        assert_eq!(all.transfers, transfers(&all.balances, 159));
+    }
+
+    #[test]
+    fn applies_the_milestones_left_to_release() {
+        let (member, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = event(member, creator, 400, 100);
+        let participants = [participant(member, 200, 50), participant(creator, 200, 50)];
+        let mut proposal = spending(0, 300, [member, creator]);
+        proposal.proposal_type = ProposalType::Milestones {
+            release: MilestoneRelease::Approval,
+        };
+        proposal.settled = true;
+        proposal.milestones = vec![
+            Milestone {
+                amount: 100,
+                released: true,
+            },
+            Milestone {
+                amount: 101,
+                released: false,
+            },
+            Milestone {
+                amount: 99,
+                released: false,
+            },
+        ];
+
+        // each tranche is split on its own, like release_milestone
+        let outcome = dry_run(
+            &event,
+            std::slice::from_ref(&proposal),
+            &participants,
+            PendingProposals::AssumeAll,
+        )
+        .unwrap();
+        assert_eq!(outcome.applied_proposals, [0]);
+        assert_eq!(outcome.vault_balance, 100);
+        assert_eq!(
+            outcome.balances,
+            [balance(member, 200, 151), balance(creator, 200, 149)]
+        );
+
+        for milestone in proposal.milestones.iter_mut() {
+            milestone.released = true;
+        }
+        let outcome = dry_run(
+            &event,
+            &[proposal],
+            &participants,
+            PendingProposals::AssumeAll,
+        )
+        .unwrap();
+        assert!(outcome.applied_proposals.is_empty());
+    }
+
+    #[test]
+    fn settles_every_contributor_once() {
+        let (member, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = event(member, creator, 400, 100);
+
+        let mut participants = [participant(creator, 200, 150), participant(member, 200, 50)];
+        let balances = settle(&event, &mut participants).unwrap();
+        assert_eq!(
+            balances,
+            [balance(creator, 200, 150), balance(member, 200, 50)]
+        );
+        assert_eq!(participants[0].net_owed, 50);
+        assert_eq!(participants[1].net_owed, 150);
+
+        let err = settle(&event, &mut [participant(member, 200, 50)]).unwrap_err();
+        assert_eq!(err, error!(PayraError::InvalidParticipantAccounts));
+
+        let mut twice = [participant(member, 200, 50), participant(member, 200, 50)];
+        let err = settle(&event, &mut twice).unwrap_err();
+        assert_eq!(err, error!(PayraError::InvalidParticipantWallet));
+    }
+}
@@ -1,17 +1,37 @@
 use anchor_lang::prelude::*;
 
+use crate::{logic::periods_started, MAX_CONTRIBUTORS};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Event {
+    pub version: u8,
     pub event_id: u64,
     pub creator: Pubkey,
+    // set by the creator, becomes the creator once they accept
+    pub pending_creator: Option<Pubkey>,
+    pub mint: Pubkey,
     pub withdraw_token_account: Pubkey,
+    // replacement approved by the group, applicable once the timelock expires
+    pub pending_withdraw_token_account: Option<Pubkey>,
+    pub withdraw_change_available_at: i64,
     pub target_amount: u64,
+    pub contribution_rules: ContributionRules,
+    // set for ongoing pools where members pay a fixed amount every period
+    pub dues: Option<Dues>,
+    pub access: EventAccess,
     pub total_contributed: u64,
     pub total_spent: u64,
 
     #[max_len(10)]
     pub whitelist: Vec<Pubkey>,
+    // role of each whitelisted wallet, same order as `whitelist`
+    #[max_len(10)]
+    pub roles: Vec<MemberRole>,
+    // every wallet that contributed, in order of their first contribution.
+    // They all have a participant account and are settled together
+    #[max_len(MAX_CONTRIBUTORS)]
+    pub contributors: Vec<Pubkey>,
 
     pub is_cancelled: bool,
     pub is_finalized: bool,
@@ -24,6 +44,153 @@ pub struct Event {
     pub bump: u8,
 }
 
+// limits enforced on every contribution, zero means no limit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct ContributionRules {
+    pub min_contribution: u64,
+    pub max_per_participant: u64,
+    // every member owes exactly target_amount / members
+    pub equal_share: bool,
+    pub overfunding: OverfundingMode,
+    // contributions may continue past the target up to this amount
+    pub stretch_goal: u64,
+}
+
+// recurring dues, each member owes `amount` for every `period` seconds
+// started since `start`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct Dues {
+    pub amount: u64,
+    pub period: i64,
+    pub start: i64,
+}
+
+impl Dues {
+    // periods due by `now`, the running one included
+    pub fn periods_due(&self, now: i64) -> Option<u32> {
+        periods_started(self.start, self.period, now)
+    }
+
+    // every period starting before `deadline`, dues can't be paid further ahead
+    pub fn periods_until(&self, deadline: i64) -> Option<u32> {
+        periods_started(self.start, self.period, deadline.checked_sub(1)?)
+    }
+}
+
+// who may contribute to an event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum EventAccess {
+    // only whitelisted members contribute
+    #[default]
+    Whitelist,
+    // anyone contributes, contributors reaching `voting_threshold` in total
+    // are whitelisted as members so they can propose and vote
+    Open {
+        voting_threshold: u64,
+    },
+}
+
+// what happens to contributions past the funding cap (target or stretch goal)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum OverfundingMode {
+    // no cap, keep accepting contributions
+    #[default]
+    Allow,
+    // reject any contribution that would exceed the cap
+    Reject,
+    // accept only what's left up to the cap
+    PartialFill,
+}
+
+// ordered by privilege, each role can do everything the ones below it can
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, InitSpace)]
+pub enum MemberRole {
+    // view only
+    Observer,
+    // contribute and vote
+    Member,
+    // create spending proposals
+    Treasurer,
+    // manage members
+    Admin,
+}
+
+impl Event {
+    // creator is always an implicit admin
+    pub fn role_of(&self, wallet: &Pubkey) -> Option<MemberRole> {
+        if self.creator == *wallet {
+            return Some(MemberRole::Admin);
+        }
+        self.whitelist
+            .iter()
+            .position(|w| w == wallet)
+            .map(|i| self.roles[i])
+    }
+
+    // observers stay view only, even in open events
+    pub fn can_contribute(&self, wallet: &Pubkey) -> bool {
+        match self.access {
+            EventAccess::Whitelist => self.has_role(wallet, MemberRole::Member),
+            EventAccess::Open { .. } => self.role_of(wallet) != Some(MemberRole::Observer),
+        }
+    }
+
+    pub fn funding_cap(&self) -> u64 {
+        self.target_amount.max(self.contribution_rules.stretch_goal)
+    }
+
+    pub fn has_role(&self, wallet: &Pubkey, role: MemberRole) -> bool {
+        self.role_of(wallet).is_some_and(|r| r >= role)
+    }
+
+    // everyone allowed to vote, the creator included
+    pub fn voters(&self) -> Vec<Pubkey> {
+        let mut voters: Vec<Pubkey> = self
+            .whitelist
+            .iter()
+            .zip(self.roles.iter())
+            .filter(|(_, role)| **role >= MemberRole::Member)
+            .map(|(wallet, _)| *wallet)
+            .collect();
+        voters.push(self.creator);
+        voters
+    }
+
+    pub fn eligible_voters(&self) -> usize {
+        self.roles.iter().filter(|r| **r >= MemberRole::Member).count() + 1
+    }
+
+    // the equal share is split between the voters, once someone paid theirs
+    // the voters can't change
+    pub fn voters_frozen(&self) -> bool {
+        self.contribution_rules.equal_share && self.total_contributed > 0
+    }
+
+    // voters and every other wallet that contributed, donors of open events
+    // included
+    pub fn participants(&self) -> Vec<Pubkey> {
+        let mut wallets = self.voters();
+        for wallet in &self.contributors {
+            if !wallets.contains(wallet) {
+                wallets.push(*wallet);
+            }
+        }
+        wallets
+    }
+
+    pub fn add_member(&mut self, wallet: Pubkey, role: MemberRole) {
+        self.whitelist.push(wallet);
+        self.roles.push(role);
+    }
+
+    pub fn remove_member(&mut self, wallet: &Pubkey) {
+        if let Some(i) = self.whitelist.iter().position(|w| w == wallet) {
+            self.whitelist.remove(i);
+            self.roles.remove(i);
+        }
+    }
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct EventArgs {
     pub name: String,
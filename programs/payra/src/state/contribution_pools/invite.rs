@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{MemberRole, INVITE_MESSAGE_PREFIX};
+
+// Invite link content, signed off-chain by an admin of the event. Anyone
+// holding the voucher and its signature can join until it expires or runs
+// out of uses.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct InviteVoucher {
+    pub event: Pubkey,
+    pub issuer: Pubkey,
+    // chosen by the issuer, tells their vouchers apart
+    pub invite_id: u64,
+    // role given to whoever joins, only the creator can invite admins
+    pub role: MemberRole,
+    pub max_uses: u16,
+    pub expires_at: i64,
+}
+
+impl InviteVoucher {
+    // bytes the issuer signs
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = INVITE_MESSAGE_PREFIX.to_vec();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+// uses of a voucher, created when it is first redeemed
+#[account]
+#[derive(InitSpace)]
+pub struct Invite {
+    pub version: u8,
+    pub event: Pubkey,
+    pub issuer: Pubkey,
+    pub invite_id: u64,
+    pub uses: u16,
+    pub bump: u8,
+}
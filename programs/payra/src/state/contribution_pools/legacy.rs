@@ -0,0 +1,481 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PayraError, ContributionRules, Event, EventAccess, MemberRole, Participant, Proposal,
+    ProposalType, SpendingShare, EVENT_VERSION, MAX_PARTICIPANTS, PARTICIPANT_VERSION,
+    PROPOSAL_VERSION,
+};
+
+// Frozen snapshots of the layouts the program first shipped with, before the
+// version byte was introduced. They are only used by the migrate_*
+// instructions to decode those accounts, never change them.
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct EventV0 {
+    pub event_id: u64,
+    pub creator: Pubkey,
+    pub withdraw_token_account: Pubkey,
+    pub target_amount: u64,
+    pub total_contributed: u64,
+    pub total_spent: u64,
+    #[max_len(10)]
+    pub whitelist: Vec<Pubkey>,
+    pub is_cancelled: bool,
+    pub is_finalized: bool,
+    pub deadline: i64,
+    pub proposal_count: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct ProposalV0 {
+    pub proposal_id: u16,
+    #[max_len(32)]
+    pub title: String,
+    pub proposal_type: ProposalTypeV0,
+    pub amount: u64,
+    #[max_len(MAX_PARTICIPANTS)]
+    pub spendings: Vec<SpendingShare>,
+    #[max_len(MAX_PARTICIPANTS)]
+    pub yes_votes: Vec<Pubkey>,
+    #[max_len(MAX_PARTICIPANTS)]
+    pub no_votes: Vec<Pubkey>,
+    pub creator: Pubkey,
+    pub deadline: i64,
+    pub settled: bool,
+    pub cancelled: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub enum ProposalTypeV0 {
+    Spending,
+    EventSettlement,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct ParticipantV0 {
+    pub event: Pubkey,
+    pub event_id: u64,
+    pub wallet: Pubkey,
+    pub contributed: u64,
+    pub spent: u64,
+    pub refunded: bool,
+    pub bump: u8,
+    pub net_owed: i64,
+}
+
+impl EventV0 {
+    // every whitelisted wallet could contribute and vote, the creator last
+    // like in Event::voters
+    pub fn voters(&self) -> Vec<Pubkey> {
+        let mut voters = self.whitelist.clone();
+        voters.push(self.creator);
+        voters
+    }
+
+    // v0 didn't record the mint, it's taken from the withdraw token account
+    // which was checked against the mint in create_event
+    fn upgrade(self, mint: Pubkey, contributors: Vec<Pubkey>) -> Event {
+        Event {
+            version: EVENT_VERSION,
+            event_id: self.event_id,
+            creator: self.creator,
+            pending_creator: None,
+            mint,
+            withdraw_token_account: self.withdraw_token_account,
+            pending_withdraw_token_account: None,
+            withdraw_change_available_at: 0,
+            target_amount: self.target_amount,
+            contribution_rules: ContributionRules::default(),
+            dues: None,
+            access: EventAccess::Whitelist,
+            total_contributed: self.total_contributed,
+            total_spent: self.total_spent,
+            roles: vec![MemberRole::Member; self.whitelist.len()],
+            whitelist: self.whitelist,
+            contributors,
+            is_cancelled: self.is_cancelled,
+            is_finalized: self.is_finalized,
+            deadline: self.deadline,
+            proposal_count: self.proposal_count,
+            name: self.name,
+            bump: self.bump,
+        }
+    }
+}
+
+impl From<ProposalV0> for Proposal {
+    fn from(old: ProposalV0) -> Self {
+        Proposal {
+            version: PROPOSAL_VERSION,
+            proposal_id: old.proposal_id,
+            title: old.title,
+            proposal_type: match old.proposal_type {
+                ProposalTypeV0::Spending => ProposalType::Spending,
+                ProposalTypeV0::EventSettlement => ProposalType::EventSettlement,
+            },
+            amount: old.amount,
+            spendings: old.spendings,
+            milestones: Vec::new(),
+            yes_votes: old.yes_votes,
+            no_votes: old.no_votes,
+            milestone_approvals: Vec::new(),
+            creator: old.creator,
+            deadline: old.deadline,
+            settled: old.settled,
+            cancelled: old.cancelled,
+            fractional_vault: None,
+            bump: old.bump,
+        }
+    }
+}
+
+// contributions made before dues existed don't pay for any period
+impl From<ParticipantV0> for Participant {
+    fn from(old: ParticipantV0) -> Self {
+        Participant {
+            version: PARTICIPANT_VERSION,
+            event: old.event,
+            event_id: old.event_id,
+            wallet: old.wallet,
+            contributed: old.contributed,
+            spent: old.spent,
+            refunded: old.refunded,
+            bump: old.bump,
+            net_owed: old.net_owed,
+            periods_paid: 0,
+        }
+    }
+}
+
+// v0 settle_proposal paid spendings out of the vault without adding them to
+// the event total_spent, what a migrated proposal still has to add to it
+pub fn unrecorded_spending(proposal: &Proposal) -> u64 {
+    let paid = matches!(proposal.proposal_type, ProposalType::Spending)
+        && proposal.settled
+        && !proposal.cancelled;
+    if paid {
+        proposal.amount
+    } else {
+        0
+    }
+}
+
+// v0 accounts have no version byte, they are recognised by their allocation
+// size which was always exactly 8 + INIT_SPACE of the old layout
+fn check_v0(data: &[u8], legacy_len: usize, version: u8) -> Result<()> {
+    if data.len() == legacy_len {
+        return Ok(());
+    }
+    match data.get(8) {
+        Some(stored) if *stored == version => err!(PayraError::AlreadyMigrated),
+        _ => err!(PayraError::InvalidAccountVersion),
+    }
+}
+
+// `withdraw_token_account` is the (key, mint) of the event's current withdraw
+// token account, v0 events recover their mint from it
+// `participants` pairs the participant address of every voter, in the order
+// of EventV0::voters, with whether an account exists there. v0 created it
+// on the first contribution, so only those voters become contributors.
+pub fn upgrade_event(
+    data: &[u8],
+    withdraw_token_account: Option<(Pubkey, Pubkey)>,
+    participants: &[(Pubkey, bool)],
+) -> Result<Event> {
+    check_v0(data, 8 + EventV0::INIT_SPACE, EVENT_VERSION)?;
+    let old = EventV0::deserialize(&mut &data[8..])?;
+
+    let (key, mint) = withdraw_token_account.ok_or(PayraError::InvalidWithdrawAccount)?;
+    require_keys_eq!(
+        key,
+        old.withdraw_token_account,
+        PayraError::InvalidWithdrawAccount
+    );
+
+    let voters = old.voters();
+    require!(
+        participants.len() == voters.len(),
+        PayraError::InvalidParticipantAccounts
+    );
+    let mut contributors = Vec::new();
+    for (wallet, (address, exists)) in voters.into_iter().zip(participants) {
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"participant",
+                old.event_id.to_le_bytes().as_ref(),
+                wallet.as_ref(),
+            ],
+            &crate::ID,
+        );
+        require_keys_eq!(*address, expected, PayraError::InvalidParticipantAccounts);
+        if *exists {
+            contributors.push(wallet);
+        }
+    }
+    Ok(old.upgrade(mint, contributors))
+}
+
+pub fn upgrade_proposal(data: &[u8]) -> Result<Proposal> {
+    check_v0(data, 8 + ProposalV0::INIT_SPACE, PROPOSAL_VERSION)?;
+    Ok(ProposalV0::deserialize(&mut &data[8..])?.into())
+}
+
+pub fn upgrade_participant(data: &[u8]) -> Result<Participant> {
+    check_v0(data, 8 + ParticipantV0::INIT_SPACE, PARTICIPANT_VERSION)?;
+    Ok(ParticipantV0::deserialize(&mut &data[8..])?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{prelude::borsh::to_vec, Discriminator};
+
+    // v0 accounts were allocated at their full size, the tail is zeroed
+    fn stored_v0(discriminator: &[u8], body: &impl AnchorSerialize, space: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(to_vec(body).unwrap());
+        data.resize(8 + space, 0);
+        data
+    }
+
+    fn stored(discriminator: &[u8], account: &impl AnchorSerialize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(to_vec(account).unwrap());
+        data
+    }
+
+    fn event_v0() -> EventV0 {
+        EventV0 {
+            event_id: 7,
+            creator: Pubkey::new_unique(),
+            withdraw_token_account: Pubkey::new_unique(),
+            target_amount: 1_000,
+            total_contributed: 300,
+            total_spent: 0,
+            whitelist: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            is_cancelled: false,
+            is_finalized: true,
+            deadline: 9_000,
+            proposal_count: 3,
+            name: "trip".to_string(),
+            bump: 254,
+        }
+    }
+
+    // participant address of every voter, and whether it exists
+    fn participants(old: &EventV0, contributed: &[bool]) -> Vec<(Pubkey, bool)> {
+        old.voters()
+            .iter()
+            .zip(contributed)
+            .map(|(wallet, exists)| {
+                let seeds = [
+                    b"participant".as_ref(),
+                    &old.event_id.to_le_bytes(),
+                    wallet.as_ref(),
+                ];
+                (Pubkey::find_program_address(&seeds, &crate::ID).0, *exists)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn upgrades_event_v0_with_the_withdraw_account_mint() {
+        let old = event_v0();
+        let mint = Pubkey::new_unique();
+        let data = stored_v0(Event::DISCRIMINATOR, &old, EventV0::INIT_SPACE);
+        // the second member never contributed
+        let participants = participants(&old, &[true, false, true]);
+        let withdraw = Some((old.withdraw_token_account, mint));
+        let event = upgrade_event(&data, withdraw, &participants).unwrap();
+
+        assert_eq!(event.version, EVENT_VERSION);
+        assert_eq!(event.event_id, old.event_id);
+        assert_eq!(event.creator, old.creator);
+        assert_eq!(event.pending_creator, None);
+        assert_eq!(event.mint, mint);
+        assert_eq!(event.withdraw_token_account, old.withdraw_token_account);
+        assert_eq!(event.pending_withdraw_token_account, None);
+        assert_eq!(event.withdraw_change_available_at, 0);
+        assert_eq!(event.target_amount, old.target_amount);
+        assert_eq!(event.total_contributed, old.total_contributed);
+        assert_eq!(event.total_spent, old.total_spent);
+        assert_eq!(event.whitelist, old.whitelist);
+        assert!(event.roles == vec![MemberRole::Member; 2]);
+        assert_eq!(event.contributors, [old.whitelist[0], old.creator]);
+        assert!(event.access == EventAccess::Whitelist);
+        assert!(event.dues.is_none());
+        assert_eq!(event.contribution_rules.min_contribution, 0);
+        assert_eq!(event.contribution_rules.max_per_participant, 0);
+        assert!(!event.contribution_rules.equal_share);
+        assert_eq!(event.is_cancelled, old.is_cancelled);
+        assert_eq!(event.is_finalized, old.is_finalized);
+        assert_eq!(event.deadline, old.deadline);
+        assert_eq!(event.proposal_count, old.proposal_count);
+        assert_eq!(event.name, old.name);
+        assert_eq!(event.bump, old.bump);
+    }
+
+    #[test]
+    fn rejects_event_v0_without_its_withdraw_account() {
+        let old = event_v0();
+        let data = stored_v0(Event::DISCRIMINATOR, &old, EventV0::INIT_SPACE);
+
+        let participants = participants(&old, &[true; 3]);
+        let err = upgrade_event(&data, None, &participants).err().unwrap();
+        assert_eq!(err, error!(PayraError::InvalidWithdrawAccount));
+
+        let other = Some((Pubkey::new_unique(), Pubkey::new_unique()));
+        let err = upgrade_event(&data, other, &participants).err().unwrap();
+        assert_eq!(err, error!(PayraError::InvalidWithdrawAccount));
+    }
+
+    #[test]
+    fn rejects_event_v0_without_every_voter_participant() {
+        let old = event_v0();
+        let data = stored_v0(Event::DISCRIMINATOR, &old, EventV0::INIT_SPACE);
+        let withdraw = Some((old.withdraw_token_account, Pubkey::new_unique()));
+
+        // a voter left out would never be settled
+        let mut participants = participants(&old, &[true; 3]);
+        let last = participants.pop().unwrap();
+        let err = upgrade_event(&data, withdraw, &participants).err().unwrap();
+        assert_eq!(err, error!(PayraError::InvalidParticipantAccounts));
+
+        participants.insert(0, last);
+        let err = upgrade_event(&data, withdraw, &participants).err().unwrap();
+        assert_eq!(err, error!(PayraError::InvalidParticipantAccounts));
+    }
+
+    #[test]
+    fn rejects_events_already_migrated() {
+        let old = event_v0();
+        let withdraw = Some((old.withdraw_token_account, Pubkey::new_unique()));
+        let event = old.upgrade(Pubkey::new_unique(), Vec::new());
+        let err = upgrade_event(&stored(Event::DISCRIMINATOR, &event), withdraw, &[])
+            .err()
+            .unwrap();
+        assert_eq!(err, error!(PayraError::AlreadyMigrated));
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut data = stored(Event::DISCRIMINATOR, &event_v0());
+        data[8] = EVENT_VERSION + 1;
+        let err = upgrade_event(&data, None, &[]).err().unwrap();
+        assert_eq!(err, error!(PayraError::InvalidAccountVersion));
+    }
+
+    #[test]
+    fn upgrades_proposal_v0() {
+        let creator = Pubkey::new_unique();
+        let v0 = ProposalV0 {
+            proposal_id: 4,
+            title: "dinner".to_string(),
+            proposal_type: ProposalTypeV0::EventSettlement,
+            amount: 250,
+            spendings: vec![SpendingShare {
+                wallet: creator,
+                percentage: 100,
+            }],
+            yes_votes: vec![creator],
+            no_votes: Vec::new(),
+            creator,
+            deadline: 1_000,
+            settled: true,
+            cancelled: false,
+            bump: 253,
+        };
+        let data = stored_v0(Proposal::DISCRIMINATOR, &v0, ProposalV0::INIT_SPACE);
+        let proposal = upgrade_proposal(&data).unwrap();
+
+        assert_eq!(proposal.version, PROPOSAL_VERSION);
+        assert_eq!(proposal.proposal_id, 4);
+        assert_eq!(proposal.title, "dinner");
+        assert!(matches!(
+            proposal.proposal_type,
+            ProposalType::EventSettlement
+        ));
+        assert_eq!(proposal.amount, 250);
+        assert_eq!(proposal.spendings.len(), 1);
+        assert_eq!(proposal.spendings[0].wallet, creator);
+        assert_eq!(proposal.yes_votes, vec![creator]);
+        assert!(proposal.milestones.is_empty());
+        assert!(proposal.milestone_approvals.is_empty());
+        assert_eq!(proposal.creator, creator);
+        assert_eq!(proposal.deadline, 1_000);
+        assert!(proposal.settled);
+        assert_eq!(proposal.bump, 253);
+        assert_eq!(unrecorded_spending(&proposal), 0);
+
+        let err = upgrade_proposal(&stored(Proposal::DISCRIMINATOR, &proposal))
+            .err()
+            .unwrap();
+        assert_eq!(err, error!(PayraError::AlreadyMigrated));
+    }
+
+    #[test]
+    fn counts_the_spendings_paid_before_versioning() {
+        let creator = Pubkey::new_unique();
+        let v0 = |settled, cancelled| ProposalV0 {
+            proposal_id: 1,
+            title: "hotel".to_string(),
+            proposal_type: ProposalTypeV0::Spending,
+            amount: 400,
+            spendings: vec![SpendingShare {
+                wallet: creator,
+                percentage: 100,
+            }],
+            yes_votes: vec![creator],
+            no_votes: Vec::new(),
+            creator,
+            deadline: 1_000,
+            settled,
+            cancelled,
+            bump: 253,
+        };
+        let spent = |proposal| {
+            let data = stored_v0(Proposal::DISCRIMINATOR, &proposal, ProposalV0::INIT_SPACE);
+            unrecorded_spending(&upgrade_proposal(&data).unwrap())
+        };
+
+        assert_eq!(spent(v0(true, false)), 400);
+        // still voting, or rejected and never paid
+        assert_eq!(spent(v0(false, false)), 0);
+        assert_eq!(spent(v0(true, true)), 0);
+    }
+
+    #[test]
+    fn upgrades_participant_v0() {
+        let v0 = ParticipantV0 {
+            event: Pubkey::new_unique(),
+            event_id: 9,
+            wallet: Pubkey::new_unique(),
+            contributed: 300,
+            spent: 120,
+            refunded: false,
+            bump: 251,
+            net_owed: 180,
+        };
+        let data = stored_v0(Participant::DISCRIMINATOR, &v0, ParticipantV0::INIT_SPACE);
+        let participant = upgrade_participant(&data).unwrap();
+
+        assert_eq!(participant.version, PARTICIPANT_VERSION);
+        assert_eq!(participant.event, v0.event);
+        assert_eq!(participant.event_id, 9);
+        assert_eq!(participant.wallet, v0.wallet);
+        assert_eq!(participant.contributed, 300);
+        assert_eq!(participant.spent, 120);
+        assert_eq!(participant.bump, 251);
+        assert_eq!(participant.net_owed, 180);
+        assert_eq!(participant.periods_paid, 0);
+
+        let err = upgrade_participant(&stored(Participant::DISCRIMINATOR, &participant))
+            .err()
+            .unwrap();
+        assert_eq!(err, error!(PayraError::AlreadyMigrated));
+    }
+}
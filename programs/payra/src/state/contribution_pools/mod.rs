@@ -1,9 +1,15 @@
 pub mod event;
 pub mod event_counter;
+pub mod invite;
+pub mod legacy;
 pub mod participant;
 pub mod proposal;
+pub mod session;
 
 pub use event::*;
 pub use event_counter::*;
+pub use invite::*;
+pub use legacy::*;
 pub use participant::*;
 pub use proposal::*;
+pub use session::*;
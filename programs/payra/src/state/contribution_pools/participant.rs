@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::PayraError, logic::net_owed};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Participant {
+    pub version: u8,
     pub event: Pubkey,
     pub event_id: u64,
     pub wallet: Pubkey,
@@ -11,4 +14,58 @@ pub struct Participant {
     pub refunded: bool,
     pub bump: u8,
     pub net_owed: i64,
+    // dues periods paid, oldest first: periods 0..periods_paid are settled
+    pub periods_paid: u32,
+}
+
+impl Participant {
+    // what settle_event will record as net_owed
+    pub fn projected_net_owed(&self) -> Result<i64> {
+        net_owed(self.contributed, self.spent).ok_or(PayraError::MathOverflow.into())
+    }
+
+    // Reads a participant account passed in remaining accounts. The address is
+    // checked against the stored bump, one create_program_address instead of
+    // a find_program_address search per wallet.
+    pub fn load_checked(
+        acc_info: &AccountInfo,
+        event: &Pubkey,
+        event_id: u64,
+        program_id: &Pubkey,
+    ) -> Result<Self> {
+        require_keys_eq!(
+            *acc_info.owner,
+            *program_id,
+            PayraError::InvalidParticipantAccounts
+        );
+        let participant = Self::try_deserialize(&mut &acc_info.data.borrow()[..])?;
+        require_keys_eq!(
+            participant.event,
+            *event,
+            PayraError::InvalidParticipantEvent
+        );
+
+        let address = Pubkey::create_program_address(
+            &[
+                b"participant",
+                event_id.to_le_bytes().as_ref(),
+                participant.wallet.as_ref(),
+                &[participant.bump],
+            ],
+            program_id,
+        )
+        .map_err(|_| PayraError::InvalidParticipantAccounts)?;
+        require_keys_eq!(
+            acc_info.key(),
+            address,
+            PayraError::InvalidParticipantAccounts
+        );
+
+        Ok(participant)
+    }
+
+    // writes the participant back, the account must be passed as writable
+    pub fn store(&self, acc_info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut acc_info.data.borrow_mut()[..])
+    }
 }
@@ -1,10 +1,15 @@
 use anchor_lang::prelude::*;
 
-use crate::MAX_PARTICIPANTS;
+use crate::{
+    error::PayraError,
+    logic::{split_share, Tally},
+    MAX_MILESTONES, MAX_PARTICIPANTS,
+};
 
 #[account]
 #[derive(InitSpace)]
 pub struct Proposal {
+    pub version: u8,
     pub proposal_id: u16,
     #[max_len(32)]
     pub title: String,
@@ -14,15 +19,24 @@ pub struct Proposal {
     pub amount: u64,
     #[max_len(MAX_PARTICIPANTS)]
     pub spendings: Vec<SpendingShare>,
+    // tranches of a milestone proposal, adding up to `amount`
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<Milestone>,
 
     #[max_len(MAX_PARTICIPANTS)]
     pub yes_votes: Vec<Pubkey>,
     #[max_len(MAX_PARTICIPANTS)]
     pub no_votes: Vec<Pubkey>,
+    // approvals of the next milestone, cleared once it is released
+    #[max_len(MAX_PARTICIPANTS)]
+    pub milestone_approvals: Vec<Pubkey>,
     pub creator: Pubkey,
     pub deadline: i64,
     pub settled: bool,
     pub cancelled: bool,
+    // fractional vault of the NFT a settled spending paid for, an NFT per
+    // spending
+    pub fractional_vault: Option<Pubkey>,
     pub bump: u8,
 }
 
@@ -33,8 +47,106 @@ pub struct SpendingShare {
     pub percentage: u8,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Milestone {
+    pub amount: u64,
+    pub released: bool,
+}
+
+// what releases the next tranche of an approved milestone proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MilestoneRelease {
+    // a majority of the wallets sharing the expense
+    Approval,
+    // the event creator attesting the milestone was delivered
+    Attestation,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub enum ProposalType {
     Spending,
     EventSettlement,
+    // governance proposals, applied to the event once approved
+    ExtendDeadline { new_deadline: i64 },
+    ChangeTarget { new_target_amount: u64 },
+    ChangeWithdrawAccount { new_withdraw_token_account: Pubkey },
+    // spending paid out in tranches once approved
+    Milestones { release: MilestoneRelease },
+    // lowest buyout price of an NFT fractionalized by the event
+    ReservePrice { vault: Pubkey, price: u64 },
+}
+
+impl ProposalType {
+    pub fn is_governance(&self) -> bool {
+        !matches!(
+            self,
+            ProposalType::Spending | ProposalType::EventSettlement | ProposalType::Milestones { .. }
+        )
+    }
+
+    // voted by the wallets sharing the expense
+    pub fn is_spending(&self) -> bool {
+        matches!(self, ProposalType::Spending | ProposalType::Milestones { .. })
+    }
+}
+
+impl Proposal {
+    pub fn tally(&self, eligible: usize) -> Tally {
+        Tally {
+            yes: self.yes_votes.len(),
+            no: self.no_votes.len(),
+            eligible,
+        }
+    }
+
+    // amount charged to the wallet of the spending share at `index`
+    pub fn spending_share(&self, index: usize) -> Result<u64> {
+        self.share_of(self.amount, index)
+    }
+
+    // tranches are split on their own, the rounding remainder of each one
+    // goes to the first shares like for a whole spending
+    pub fn share_of(&self, amount: u64, index: usize) -> Result<u64> {
+        let percentages: Vec<u8> = self.spendings.iter().map(|s| s.percentage).collect();
+        split_share(amount, &percentages, index).ok_or(PayraError::MathOverflow.into())
+    }
+
+    // release mode and index of the next tranche of an approved milestone
+    // proposal that is still paying out
+    pub fn pending_milestone(&self) -> Result<(MilestoneRelease, usize)> {
+        let ProposalType::Milestones { release } = self.proposal_type else {
+            return err!(PayraError::InvalidProposalType);
+        };
+        require!(
+            self.settled && !self.cancelled,
+            PayraError::MilestonesNotApproved
+        );
+        let next = self.next_milestone().ok_or(PayraError::NoPendingMilestone)?;
+        Ok((release, next))
+    }
+
+    // first milestone not released yet
+    pub fn next_milestone(&self) -> Option<usize> {
+        self.milestones.iter().position(|m| !m.released)
+    }
+
+    pub fn released_amount(&self) -> u64 {
+        self.milestones
+            .iter()
+            .filter(|m| m.released)
+            .map(|m| m.amount)
+            .sum()
+    }
+
+    // amount charged so far to the wallet of the spending share at `index`
+    pub fn released_share(&self, index: usize) -> Result<u64> {
+        self.milestones
+            .iter()
+            .filter(|m| m.released)
+            .try_fold(0u64, |total, m| {
+                total
+                    .checked_add(self.share_of(m.amount, index)?)
+                    .ok_or(PayraError::MathOverflow.into())
+            })
+    }
 }
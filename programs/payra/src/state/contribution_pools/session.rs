@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+// Ephemeral key a member lets vote for them on one event until it expires,
+// so voting doesn't need their wallet every time. One per member and event,
+// creating a new one replaces it.
+#[account]
+#[derive(InitSpace)]
+pub struct Session {
+    pub version: u8,
+    pub event: Pubkey,
+    pub member: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    // paid the account rent, refunded when the session is revoked
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
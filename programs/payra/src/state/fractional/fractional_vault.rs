@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_CONTRIBUTORS;
+
+// NFT held in escrow for an event, split into fungible share tokens. The
+// escrow and the buyout proceeds are associated token accounts of this
+// account, the share mint is its PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct FractionalVault {
+    pub version: u8,
+    pub event: Pubkey,
+    // event mint, buyouts are paid in it
+    pub mint: Pubkey,
+    pub nft_mint: Pubkey,
+    pub share_mint: Pubkey,
+    // settled spending proposal that paid for the NFT
+    pub proposal: Pubkey,
+    // one share per base unit contributed to the event
+    pub total_shares: u64,
+    // shares burned against the buyout proceeds
+    pub redeemed_shares: u64,
+    // lowest price a buyout is accepted at, in the event mint. Set by a
+    // ReservePrice governance vote, no buyout is accepted before
+    pub reserve_price: u64,
+    // participants who claimed their shares, only contributors have any
+    #[max_len(MAX_CONTRIBUTORS)]
+    pub claimed: Vec<Pubkey>,
+    pub status: FractionalStatus,
+    pub bump: u8,
+    pub share_mint_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum FractionalStatus {
+    // NFT in escrow
+    Active,
+    // bought for `price`, share holders redeem the proceeds
+    BoughtOut { buyer: Pubkey, price: u64 },
+    // every share was burned by `holder` against the NFT
+    Redeemed { holder: Pubkey },
+}
+
+impl FractionalVault {
+    // shares still entitled to a part of the buyout proceeds, claimed or not
+    pub fn outstanding_shares(&self) -> u64 {
+        self.total_shares.saturating_sub(self.redeemed_shares)
+    }
+}
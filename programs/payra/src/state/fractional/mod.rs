@@ -0,0 +1,3 @@
+pub mod fractional_vault;
+
+pub use fractional_vault::*;
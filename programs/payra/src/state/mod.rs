@@ -1,3 +1,5 @@
 pub mod contribution_pools;
+pub mod fractional;
 
-pub use contribution_pools::*;
\ No newline at end of file
+pub use contribution_pools::*;
+pub use fractional::*;
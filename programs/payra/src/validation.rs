@@ -0,0 +1,211 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+
+use crate::{
+    error::PayraError,
+    logic::{dues_periods, ed25519_signed_message, equal_share, is_full_split},
+    Event, MemberRole, OverfundingMode, Participant, SpendingShare, MAX_CONTRIBUTORS,
+    MAX_MILESTONES, MAX_NAME_LEN, MAX_PARTICIPANTS, MAX_TITLE_LEN,
+};
+
+// Input checks shared by the instruction handlers. Each one fails with a
+// dedicated PayraError so clients can tell exactly which argument was wrong.
+
+pub fn validate_amount(amount: u64) -> Result<()> {
+    require!(amount > 0, PayraError::ZeroAmount);
+    Ok(())
+}
+
+pub fn validate_name(name: &str) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LEN, PayraError::NameTooLong);
+    Ok(())
+}
+
+pub fn validate_title(title: &str) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, PayraError::TitleTooLong);
+    Ok(())
+}
+
+// every share must be a distinct member able to contribute, carry a non-zero
+// percentage and all of them together must add up to 100
+pub fn validate_spendings(event: &Event, spendings: &[SpendingShare]) -> Result<()> {
+    require!(!spendings.is_empty(), PayraError::NoParticipants);
+    require!(
+        spendings.len() <= MAX_PARTICIPANTS as usize,
+        PayraError::TooManyShares
+    );
+
+    for (i, share) in spendings.iter().enumerate() {
+        require!(share.percentage > 0, PayraError::ZeroPercentage);
+        require!(
+            !spendings[..i].iter().any(|s| s.wallet == share.wallet),
+            PayraError::DuplicateShareWallet
+        );
+        require!(
+            event.has_role(&share.wallet, MemberRole::Member),
+            PayraError::ShareWalletNotWhitelisted
+        );
+    }
+
+    require!(
+        is_full_split(spendings.iter().map(|s| s.percentage)),
+        PayraError::InvalidPercentage
+    );
+    Ok(())
+}
+
+// tranches of a milestone proposal, returns their total
+pub fn validate_milestones(amounts: &[u64]) -> Result<u64> {
+    require!(
+        !amounts.is_empty() && amounts.len() <= MAX_MILESTONES as usize,
+        PayraError::InvalidMilestones
+    );
+    require!(amounts.iter().all(|a| *a > 0), PayraError::InvalidMilestones);
+    amounts
+        .iter()
+        .try_fold(0u64, |total, a| total.checked_add(*a))
+        .ok_or(PayraError::MathOverflow.into())
+}
+
+// what a contribution accepted by `validate_contribution` records
+pub struct Contribution {
+    // trimmed to what is left to fund under OverfundingMode::PartialFill
+    pub amount: u64,
+    // totals of the participant once it is recorded
+    pub contributed: u64,
+    pub periods_paid: u32,
+}
+
+// every check of `contribute`, also run off-chain by clients building the
+// transaction. `participant` is the wallet's account once it has contributed.
+pub fn validate_contribution(
+    event: &Event,
+    wallet: &Pubkey,
+    participant: Option<&Participant>,
+    amount: u64,
+    now: i64,
+) -> Result<Contribution> {
+    validate_amount(amount)?;
+    require!(now < event.deadline, PayraError::DeadlineAlreadyReached);
+    // observers and wallets off the whitelist of a closed event can't
+    // contribute
+    require!(event.can_contribute(wallet), PayraError::NotWhitelisted);
+    require!(!event.is_cancelled, PayraError::EventCancelled);
+
+    let rules = &event.contribution_rules;
+    require!(
+        amount >= rules.min_contribution,
+        PayraError::ContributionTooSmall
+    );
+
+    // past the funding cap contributions are rejected or trimmed
+    let room = event.funding_cap().saturating_sub(event.total_contributed);
+    let amount = match rules.overfunding {
+        OverfundingMode::Allow => amount,
+        OverfundingMode::Reject => {
+            require!(amount <= room, PayraError::FundingCapExceeded);
+            amount
+        }
+        OverfundingMode::PartialFill => {
+            require!(room > 0, PayraError::FundingCapExceeded);
+            amount.min(room)
+        }
+    };
+    let contributed = participant
+        .map_or(0, |p| p.contributed)
+        .checked_add(amount)
+        .ok_or(PayraError::ContributionOverflow)?;
+    require!(
+        rules.max_per_participant == 0 || contributed <= rules.max_per_participant,
+        PayraError::ContributionTooLarge
+    );
+    // the share may be paid in installments, the voters it is split
+    // between are frozen by the first contribution
+    if rules.equal_share {
+        let share = equal_share(event.target_amount, event.eligible_voters())
+            .ok_or(PayraError::MathOverflow)?;
+        require!(contributed <= share, PayraError::InvalidEqualShare);
+    }
+
+    // dues are paid a whole number of periods at a time, at most up to
+    // the last period starting before the deadline
+    let periods_paid = participant.map_or(0, |p| p.periods_paid);
+    let periods_paid = match event.dues {
+        Some(dues) => {
+            let periods = dues_periods(amount, dues.amount).ok_or(PayraError::InvalidDuesAmount)?;
+            let paid = periods_paid
+                .checked_add(periods)
+                .ok_or(PayraError::MathOverflow)?;
+            let payable = dues
+                .periods_until(event.deadline)
+                .ok_or(PayraError::MathOverflow)?;
+            require!(paid <= payable, PayraError::DuesBeyondDeadline);
+            paid
+        }
+        None => periods_paid,
+    };
+
+    // every contributor is settled with settle_event, so their number is
+    // bounded
+    require!(
+        event.contributors.contains(wallet) || event.contributors.len() < MAX_CONTRIBUTORS as usize,
+        PayraError::TooManyContributors
+    );
+
+    Ok(Contribution {
+        amount,
+        contributed,
+        periods_paid,
+    })
+}
+
+// wallets added to the whitelist in one batch
+pub fn validate_new_members(event: &Event, wallets: &[Pubkey]) -> Result<()> {
+    require!(!event.voters_frozen(), PayraError::EqualShareVotersFrozen);
+    require!(
+        event.whitelist.len() + wallets.len() <= MAX_PARTICIPANTS as usize,
+        PayraError::WhitelistFull
+    );
+
+    for (i, wallet) in wallets.iter().enumerate() {
+        require_keys_neq!(*wallet, Pubkey::default(), PayraError::InvalidWallet);
+        // the creator is already an implicit member
+        require!(
+            *wallet != event.creator
+                && !wallets[..i].contains(wallet)
+                && !event.whitelist.contains(wallet),
+            PayraError::DuplicateWallet
+        );
+    }
+    Ok(())
+}
+
+// an invite voucher `message` must be signed by `signer` in an ed25519
+// program instruction placed right before the current one
+pub fn validate_invite_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, PayraError::InvalidInviteSignature);
+    let verify = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        verify.program_id,
+        ed25519_program::ID,
+        PayraError::InvalidInviteSignature
+    );
+
+    let (public_key, signed) =
+        ed25519_signed_message(&verify.data).ok_or(PayraError::InvalidInviteSignature)?;
+    require!(
+        public_key == signer.as_ref() && signed == message,
+        PayraError::InvalidInviteSignature
+    );
+    Ok(())
+}
@@ -0,0 +1,311 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("contribution rules", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  async function contribute(amount: number) {
+    await program.methods
+      .contribute(new anchor.BN(amount * 10 ** 6))
+      .accountsStrict({
+        contributor: poolCreator.publicKey,
+        contributorAta: poolCreatorUsdcATA.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
+        mint: usdcMint,
+        participant: participantPDA,
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  const poolCreator = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let participantPDA: PublicKey;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreatorUsdcATA.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      1000 * 10 ** 6 // amount in base units
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    const eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+    [participantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, poolCreator.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent({
+        name: "Concert Tickets",
+        deadline: new anchor.BN(now + 60),
+        targetAmount: new anchor.BN(100 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+  });
+
+  it("sets a minimum contribution and a partially filled hard cap", async () => {
+    await program.methods
+      .setContributionRules({
+        minContribution: new anchor.BN(10 * 10 ** 6),
+        maxPerParticipant: new anchor.BN(0),
+        equalShare: false,
+        overfunding: { partialFill: {} },
+        stretchGoal: new anchor.BN(0),
+      })
+      .accountsStrict({
+        admin: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(
+      event.contributionRules.minContribution.toNumber(),
+      10 * 10 ** 6
+    );
+  });
+
+  it("rejects contributions below the minimum", async () => {
+    await expectError(contribute(5), "ContributionTooSmall");
+  });
+
+  it("only accepts what is left up to the target", async () => {
+    await contribute(80);
+    await contribute(50);
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.totalContributed.toNumber(), 100 * 10 ** 6);
+
+    const vaultBalance = await provider.connection.getTokenAccountBalance(
+      eventVault.address
+    );
+    assert.equal(vaultBalance.value.uiAmount, 100);
+  });
+
+  it("rejects contributions once the cap is reached", async () => {
+    await expectError(contribute(10), "FundingCapExceeded");
+  });
+
+  it("rejects zero amount contributions", async () => {
+    await expectError(contribute(0), "ZeroAmount");
+  });
+
+  describe("equal share", () => {
+    const member = anchor.web3.Keypair.generate();
+    let sharePDA: PublicKey;
+    let shareVault: Account;
+    let shareParticipantPDA: PublicKey;
+
+    async function payShare(amount: number) {
+      await program.methods
+        .contribute(new anchor.BN(amount * 10 ** 6))
+        .accountsStrict({
+          contributor: poolCreator.publicKey,
+          contributorAta: poolCreatorUsdcATA.address,
+          event: sharePDA,
+          eventVault: shareVault.address,
+          payer: poolCreator.publicKey,
+          mint: usdcMint,
+          participant: shareParticipantPDA,
+          associatedTokenProgram,
+          systemProgram,
+          tokenProgram,
+        })
+        .signers([poolCreator])
+        .rpc();
+    }
+
+    before(async () => {
+      const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+      const eventId = counter.count.toArrayLike(Buffer, "le", 8);
+      [sharePDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId],
+        program.programId
+      );
+      [shareParticipantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("participant"), eventId, poolCreator.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent({
+          name: "Cabin Rental",
+          deadline: new anchor.BN(now + 60),
+          targetAmount: new anchor.BN(100 * 10 ** 6),
+        })
+        .signers([poolCreator])
+        .accounts({
+          creator: poolCreator.publicKey,
+          mint: usdcMint,
+          withdrawTokenAccount: poolCreatorUsdcATA.address,
+        })
+        .rpc();
+
+      // the creator and one member split the target, 50 each
+      await program.methods
+        .whitelist([member.publicKey])
+        .accountsStrict({
+          creator: poolCreator.publicKey,
+          event: sharePDA,
+        })
+        .signers([poolCreator])
+        .rpc();
+
+      await program.methods
+        .setContributionRules({
+          minContribution: new anchor.BN(0),
+          maxPerParticipant: new anchor.BN(0),
+          equalShare: true,
+          overfunding: { allow: {} },
+          stretchGoal: new anchor.BN(0),
+        })
+        .accountsStrict({
+          admin: poolCreator.publicKey,
+          event: sharePDA,
+        })
+        .signers([poolCreator])
+        .rpc();
+
+      shareVault = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        sharePDA, // owner
+        true
+      );
+    });
+
+    it("accepts the share in installments", async () => {
+      await payShare(20);
+      await payShare(30);
+
+      const participant = await program.account.participant.fetch(
+        shareParticipantPDA
+      );
+      assert.equal(participant.contributed.toNumber(), 50 * 10 ** 6);
+    });
+
+    it("rejects paying past the share", async () => {
+      await expectError(payShare(1), "InvalidEqualShare");
+    });
+
+    it("freezes the voters once someone paid", async () => {
+      const latecomer = anchor.web3.Keypair.generate();
+      await expectError(
+        program.methods
+          .whitelist([latecomer.publicKey])
+          .accountsStrict({
+            creator: poolCreator.publicKey,
+            event: sharePDA,
+          })
+          .signers([poolCreator])
+          .rpc(),
+        "EqualShareVotersFrozen"
+      );
+    });
+  });
+});
@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("recurring dues", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function participantPDA(wallet: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  async function contribute(amount: number) {
+    await program.methods
+      .contribute(new anchor.BN(amount * 10 ** 6))
+      .accountsStrict({
+        contributor: poolCreator.publicKey,
+        contributorAta: poolCreatorUsdcATA.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(poolCreator.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => shared apartment fund creator, pays dues
+  // 2. Pool User => flatmate, never pays
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  // three periods of 20 seconds fit before the deadline
+  const PERIOD = 20;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreatorUsdcATA.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      1000 * 10 ** 6 // amount in base units
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent({
+        name: "Apartment",
+        deadline: new anchor.BN(now + 3 * PERIOD),
+        targetAmount: new anchor.BN(600 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+  });
+
+  it("only lets admins set dues", async () => {
+    await expectError(
+      program.methods
+        .setDues(new anchor.BN(100 * 10 ** 6), new anchor.BN(PERIOD))
+        .accountsStrict({ admin: poolUser.publicKey, event: eventPDA })
+        .signers([poolUser])
+        .rpc(),
+      "Unauthorised"
+    );
+  });
+
+  it("sets monthly style dues", async () => {
+    await program.methods
+      .setDues(new anchor.BN(100 * 10 ** 6), new anchor.BN(PERIOD))
+      .accountsStrict({ admin: poolCreator.publicKey, event: eventPDA })
+      .signers([poolCreator])
+      .rpc();
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.dues.amount.toNumber(), 100 * 10 ** 6);
+    assert.equal(event.dues.period.toNumber(), PERIOD);
+  });
+
+  it("rejects contributions that don't pay whole periods", async () => {
+    await expectError(contribute(150), "InvalidDuesAmount");
+  });
+
+  it("records the periods paid", async () => {
+    await contribute(200);
+
+    const participant = await program.account.participant.fetch(
+      participantPDA(poolCreator.publicKey)
+    );
+    assert.equal(participant.periodsPaid, 2);
+    assert.equal(participant.contributed.toNumber(), 200 * 10 ** 6);
+  });
+
+  it("rejects dues past the deadline", async () => {
+    await expectError(contribute(200), "DuesBeyondDeadline");
+  });
+
+  it("reports who is behind", async () => {
+    const status = await program.methods
+      .getDuesStatus()
+      .accountsStrict({ event: eventPDA })
+      .remainingAccounts([
+        {
+          pubkey: participantPDA(poolUser.publicKey),
+          isSigner: false,
+          isWritable: false,
+        },
+        {
+          pubkey: participantPDA(poolCreator.publicKey),
+          isSigner: false,
+          isWritable: false,
+        },
+      ])
+      .view();
+
+    assert.equal(status.periodsDue, 1);
+    // voters order: members first, the creator last
+    const [user, creator] = status.members;
+    assert.ok(user.wallet.equals(poolUser.publicKey));
+    assert.equal(user.periodsPaid, 0);
+    assert.equal(user.periodsBehind, 1);
+    assert.equal(user.amountBehind.toNumber(), 100 * 10 ** 6);
+    assert.equal(creator.periodsPaid, 2);
+    assert.equal(creator.periodsBehind, 0);
+  });
+});
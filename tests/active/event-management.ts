@@ -257,6 +257,7 @@ describe("event management", () => {
         contributorAta: poolUserUsdcATA.address,
         event: event2PDA,
         eventVault: event2Vault.address,
+        payer: poolUser.publicKey,
         mint: usdcMint,
         participant: userParticipantPDA,
         associatedTokenProgram,
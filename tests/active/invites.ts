@@ -0,0 +1,199 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import {
+  Ed25519Program,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { createMint, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+
+describe("invite vouchers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function voucher(inviteId: number, maxUses: number, expiresIn: number) {
+    return {
+      event: eventPDA,
+      issuer: poolCreator.publicKey,
+      inviteId: new anchor.BN(inviteId),
+      role: { member: {} },
+      maxUses,
+      expiresAt: new anchor.BN(Math.floor(Date.now() / 1000) + expiresIn),
+    };
+  }
+
+  // what the issuer signs off-chain
+  function sign(invite: ReturnType<typeof voucher>, signer = poolCreator) {
+    const message = Buffer.concat([
+      Buffer.from("payra invite:"),
+      program.coder.types.encode("inviteVoucher", invite),
+    ]);
+    return Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message,
+    });
+  }
+
+  function invitePDA(inviteId: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("invite"),
+        eventPDA.toBuffer(),
+        poolCreator.publicKey.toBuffer(),
+        new anchor.BN(inviteId).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+  }
+
+  function join(
+    member: anchor.web3.Keypair,
+    invite: ReturnType<typeof voucher>,
+    verify: anchor.web3.TransactionInstruction | null
+  ) {
+    return program.methods
+      .joinEvent(invite)
+      .accountsStrict({
+        member: member.publicKey,
+        event: eventPDA,
+        payer: member.publicKey,
+        invite: invitePDA(invite.inviteId.toNumber()),
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram,
+      })
+      .preInstructions(verify ? [verify] : [])
+      .signers([member])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => shares the invite link
+  // 2. Friends => join with the link, no whitelisting by the creator
+  const poolCreator = anchor.web3.Keypair.generate();
+  const friends = [0, 1, 2].map(() => anchor.web3.Keypair.generate());
+
+  let eventPDA: PublicKey;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    for (const wallet of [poolCreator, ...friends]) {
+      await airdropBalance(wallet.publicKey);
+    }
+
+    const usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+    const poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    const eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    await program.methods
+      .createEvent({
+        name: "Ski trip",
+        deadline: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        targetAmount: new anchor.BN(600 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+  });
+
+  it("whitelists whoever joins with a signed voucher", async () => {
+    const invite = voucher(1, 2, 600);
+    await join(friends[0], invite, sign(invite));
+    await join(friends[1], invite, sign(invite));
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.deepEqual(
+      event.whitelist.map((w) => w.toBase58()),
+      [friends[0].publicKey.toBase58(), friends[1].publicKey.toBase58()]
+    );
+    const usage = await program.account.invite.fetch(invitePDA(1));
+    assert.equal(usage.uses, 2);
+  });
+
+  it("stops once the voucher is used up", async () => {
+    const invite = voucher(1, 2, 600);
+    await expectError(join(friends[2], invite, sign(invite)), "InviteUsedUp");
+  });
+
+  it("rejects expired vouchers", async () => {
+    const invite = voucher(2, 5, -60);
+    await expectError(join(friends[2], invite, sign(invite)), "InviteExpired");
+  });
+
+  it("rejects vouchers not signed by an admin", async () => {
+    const invite = voucher(3, 5, 600);
+    await expectError(join(friends[2], invite, null), "InvalidInviteSignature");
+    await expectError(
+      join(friends[2], invite, sign(invite, friends[0])),
+      "InvalidInviteSignature"
+    );
+
+    // signed for a member, redeemed as a treasurer
+    const tampered = { ...invite, role: { treasurer: {} } };
+    await expectError(join(friends[2], tampered, sign(invite)), "InvalidInviteSignature");
+  });
+});
@@ -0,0 +1,325 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("open events", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function participantPDA(wallet: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function proposalPDA(proposalId: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(proposalId).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+  }
+
+  async function contribute(contributor: anchor.web3.Keypair, ata: Account, amount: number) {
+    await program.methods
+      .contribute(new anchor.BN(amount * 10 ** 6))
+      .accountsStrict({
+        contributor: contributor.publicKey,
+        contributorAta: ata.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: contributor.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(contributor.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([contributor])
+      .rpc();
+  }
+
+  function settleEvent(wallets: PublicKey[]) {
+    return program.methods
+      .settleEvent()
+      .accountsStrict({
+        event: eventPDA,
+        proposal: proposalPDA(0),
+        signer: poolCreator.publicKey,
+      })
+      .remainingAccounts(
+        wallets.map((wallet) => ({
+          pubkey: participantPDA(wallet),
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([poolCreator])
+      .rpc();
+  }
+
+  function setAccess(access: object) {
+    return program.methods
+      .setEventAccess(access as any)
+      .accountsStrict({
+        admin: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => opens the fundraiser to anyone
+  // 2. Backer => gives enough to get a vote
+  // 3. Donor => gives a little, no vote
+  const poolCreator = anchor.web3.Keypair.generate();
+  const backer = anchor.web3.Keypair.generate();
+  const donor = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let backerUsdcATA: Account;
+  let donorUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    for (const wallet of [poolCreator, backer, donor]) {
+      await airdropBalance(wallet.publicKey);
+    }
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    const poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+    backerUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      backer.publicKey // owner
+    );
+    donorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      donor.publicKey // owner
+    );
+
+    for (const ata of [backerUsdcATA, donorUsdcATA]) {
+      await mintTo(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        ata.address, // ATA address
+        provider.wallet.payer.publicKey, // mint authority
+        100 * 10 ** 6 // amount in base units
+      );
+    }
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    await program.methods
+      .createEvent({
+        name: "Community garden",
+        deadline: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        targetAmount: new anchor.BN(500 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+  });
+
+  it("rejects outside contributors while whitelisted", async () => {
+    await expectError(contribute(donor, donorUsdcATA, 5), "NotWhitelisted");
+  });
+
+  it("opens the event to anyone", async () => {
+    await setAccess({ open: { votingThreshold: new anchor.BN(20 * 10 ** 6) } });
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.access.open.votingThreshold.toNumber(), 20 * 10 ** 6);
+  });
+
+  it("accepts anyone and gives a vote past the threshold", async () => {
+    await contribute(donor, donorUsdcATA, 5);
+    await contribute(backer, backerUsdcATA, 15);
+
+    let event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.totalContributed.toNumber(), 20 * 10 ** 6);
+    assert.isEmpty(event.whitelist);
+
+    // the threshold counts everything the backer gave
+    await contribute(backer, backerUsdcATA, 10);
+    event = await program.account.event.fetch(eventPDA);
+    assert.deepEqual(
+      event.whitelist.map((w) => w.toBase58()),
+      [backer.publicKey.toBase58()]
+    );
+    assert.deepEqual(event.roles[0], { member: {} });
+  });
+
+  it("keeps the access fixed once contributions started", async () => {
+    await expectError(setAccess({ whitelist: {} }), "ContributionsAlreadyStarted");
+  });
+
+  it("records every contributor, donors included", async () => {
+    const event = await program.account.event.fetch(eventPDA);
+    assert.deepEqual(
+      event.contributors.map((w) => w.toBase58()),
+      [donor.publicKey.toBase58(), backer.publicKey.toBase58()]
+    );
+  });
+
+  it("settles the donors with the voters", async () => {
+    await program.methods
+      .createSettleProposal(new anchor.BN(Math.floor(Date.now() / 1000) + 3600))
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        mint: usdcMint,
+        proposal: proposalPDA(0),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+    for (const voter of [poolCreator, backer]) {
+      await program.methods
+        .vote(true)
+        .accountsStrict({
+          event: eventPDA,
+          proposal: proposalPDA(0),
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: voter.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    // the donor has no vote but still has to be settled
+    await expectError(settleEvent([backer.publicKey]), "InvalidParticipantAccounts");
+    await settleEvent([backer.publicKey, donor.publicKey]);
+
+    const donorAccount = await program.account.participant.fetch(
+      participantPDA(donor.publicKey)
+    );
+    assert.equal(donorAccount.netOwed.toNumber(), 5 * 10 ** 6);
+    const backerAccount = await program.account.participant.fetch(
+      participantPDA(backer.publicKey)
+    );
+    assert.equal(backerAccount.netOwed.toNumber(), 25 * 10 ** 6);
+  });
+
+  it("accepts reaching the threshold without a vote once the whitelist is full", async () => {
+    const members = Array.from({ length: 9 }, () => anchor.web3.Keypair.generate().publicKey);
+    await program.methods
+      .whitelist(members)
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await contribute(donor, donorUsdcATA, 15);
+    // no seat left, the donor stays without a vote
+    const event = await program.account.event.fetch(eventPDA);
+    assert.notInclude(
+      event.whitelist.map((w) => w.toBase58()),
+      donor.publicKey.toBase58()
+    );
+    assert.include(
+      event.contributors.map((w) => w.toBase58()),
+      donor.publicKey.toBase58()
+    );
+  });
+});
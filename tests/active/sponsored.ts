@@ -0,0 +1,248 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import {
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("sponsored transactions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => sponsors the fees and rent of its friends
+  // 2. Friend => holds tokens but no SOL, only signs the transfer
+  const poolCreator = anchor.web3.Keypair.generate();
+  const friend = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let friendUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let participantPDA: PublicKey;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    const poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+    friendUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      friend.publicKey // owner
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      friendUsdcATA.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      100 * 10 ** 6 // amount in base units
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    const eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+    [participantPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, friend.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createEvent({
+        name: "Birthday gift",
+        deadline: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        targetAmount: new anchor.BN(200 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([friend.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+  });
+
+  it("lets a sponsor pay the fees and rent of a contribution", async () => {
+    const sponsorBalance = await provider.connection.getBalance(poolCreator.publicKey);
+
+    const tx = await program.methods
+      .contribute(new anchor.BN(40 * 10 ** 6))
+      .accountsStrict({
+        contributor: friend.publicKey,
+        contributorAta: friendUsdcATA.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
+        mint: usdcMint,
+        participant: participantPDA,
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .transaction();
+    // the first signer pays the fees
+    await sendAndConfirmTransaction(provider.connection, tx, [poolCreator, friend]);
+
+    const participant = await program.account.participant.fetch(participantPDA);
+    assert.equal(participant.contributed.toNumber(), 40 * 10 ** 6);
+    assert.equal(await provider.connection.getBalance(friend.publicKey), 0);
+    assert.isBelow(
+      await provider.connection.getBalance(poolCreator.publicKey),
+      sponsorBalance
+    );
+  });
+
+  it("lets a sponsor pay for the accounts a member creates", async () => {
+    const [sessionPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), eventPDA.toBuffer(), friend.publicKey.toBuffer()],
+      program.programId
+    );
+    const [proposalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    );
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 600);
+
+    const tx = new Transaction().add(
+      await program.methods
+        .createSession(anchor.web3.Keypair.generate().publicKey, expiresAt)
+        .accountsStrict({
+          member: friend.publicKey,
+          event: eventPDA,
+          payer: poolCreator.publicKey,
+          session: sessionPDA,
+          systemProgram,
+        })
+        .instruction(),
+      await program.methods
+        .createSettleProposal(expiresAt)
+        .accountsStrict({
+          creator: friend.publicKey,
+          event: eventPDA,
+          mint: usdcMint,
+          payer: poolCreator.publicKey,
+          proposal: proposalPDA,
+          associatedTokenProgram,
+          systemProgram,
+          tokenProgram,
+        })
+        .instruction()
+    );
+    await sendAndConfirmTransaction(provider.connection, tx, [poolCreator, friend]);
+
+    const session = await program.account.session.fetch(sessionPDA);
+    assert.equal(session.expiresAt.toNumber(), expiresAt.toNumber());
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.equal(proposal.creator.toBase58(), friend.publicKey.toBase58());
+    assert.equal(await provider.connection.getBalance(friend.publicKey), 0);
+  });
+
+  it("refunds the session rent to the sponsor", async () => {
+    const [sessionPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), eventPDA.toBuffer(), friend.publicKey.toBuffer()],
+      program.programId
+    );
+    const session = await program.account.session.fetch(sessionPDA);
+    assert.equal(session.rentPayer.toBase58(), poolCreator.publicKey.toBase58());
+    const sponsorBalance = await provider.connection.getBalance(poolCreator.publicKey);
+
+    const tx = await program.methods
+      .revokeSession()
+      .accountsStrict({
+        member: friend.publicKey,
+        session: sessionPDA,
+        rentPayer: poolCreator.publicKey,
+      })
+      .transaction();
+    // the sponsor pays the fee, the rent more than covers it
+    await sendAndConfirmTransaction(provider.connection, tx, [poolCreator, friend]);
+
+    assert.isNull(await provider.connection.getAccountInfo(sessionPDA));
+    assert.equal(await provider.connection.getBalance(friend.publicKey), 0);
+    assert.isAbove(
+      await provider.connection.getBalance(poolCreator.publicKey),
+      sponsorBalance
+    );
+  });
+});
@@ -0,0 +1,687 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("fractionalized NFT", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function sleep(ms: number) {
+    return new Promise((resolve) => setTimeout(resolve, ms));
+  }
+
+  function participantPDA(wallet: PublicKey, id: Buffer = eventId) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), id, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function proposalPDA(event: PublicKey, proposalId: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        event.toBuffer(),
+        new anchor.BN(proposalId).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+  }
+
+  // the spending proposal paying the creator back for the NFT, voted yes by
+  // every wallet sharing it and settled
+  async function payForNft(
+    creator: anchor.web3.Keypair,
+    creatorAta: Account,
+    event: PublicKey,
+    id: Buffer,
+    vault: Account,
+    shares: { wallet: anchor.web3.Keypair; percentage: number }[],
+    amount: number
+  ) {
+    const { proposalCount } = await program.account.event.fetch(event);
+    const proposal = proposalPDA(event, proposalCount);
+    await program.methods
+      .createProposal(
+        "Artwork",
+        new anchor.BN(amount * 10 ** 6),
+        shares.map(({ wallet, percentage }) => ({ wallet: wallet.publicKey, percentage })),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accountsStrict({
+        creator: creator.publicKey,
+        payer: creator.publicKey,
+        event,
+        mint: usdcMint,
+        proposal,
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([creator])
+      .rpc();
+
+    for (const { wallet } of shares) {
+      await program.methods
+        .vote(true)
+        .accountsStrict({
+          event,
+          proposal,
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: wallet.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([wallet])
+        .rpc();
+    }
+
+    await program.methods
+      .settleProposal()
+      .accountsStrict({
+        associatedTokenProgram,
+        event,
+        eventVault: vault.address,
+        mint: usdcMint,
+        proposal,
+        signer: creator.publicKey,
+        systemProgram,
+        tokenProgram,
+        withdrawAccount: creatorAta.address,
+      })
+      .remainingAccounts(
+        shares.map(({ wallet }) => ({
+          pubkey: participantPDA(wallet.publicKey, id),
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([creator])
+      .rpc();
+    return proposalCount;
+  }
+
+  function fractionalize(
+    admin: anchor.web3.Keypair,
+    event: PublicKey,
+    proposalId: number,
+    nft: PublicKey
+  ) {
+    const vault = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fractional"), event.toBuffer(), nft.toBuffer()],
+      program.programId
+    )[0];
+    return program.methods
+      .fractionalize()
+      .accountsStrict({
+        admin: admin.publicKey,
+        event,
+        proposal: proposalPDA(event, proposalId),
+        mint: usdcMint,
+        nftMint: nft,
+        adminNftAccount: getAssociatedTokenAddressSync(nft, admin.publicKey),
+        fractionalVault: vault,
+        shareMint: anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("shares"), vault.toBuffer()],
+          program.programId
+        )[0],
+        escrow: getAssociatedTokenAddressSync(nft, vault, true),
+        associatedTokenProgram,
+        tokenProgram,
+        systemProgram,
+      })
+      .signers([admin])
+      .rpc();
+  }
+
+  // governance proposal on the reserve price, voted yes by both members
+  async function voteReservePrice(price: number) {
+    const { proposalCount } = await program.account.event.fetch(eventPDA);
+    const proposal = proposalPDA(eventPDA, proposalCount);
+    await program.methods
+      .createGovernanceProposal(
+        {
+          reservePrice: {
+            vault: fractionalVaultPDA,
+            price: new anchor.BN(price * 10 ** 6),
+          },
+        },
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accountsStrict({
+        creator: poolUser.publicKey,
+        payer: poolUser.publicKey,
+        event: eventPDA,
+        proposal,
+        newWithdrawTokenAccount: null,
+        fractionalVault: fractionalVaultPDA,
+        systemProgram,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    for (const wallet of [poolCreator, poolUser]) {
+      await program.methods
+        .vote(true)
+        .accountsStrict({
+          event: eventPDA,
+          proposal,
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: wallet.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([wallet])
+        .rpc();
+    }
+
+    await program.methods
+      .settleGovernanceProposal()
+      .accountsStrict({
+        signer: poolUser.publicKey,
+        event: eventPDA,
+        proposal,
+        fractionalVault: fractionalVaultPDA,
+      })
+      .signers([poolUser])
+      .rpc();
+  }
+
+  // an NFT minted to the wallet's associated token account
+  async function mintNft(owner: PublicKey) {
+    const nft = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      0 // decimals
+    );
+    const ownerAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      nft, // mint
+      owner // owner
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      nft, // mint
+      ownerAta.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      1 // amount in base units
+    );
+    return nft;
+  }
+
+  async function contribute(wallet: anchor.web3.Keypair, ata: Account, amount: number) {
+    await program.methods
+      .contribute(new anchor.BN(amount * 10 ** 6))
+      .accountsStrict({
+        contributor: wallet.publicKey,
+        contributorAta: ata.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: wallet.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(wallet.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([wallet])
+      .rpc();
+  }
+
+  function claimShares(
+    wallet: anchor.web3.Keypair,
+    event = eventPDA,
+    id = eventId,
+    vault = fractionalVaultPDA,
+    shareMint = shareMintPDA
+  ) {
+    return program.methods
+      .claimShares()
+      .accountsStrict({
+        claimer: wallet.publicKey,
+        event,
+        participant: participantPDA(wallet.publicKey, id),
+        fractionalVault: vault,
+        shareMint,
+        claimerShares: getAssociatedTokenAddressSync(shareMint, wallet.publicKey),
+        associatedTokenProgram,
+        tokenProgram,
+        systemProgram,
+      })
+      .signers([wallet])
+      .rpc();
+  }
+
+  function buyout(price: number) {
+    return program.methods
+      .buyout(new anchor.BN(price * 10 ** 6))
+      .accountsStrict({
+        buyer: buyer.publicKey,
+        event: eventPDA,
+        mint: usdcMint,
+        nftMint,
+        fractionalVault: fractionalVaultPDA,
+        escrow: getAssociatedTokenAddressSync(nftMint, fractionalVaultPDA, true),
+        proceeds: getAssociatedTokenAddressSync(usdcMint, fractionalVaultPDA, true),
+        buyerPayment: buyerUsdcATA.address,
+        buyerNftAccount: getAssociatedTokenAddressSync(nftMint, buyer.publicKey),
+        associatedTokenProgram,
+        tokenProgram,
+        systemProgram,
+      })
+      .signers([buyer])
+      .rpc();
+  }
+
+  async function tokenBalance(address: PublicKey) {
+    const account = await getAccount(provider.connection, address);
+    return Number(account.amount);
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => collector fund creator, holds the NFT bought for the group
+  // 2. Pool User => co-owner who put in a quarter of the funds
+  // 3. Buyer => outsider buying the NFT out of escrow
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+  const buyer = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let nftMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let poolUserUsdcATA: Account;
+  let buyerUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let fractionalVaultPDA: PublicKey;
+  let shareMintPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+    await airdropBalance(poolUser.publicKey);
+    await airdropBalance(buyer.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+    poolUserUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolUser.publicKey // owner
+    );
+    buyerUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      buyer.publicKey // owner
+    );
+
+    for (const ata of [poolCreatorUsdcATA, poolUserUsdcATA, buyerUsdcATA]) {
+      await mintTo(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        ata.address, // ATA address
+        provider.wallet.payer.publicKey, // mint authority
+        1000 * 10 ** 6 // amount in base units
+      );
+    }
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    await program.methods
+      .createEvent({
+        name: "Artwork",
+        deadline: new anchor.BN(Math.floor(Date.now() / 1000) + 4),
+        targetAmount: new anchor.BN(400 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+
+    await contribute(poolCreator, poolCreatorUsdcATA, 300);
+    await contribute(poolUser, poolUserUsdcATA, 100);
+
+    // the NFT the group bought, held by the creator
+    nftMint = await mintNft(poolCreator.publicKey);
+
+    [fractionalVaultPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fractional"), eventPDA.toBuffer(), nftMint.toBuffer()],
+      program.programId
+    );
+    [shareMintPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("shares"), fractionalVaultPDA.toBuffer()],
+      program.programId
+    );
+
+    // contributions have to be closed before fractionalizing
+    await sleep(5000);
+  });
+
+  it("rejects an NFT no settled spending paid for", async () => {
+    // a spending proposal that hasn't been voted yet
+    await program.methods
+      .createProposal(
+        "Artwork",
+        new anchor.BN(400 * 10 ** 6),
+        [{ wallet: poolCreator.publicKey, percentage: 100 }],
+        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+      )
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        mint: usdcMint,
+        proposal: proposalPDA(eventPDA, 0),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await expectError(fractionalize(poolCreator, eventPDA, 0, nftMint), "NftNotPaidByEvent");
+  });
+
+  it("moves the NFT into escrow", async () => {
+    const proposalId = await payForNft(
+      poolCreator,
+      poolCreatorUsdcATA,
+      eventPDA,
+      eventId,
+      eventVault,
+      [
+        { wallet: poolCreator, percentage: 75 },
+        { wallet: poolUser, percentage: 25 },
+      ],
+      400
+    );
+    await fractionalize(poolCreator, eventPDA, proposalId, nftMint);
+
+    const vault = await program.account.fractionalVault.fetch(fractionalVaultPDA);
+    assert.equal(vault.totalShares.toNumber(), 400 * 10 ** 6);
+    assert.equal(vault.proposal.toBase58(), proposalPDA(eventPDA, proposalId).toBase58());
+    assert.equal(
+      await tokenBalance(getAssociatedTokenAddressSync(nftMint, fractionalVaultPDA, true)),
+      1
+    );
+
+    // the spending only paid for one NFT
+    const other = await mintNft(poolCreator.publicKey);
+    await expectError(
+      fractionalize(poolCreator, eventPDA, proposalId, other),
+      "ProposalAlreadyFractionalized"
+    );
+  });
+
+  it("mints shares proportional to the contributions", async () => {
+    await claimShares(poolCreator);
+    await claimShares(poolUser);
+    await expectError(claimShares(poolUser), "SharesAlreadyClaimed");
+
+    assert.equal(
+      await tokenBalance(getAssociatedTokenAddressSync(shareMintPDA, poolCreator.publicKey)),
+      300 * 10 ** 6
+    );
+    assert.equal(
+      await tokenBalance(getAssociatedTokenAddressSync(shareMintPDA, poolUser.publicKey)),
+      100 * 10 ** 6
+    );
+  });
+
+  it("waits for the members to vote a reserve price", async () => {
+    await expectError(buyout(800), "ReservePriceNotSet");
+    await voteReservePrice(500);
+
+    const vault = await program.account.fractionalVault.fetch(fractionalVaultPDA);
+    assert.equal(vault.reservePrice.toNumber(), 500 * 10 ** 6);
+  });
+
+  it("rejects a buyout below the reserve price", async () => {
+    await expectError(buyout(499), "BuyoutBelowReserve");
+  });
+
+  it("hands the NFT to the buyer", async () => {
+    await buyout(800);
+
+    assert.equal(
+      await tokenBalance(getAssociatedTokenAddressSync(nftMint, buyer.publicKey)),
+      1
+    );
+    const vault = await program.account.fractionalVault.fetch(fractionalVaultPDA);
+    assert.equal(vault.status.boughtOut.price.toNumber(), 800 * 10 ** 6);
+    await expectError(buyout(900), "NftNotInEscrow");
+  });
+
+  it("pays share holders their part of the proceeds", async () => {
+    const holderShares = getAssociatedTokenAddressSync(shareMintPDA, poolUser.publicKey);
+    const before = await tokenBalance(poolUserUsdcATA.address);
+
+    await program.methods
+      .redeemShares(new anchor.BN(100 * 10 ** 6))
+      .accountsStrict({
+        holder: poolUser.publicKey,
+        fractionalVault: fractionalVaultPDA,
+        shareMint: shareMintPDA,
+        holderShares,
+        proceeds: getAssociatedTokenAddressSync(usdcMint, fractionalVaultPDA, true),
+        holderPayment: poolUserUsdcATA.address,
+        tokenProgram,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    assert.equal((await tokenBalance(poolUserUsdcATA.address)) - before, 200 * 10 ** 6);
+    assert.equal(await tokenBalance(holderShares), 0);
+    const vault = await program.account.fractionalVault.fetch(fractionalVaultPDA);
+    assert.equal(vault.redeemedShares.toNumber(), 100 * 10 ** 6);
+  });
+
+  it("lets a full whitelist and the creator all claim", async () => {
+    const creator = anchor.web3.Keypair.generate();
+    const members = Array.from({ length: 10 }, () => anchor.web3.Keypair.generate());
+    const everyone = [creator, ...members];
+    for (const wallet of everyone) {
+      await airdropBalance(wallet.publicKey);
+    }
+    const atas: Account[] = [];
+    for (const wallet of everyone) {
+      const ata = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        wallet.publicKey // owner
+      );
+      await mintTo(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        ata.address, // ATA address
+        provider.wallet.payer.publicKey, // mint authority
+        10 * 10 ** 6 // amount in base units
+      );
+      atas.push(ata);
+    }
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    const id = counter.count.toArrayLike(Buffer, "le", 8);
+    const [event] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), id],
+      program.programId
+    );
+    const deadline = Math.floor(Date.now() / 1000) + 20;
+    await program.methods
+      .createEvent({
+        name: "Full house",
+        deadline: new anchor.BN(deadline),
+        targetAmount: new anchor.BN(110 * 10 ** 6),
+      })
+      .signers([creator])
+      .accounts({
+        creator: creator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: atas[0].address,
+      })
+      .rpc();
+    await program.methods
+      .whitelist(members.map((member) => member.publicKey))
+      .accountsStrict({ creator: creator.publicKey, event })
+      .signers([creator])
+      .rpc();
+    const vault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      event, // owner
+      true
+    );
+
+    for (const [i, wallet] of everyone.entries()) {
+      await program.methods
+        .contribute(new anchor.BN(10 * 10 ** 6))
+        .accountsStrict({
+          contributor: wallet.publicKey,
+          contributorAta: atas[i].address,
+          event,
+          eventVault: vault.address,
+          payer: wallet.publicKey,
+          mint: usdcMint,
+          participant: participantPDA(wallet.publicKey, id),
+          associatedTokenProgram,
+          systemProgram,
+          tokenProgram,
+        })
+        .signers([wallet])
+        .rpc();
+    }
+
+    const nft = await mintNft(creator.publicKey);
+    await sleep(Math.max(0, deadline * 1000 - Date.now()) + 1000);
+    const proposalId = await payForNft(
+      creator,
+      atas[0],
+      event,
+      id,
+      vault,
+      [{ wallet: creator, percentage: 100 }],
+      110
+    );
+    await fractionalize(creator, event, proposalId, nft);
+
+    const [fractional] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fractional"), event.toBuffer(), nft.toBuffer()],
+      program.programId
+    );
+    const [shareMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("shares"), fractional.toBuffer()],
+      program.programId
+    );
+    for (const wallet of everyone) {
+      await claimShares(wallet, event, id, fractional, shareMint);
+    }
+
+    const vaultAccount = await program.account.fractionalVault.fetch(fractional);
+    assert.equal(vaultAccount.claimed.length, 11);
+  });
+});
@@ -0,0 +1,453 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("event governance", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function proposalPDA(event: PublicKey, proposalId: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        event.toBuffer(),
+        new anchor.BN(proposalId).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+  }
+
+  function participantPDA(wallet: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  async function voteAll(proposal: PublicKey, choice: boolean) {
+    for (const voter of [poolCreator, poolUser]) {
+      await program.methods
+        .vote(choice)
+        .accountsStrict({
+          event: eventPDA,
+          proposal,
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: voter.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([voter])
+        .rpc();
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => Community Pool creator
+  // 2. Pool User => whitelisted member
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let poolUserUsdcATA: Account;
+  let eventPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+    await airdropBalance(poolUser.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    poolUserUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolUser.publicKey // owner
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent({
+        name: "Club Fund",
+        deadline: new anchor.BN(now + 60),
+        targetAmount: new anchor.BN(300 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+  });
+
+  it("extends the event deadline after group approval", async () => {
+    const event = await program.account.event.fetch(eventPDA);
+    const proposal = proposalPDA(eventPDA, event.proposalCount);
+    const now = Math.floor(Date.now() / 1000);
+    const newDeadline = event.deadline.add(new anchor.BN(3600));
+
+    await program.methods
+      .createGovernanceProposal(
+        { extendDeadline: { newDeadline } },
+        new anchor.BN(now + 60)
+      )
+      .accountsStrict({
+        creator: poolUser.publicKey,
+        payer: poolUser.publicKey,
+        event: eventPDA,
+        proposal,
+        newWithdrawTokenAccount: null,
+        fractionalVault: null,
+        systemProgram,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    await voteAll(proposal, true);
+
+    await program.methods
+      .settleGovernanceProposal()
+      .accountsStrict({
+        signer: poolUser.publicKey,
+        event: eventPDA,
+        proposal,
+        fractionalVault: null,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    const eventAfter = await program.account.event.fetch(eventPDA);
+    assert.equal(eventAfter.deadline.toNumber(), newDeadline.toNumber());
+  });
+
+  it("keeps the target when the change is rejected", async () => {
+    const event = await program.account.event.fetch(eventPDA);
+    const proposal = proposalPDA(eventPDA, event.proposalCount);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .createGovernanceProposal(
+        { changeTarget: { newTargetAmount: new anchor.BN(100 * 10 ** 6) } },
+        new anchor.BN(now + 60)
+      )
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal,
+        newWithdrawTokenAccount: null,
+        fractionalVault: null,
+        systemProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await voteAll(proposal, false);
+
+    await program.methods
+      .settleGovernanceProposal()
+      .accountsStrict({
+        signer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal,
+        fractionalVault: null,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    const eventAfter = await program.account.event.fetch(eventPDA);
+    const proposalAfter = await program.account.proposal.fetch(proposal);
+    assert.equal(eventAfter.targetAmount.toNumber(), 300 * 10 ** 6);
+    assert.equal(proposalAfter.cancelled, true);
+  });
+
+  it("queues a new withdraw account behind the timelock", async () => {
+    const event = await program.account.event.fetch(eventPDA);
+    const proposal = proposalPDA(eventPDA, event.proposalCount);
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .createGovernanceProposal(
+        {
+          changeWithdrawAccount: {
+            newWithdrawTokenAccount: poolUserUsdcATA.address,
+          },
+        },
+        new anchor.BN(now + 60)
+      )
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal,
+        newWithdrawTokenAccount: poolUserUsdcATA.address,
+        fractionalVault: null,
+        systemProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await voteAll(proposal, true);
+
+    await program.methods
+      .settleGovernanceProposal()
+      .accountsStrict({
+        signer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal,
+        fractionalVault: null,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    const eventAfter = await program.account.event.fetch(eventPDA);
+    assert.equal(
+      eventAfter.pendingWithdrawTokenAccount.toBase58(),
+      poolUserUsdcATA.address.toBase58()
+    );
+    assert.equal(
+      eventAfter.withdrawTokenAccount.toBase58(),
+      poolCreatorUsdcATA.address.toBase58()
+    );
+
+    try {
+      await program.methods
+        .applyWithdrawAccountChange()
+        .accountsStrict({
+          signer: poolCreator.publicKey,
+          event: eventPDA,
+          newWithdrawTokenAccount: poolUserUsdcATA.address,
+        })
+        .signers([poolCreator])
+        .rpc();
+      assert.fail("withdraw account change applied before the timelock");
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, "WithdrawChangeTimelocked");
+    }
+  });
+
+  it("hands the creator role over in two steps", async () => {
+    await program.methods
+      .proposeCreatorTransfer(poolUser.publicKey)
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    const pending = await program.account.event.fetch(eventPDA);
+    assert.equal(pending.creator.toBase58(), poolCreator.publicKey.toBase58());
+    assert.equal(
+      pending.pendingCreator.toBase58(),
+      poolUser.publicKey.toBase58()
+    );
+
+    await program.methods
+      .acceptCreatorTransfer()
+      .accountsStrict({
+        newCreator: poolUser.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    const eventAfter = await program.account.event.fetch(eventPDA);
+    assert.equal(eventAfter.creator.toBase58(), poolUser.publicKey.toBase58());
+    assert.equal(eventAfter.pendingCreator, null);
+    assert.deepStrictEqual(
+      eventAfter.whitelist.map((w) => w.toBase58()),
+      [poolCreator.publicKey.toBase58()]
+    );
+  });
+
+  it("stops observers from voting", async () => {
+    // poolUser is the creator after the transfer above
+    await program.methods
+      .setMemberRole(poolCreator.publicKey, { observer: {} })
+      .accountsStrict({
+        admin: poolUser.publicKey,
+        event: eventPDA,
+        participant: participantPDA(poolCreator.publicKey),
+      })
+      .signers([poolUser])
+      .rpc();
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.deepStrictEqual(event.roles, [{ observer: {} }]);
+
+    const proposal = proposalPDA(eventPDA, event.proposalCount);
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createGovernanceProposal(
+        { changeTarget: { newTargetAmount: new anchor.BN(200 * 10 ** 6) } },
+        new anchor.BN(now + 60)
+      )
+      .accountsStrict({
+        creator: poolUser.publicKey,
+        payer: poolUser.publicKey,
+        event: eventPDA,
+        proposal,
+        newWithdrawTokenAccount: null,
+        fractionalVault: null,
+        systemProgram,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    try {
+      await program.methods
+        .vote(true)
+        .accountsStrict({
+          event: eventPDA,
+          proposal,
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: poolCreator.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([poolCreator])
+        .rpc();
+      assert.fail("observer was able to vote");
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, "NotAuthorizedToVote");
+    }
+  });
+
+  it("keeps wallets that contributed from becoming observers", async () => {
+    const setRole = (role) =>
+      program.methods
+        .setMemberRole(poolCreator.publicKey, role)
+        .accountsStrict({
+          admin: poolUser.publicKey,
+          event: eventPDA,
+          participant: participantPDA(poolCreator.publicKey),
+        })
+        .signers([poolUser])
+        .rpc();
+
+    await setRole({ member: {} });
+
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreatorUsdcATA.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      10 * 10 ** 6 // amount in base units
+    );
+    const eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+    await program.methods
+      .contribute(new anchor.BN(10 * 10 ** 6))
+      .accountsStrict({
+        contributor: poolCreator.publicKey,
+        contributorAta: poolCreatorUsdcATA.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(poolCreator.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    try {
+      await setRole({ observer: {} });
+      assert.fail("contributor was made an observer");
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, "ContributorCannotBeObserver");
+    }
+
+    const event = await program.account.event.fetch(eventPDA);
+    assert.deepStrictEqual(event.roles, [{ member: {} }]);
+  });
+});
@@ -0,0 +1,229 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import { createMint, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+
+describe("session keys", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function sessionPDA(member: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("session"), eventPDA.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function createSession(sessionKey: PublicKey, expiresIn: number) {
+    return program.methods
+      .createSession(sessionKey, new anchor.BN(Math.floor(Date.now() / 1000) + expiresIn))
+      .accountsStrict({
+        member: poolUser.publicKey,
+        payer: poolUser.publicKey,
+        event: eventPDA,
+        session: sessionPDA(poolUser.publicKey),
+        systemProgram,
+      })
+      .signers([poolUser])
+      .rpc();
+  }
+
+  // vote of the pool user, signed by `signer` only
+  function sessionVote(signer: anchor.web3.Keypair, session: PublicKey | null) {
+    return program.methods
+      .vote(true)
+      .accountsStrict({
+        event: eventPDA,
+        proposal: proposalPDA,
+        systemProgram,
+        tokenProgram,
+        associatedTokenProgram,
+        mint: usdcMint,
+        voter: poolUser.publicKey,
+        sessionKey: signer.publicKey,
+        session,
+      })
+      .signers([signer])
+      .rpc();
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => proposes a change
+  // 2. Pool User => votes through a session key instead of their wallet
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+  const sessionKey = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let eventPDA: PublicKey;
+  let proposalPDA: PublicKey;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    for (const wallet of [poolCreator, poolUser, sessionKey]) {
+      await airdropBalance(wallet.publicKey);
+    }
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+    const poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), counter.count.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [proposalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent({
+        name: "Flat share",
+        deadline: new anchor.BN(now + 3600),
+        targetAmount: new anchor.BN(300 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await program.methods
+      .createGovernanceProposal(
+        { changeTarget: { newTargetAmount: new anchor.BN(200 * 10 ** 6) } },
+        new anchor.BN(now + 600)
+      )
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal: proposalPDA,
+        newWithdrawTokenAccount: null,
+        fractionalVault: null,
+        systemProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+  });
+
+  it("rejects sessions lasting more than a week", async () => {
+    await expectError(
+      createSession(sessionKey.publicKey, 8 * 24 * 60 * 60),
+      "InvalidSessionExpiry"
+    );
+  });
+
+  it("authorizes a session key for the event", async () => {
+    await createSession(sessionKey.publicKey, 3600);
+
+    const session = await program.account.session.fetch(sessionPDA(poolUser.publicKey));
+    assert.equal(session.member.toBase58(), poolUser.publicKey.toBase58());
+    assert.equal(session.sessionKey.toBase58(), sessionKey.publicKey.toBase58());
+  });
+
+  it("rejects votes signed by neither the member nor their session key", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await expectError(sessionVote(stranger, sessionPDA(poolUser.publicKey)), "InvalidSession");
+    await expectError(sessionVote(stranger, null), "InvalidSession");
+  });
+
+  it("votes for the member with the session key", async () => {
+    await sessionVote(sessionKey, sessionPDA(poolUser.publicKey));
+
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.deepEqual(
+      proposal.yesVotes.map((v) => v.toBase58()),
+      [poolUser.publicKey.toBase58()]
+    );
+  });
+
+  it("closes the session when revoked", async () => {
+    const revoke = (rentPayer: PublicKey) =>
+      program.methods
+        .revokeSession()
+        .accountsStrict({
+          member: poolUser.publicKey,
+          session: sessionPDA(poolUser.publicKey),
+          rentPayer,
+        })
+        .signers([poolUser])
+        .rpc();
+    // the member paid the rent, nobody else gets it
+    await expectError(revoke(poolCreator.publicKey), "InvalidRentPayer");
+    await revoke(poolUser.publicKey);
+
+    const account = await provider.connection.getAccountInfo(sessionPDA(poolUser.publicKey));
+    assert.isNull(account);
+  });
+});
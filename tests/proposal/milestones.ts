@@ -0,0 +1,332 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("milestone payouts", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function participantPDA(wallet: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  const participants = () =>
+    [poolUser, poolCreator].map((wallet) => ({
+      pubkey: participantPDA(wallet.publicKey),
+      isWritable: true,
+      isSigner: false,
+    }));
+
+  async function contribute(wallet: anchor.web3.Keypair, ata: Account, amount: number) {
+    await program.methods
+      .contribute(new anchor.BN(amount * 10 ** 6))
+      .accountsStrict({
+        contributor: wallet.publicKey,
+        contributorAta: ata.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: wallet.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(wallet.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([wallet])
+      .rpc();
+  }
+
+  function releaseMilestone(signer: anchor.web3.Keypair) {
+    return program.methods
+      .releaseMilestone()
+      .accountsStrict({
+        signer: signer.publicKey,
+        event: eventPDA,
+        mint: usdcMint,
+        eventVault: eventVault.address,
+        proposal: proposalPDA,
+        withdrawAccount: poolCreatorUsdcATA.address,
+        tokenProgram,
+      })
+      .remainingAccounts(participants())
+      .signers([signer])
+      .rpc();
+  }
+
+  async function vaultBalance() {
+    const vault = await getAccount(provider.connection, eventVault.address);
+    return Number(vault.amount) / 10 ** 6;
+  }
+
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+      assert.fail(`expected ${code}`);
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, code);
+    }
+  }
+
+  // Actors
+  // 1. Pool Creator => renovation fund creator, receives the tranches
+  // 2. Pool User => flatmate approving each tranche
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let poolUserUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let proposalPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+    await airdropBalance(poolUser.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+    poolUserUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolUser.publicKey // owner
+    );
+
+    for (const ata of [poolCreatorUsdcATA, poolUserUsdcATA]) {
+      await mintTo(
+        provider.connection,
+        provider.wallet.payer, // fee payer
+        usdcMint, // mint
+        ata.address, // ATA address
+        provider.wallet.payer.publicKey, // mint authority
+        1000 * 10 ** 6 // amount in base units
+      );
+    }
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    await program.methods
+      .createEvent({
+        name: "Renovation",
+        deadline: new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+        targetAmount: new anchor.BN(600 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+
+    await contribute(poolCreator, poolCreatorUsdcATA, 300);
+    await contribute(poolUser, poolUserUsdcATA, 300);
+  });
+
+  it("creates a proposal paid in two tranches", async () => {
+    const event = await program.account.event.fetch(eventPDA);
+    [proposalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(event.proposalCount).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createMilestoneProposal(
+        "Kitchen",
+        [new anchor.BN(100 * 10 ** 6), new anchor.BN(200 * 10 ** 6)],
+        { approval: {} },
+        [
+          { wallet: poolCreator.publicKey, percentage: 50 },
+          { wallet: poolUser.publicKey, percentage: 50 },
+        ],
+        new anchor.BN(Math.floor(Date.now() / 1000) + 600)
+      )
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        proposal: proposalPDA,
+        systemProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.equal(proposal.amount.toNumber(), 300 * 10 ** 6);
+    assert.equal(proposal.milestones.length, 2);
+  });
+
+  it("approves the proposal without paying anything yet", async () => {
+    for (const voter of [poolCreator, poolUser]) {
+      await program.methods
+        .vote(true)
+        .accountsStrict({
+          event: eventPDA,
+          proposal: proposalPDA,
+          systemProgram,
+          tokenProgram,
+          associatedTokenProgram,
+          mint: usdcMint,
+          voter: voter.publicKey,
+          sessionKey: null,
+          session: null,
+        })
+        .signers([voter])
+        .rpc();
+    }
+
+    await program.methods
+      .settleProposal()
+      .accountsStrict({
+        associatedTokenProgram,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        mint: usdcMint,
+        proposal: proposalPDA,
+        signer: poolCreator.publicKey,
+        systemProgram,
+        tokenProgram,
+        withdrawAccount: poolCreatorUsdcATA.address,
+      })
+      .remainingAccounts(participants())
+      .signers([poolCreator])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.isTrue(proposal.settled);
+    assert.equal(await vaultBalance(), 600);
+  });
+
+  it("waits for the tranche to be approved", async () => {
+    await expectError(releaseMilestone(poolCreator), "MilestoneNotApproved");
+  });
+
+  it("releases an approved tranche", async () => {
+    await program.methods
+      .approveMilestone()
+      .accountsStrict({
+        approver: poolUser.publicKey,
+        event: eventPDA,
+        proposal: proposalPDA,
+      })
+      .signers([poolUser])
+      .rpc();
+
+    await releaseMilestone(poolCreator);
+
+    assert.equal(await vaultBalance(), 500);
+    const participant = await program.account.participant.fetch(
+      participantPDA(poolUser.publicKey)
+    );
+    assert.equal(participant.spent.toNumber(), 50 * 10 ** 6);
+    const proposal = await program.account.proposal.fetch(proposalPDA);
+    assert.isTrue(proposal.milestones[0].released);
+    assert.equal(proposal.milestoneApprovals.length, 0);
+  });
+
+  it("keeps the remaining tranche in the pool once cancelled", async () => {
+    const cancel = (admin: anchor.web3.Keypair) =>
+      program.methods
+        .cancelMilestones()
+        .accountsStrict({
+          admin: admin.publicKey,
+          event: eventPDA,
+          proposal: proposalPDA,
+        })
+        .signers([admin])
+        .rpc();
+
+    await expectError(cancel(poolUser), "Unauthorised");
+    await cancel(poolCreator);
+
+    await expectError(releaseMilestone(poolCreator), "MilestonesNotApproved");
+    assert.equal(await vaultBalance(), 500);
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.totalSpent.toNumber(), 100 * 10 ** 6);
+  });
+});
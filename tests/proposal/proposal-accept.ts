@@ -241,6 +241,7 @@ describe("proposal flow", () => {
         contributorAta: poolUserUsdcATA.address,
         event: eventPDA,
         eventVault: eventVault.address,
+        payer: poolUser.publicKey,
         mint: usdcMint,
         participant: userParticipantPDA,
         associatedTokenProgram,
@@ -257,6 +258,7 @@ describe("proposal flow", () => {
         contributorAta: poolCreatorUsdcATA.address,
         event: eventPDA,
         eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
         mint: usdcMint,
         participant: poolCreatorParticipantPDA,
         associatedTokenProgram,
@@ -273,6 +275,7 @@ describe("proposal flow", () => {
         contributorAta: poolUserBUsdcATA.address,
         event: eventPDA,
         eventVault: eventVault.address,
+        payer: poolUserB.publicKey,
         mint: usdcMint,
         participant: userBParticipantPDA,
         associatedTokenProgram,
@@ -319,6 +322,7 @@ describe("proposal flow", () => {
       )
       .accountsStrict({
         creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
         event: eventPDA,
         mint: usdcMint,
         proposal: proposalPDA,
@@ -366,6 +370,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUser.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUser])
       .rpc();
@@ -398,6 +404,8 @@ describe("proposal flow", () => {
           associatedTokenProgram,
           mint: usdcMint,
           voter: poolUser.publicKey,
+          sessionKey: null,
+          session: null,
         })
         .signers([poolUser])
         .rpc();
@@ -416,11 +424,60 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUserB.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUserB])
       .rpc();
   });
 
+  it("rejects settling with the same participant passed twice", async () => {
+    const [proposalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    );
+    const eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      usdcMint,
+      eventPDA,
+      true
+    );
+
+    try {
+      await program.methods
+        .settleProposal()
+        .accountsStrict({
+          associatedTokenProgram,
+          event: eventPDA,
+          eventVault: eventVault.address,
+          mint: usdcMint,
+          proposal: proposalPDA,
+          signer: poolCreator.publicKey,
+          systemProgram,
+          tokenProgram,
+          withdrawAccount: poolCreatorUsdcATA.address,
+        })
+        .remainingAccounts(
+          [userParticipantPDA, userParticipantPDA].map((pda) => ({
+            pubkey: pda,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers([poolCreator])
+        .rpc();
+      assert.fail("settled with a duplicate participant");
+    } catch (err) {
+      const anchorError = err.error || err;
+      assert.equal(anchorError.errorCode.code, "InvalidParticipantAccounts");
+    }
+  });
+
   it("settles proposal after voting", async () => {
     const [proposalPDA] = anchor.web3.PublicKey.findProgramAddressSync(
       [
@@ -466,6 +523,9 @@ describe("proposal flow", () => {
     );
     assert.equal(vaultBalance.value.uiAmount, 300);
 
+    const event = await program.account.event.fetch(eventPDA);
+    assert.equal(event.totalSpent.toNumber(), 200 * 10 ** 6);
+
     const participant = await program.account.participant.fetch(
       userParticipantPDA
     );
@@ -524,6 +584,7 @@ describe("proposal flow", () => {
       )
       .accountsStrict({
         creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
         event: eventPDA,
         mint: usdcMint,
         proposal: proposalPDA2,
@@ -548,6 +609,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUser.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUser])
       .rpc();
@@ -563,6 +626,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUserB.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUserB])
       .rpc();
@@ -631,6 +696,7 @@ describe("proposal flow", () => {
       .createSettleProposal(deadline)
       .accountsStrict({
         creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
         event: eventPDA,
         mint: usdcMint,
         proposal: settleProposalPDA,
@@ -665,6 +731,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUser.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUser])
       .rpc();
@@ -679,6 +747,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolUserB.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolUserB])
       .rpc();
@@ -693,6 +763,8 @@ describe("proposal flow", () => {
         associatedTokenProgram,
         mint: usdcMint,
         voter: poolCreator.publicKey,
+        sessionKey: null,
+        session: null,
       })
       .signers([poolCreator])
       .rpc();
@@ -0,0 +1,230 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Payra } from "../../target/types/payra";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Account,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("event views", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.payra as Program<Payra>;
+
+  // helpers
+  async function airdropBalance(wallet: PublicKey) {
+    const cluster = provider.connection.rpcEndpoint;
+    const isDevnet = cluster.includes('devnet');
+
+    if (isDevnet) {
+      // Transfer SOL from payer to wallet on devnet
+      const transferAmount = LAMPORTS_PER_SOL / 4;
+      const transferInstruction = SystemProgram.transfer({
+        fromPubkey: provider.wallet.payer.publicKey,
+        toPubkey: wallet,
+        lamports: transferAmount,
+      });
+
+      const transaction = new Transaction().add(transferInstruction);
+      await provider.sendAndConfirm(transaction);
+    } else {
+      // Use airdrop for localnet
+      await provider.connection.requestAirdrop(wallet, 2 * LAMPORTS_PER_SOL);
+    }
+  }
+
+  function participantPDA(wallet: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("participant"), eventId, wallet.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function proposalPDA(proposalId: number) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        eventPDA.toBuffer(),
+        new anchor.BN(proposalId).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId
+    )[0];
+  }
+
+  // Actors
+  // 1. Pool Creator => Community Pool creator, contributes
+  // 2. Pool User => whitelisted member, never contributes
+  const poolCreator = anchor.web3.Keypair.generate();
+  const poolUser = anchor.web3.Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let poolCreatorUsdcATA: Account;
+  let eventVault: Account;
+  let eventPDA: PublicKey;
+  let eventId: Buffer;
+
+  const systemProgram = anchor.web3.SystemProgram.programId;
+  const associatedTokenProgram = anchor.utils.token.ASSOCIATED_PROGRAM_ID;
+  const tokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID;
+
+  const [eventCounterPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("event_counter")],
+    program.programId
+  );
+
+  before(async () => {
+    await airdropBalance(poolCreator.publicKey);
+
+    usdcMint = await createMint(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      provider.wallet.payer.publicKey, // mint authority
+      null, // freeze authority (none)
+      6 // decimals
+    );
+
+    poolCreatorUsdcATA = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreator.publicKey // owner
+    );
+
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      poolCreatorUsdcATA.address, // ATA address
+      provider.wallet.payer.publicKey, // mint authority
+      100 * 10 ** 6 // amount in base units
+    );
+
+    const counter = await program.account.eventCounter.fetch(eventCounterPDA);
+    eventId = counter.count.toArrayLike(Buffer, "le", 8);
+    [eventPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("event"), eventId],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createEvent({
+        name: "Dinner",
+        deadline: new anchor.BN(now + 60),
+        targetAmount: new anchor.BN(50 * 10 ** 6),
+      })
+      .signers([poolCreator])
+      .accounts({
+        creator: poolCreator.publicKey,
+        mint: usdcMint,
+        withdrawTokenAccount: poolCreatorUsdcATA.address,
+      })
+      .rpc();
+
+    await program.methods
+      .whitelist([poolUser.publicKey])
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        event: eventPDA,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    eventVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer, // fee payer
+      usdcMint, // mint
+      eventPDA, // owner
+      true
+    );
+
+    await program.methods
+      .contribute(new anchor.BN(20 * 10 ** 6))
+      .accountsStrict({
+        contributor: poolCreator.publicKey,
+        contributorAta: poolCreatorUsdcATA.address,
+        event: eventPDA,
+        eventVault: eventVault.address,
+        payer: poolCreator.publicKey,
+        mint: usdcMint,
+        participant: participantPDA(poolCreator.publicKey),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    await program.methods
+      .createSettleProposal(new anchor.BN(now + 60))
+      .accountsStrict({
+        creator: poolCreator.publicKey,
+        payer: poolCreator.publicKey,
+        event: eventPDA,
+        mint: usdcMint,
+        proposal: proposalPDA(0),
+        associatedTokenProgram,
+        systemProgram,
+        tokenProgram,
+      })
+      .signers([poolCreator])
+      .rpc();
+  });
+
+  it("summarises the event and its open proposals", async () => {
+    const summary = await program.methods
+      .getEventSummary()
+      .accountsStrict({ event: eventPDA })
+      .remainingAccounts([
+        { pubkey: proposalPDA(0), isSigner: false, isWritable: false },
+      ])
+      .view();
+
+    assert.equal(summary.name, "Dinner");
+    assert.equal(summary.totalContributed.toNumber(), 20 * 10 ** 6);
+    assert.equal(summary.available.toNumber(), 20 * 10 ** 6);
+    assert.equal(summary.targetReached, false);
+    assert.equal(summary.members, 1);
+    assert.equal(summary.voters, 2);
+    assert.deepStrictEqual(summary.openProposals, [0]);
+  });
+
+  it("previews the settlement of every contributor", async () => {
+    const preview = await program.methods
+      .previewSettlement()
+      .accountsStrict({ event: eventPDA })
+      .remainingAccounts([
+        {
+          pubkey: participantPDA(poolCreator.publicKey),
+          isSigner: false,
+          isWritable: false,
+        },
+      ])
+      .view();
+
+    const balances = Object.fromEntries(
+      preview.balances.map((b) => [b.wallet.toBase58(), b.netOwed.toNumber()])
+    );
+    assert.equal(balances[poolCreator.publicKey.toBase58()], 20 * 10 ** 6);
+    // never contributed, nothing to settle
+    assert.notProperty(balances, poolUser.publicKey.toBase58());
+  });
+
+  it("returns the balance of a participant", async () => {
+    const balance = await program.methods
+      .getParticipantBalance()
+      .accountsStrict({
+        event: eventPDA,
+        participant: participantPDA(poolCreator.publicKey),
+      })
+      .view();
+
+    assert.equal(balance.wallet.toBase58(), poolCreator.publicKey.toBase58());
+    assert.equal(balance.contributed.toNumber(), 20 * 10 ** 6);
+    assert.equal(balance.netOwed.toNumber(), 20 * 10 ** 6);
+  });
+});